@@ -1,6 +1,6 @@
 //! Provides the `glimpse-cli` tool for rendering 3D model thumbnails.
 //!
-//! Usage: `glimpse-cli <model_file> [size]`
+//! Usage: `glimpse-cli [--auto-view] [--max-triangles <n>] [--timeout <ms>] [--animation <name>] [--time <s>] [--camera <name> | --first-camera] [--scene <index|name>] [--variant <name>] [--list] [--report] <model_file> [size]`
 //!
 //! Renders a PNG thumbnail next to the input file.
 //! Supports glTF/GLB, Blockbench (.bbmodel), and Vintage Story (.json).
@@ -8,41 +8,159 @@
 //! # Examples
 //! ```text
 //! glimpse-cli model.gltf 256
+//! glimpse-cli --auto-view model.obj
+//! glimpse-cli --max-triangles 50000 scan.glb 512
+//! glimpse-cli --timeout 2000 huge.obj
+//! glimpse-cli --report model.glb
+//! glimpse-cli --animation Walk --time 0.5 character.glb
+//! glimpse-cli --first-camera scene.gltf
+//! glimpse-cli --list configurator.glb
+//! glimpse-cli --scene Showroom --variant Midnight configurator.glb
 //! ```
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::Duration;
+
+use glimpse::budget::Budget;
+use glimpse::formats::{AnimationPose, SceneChoice};
+use glimpse::renderer::{CameraChoice, RenderOptions};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
-    if args.len() < 2 {
-        eprintln!("Usage: {} <model_file> [size]", args[0]);
+    let mut options = RenderOptions::default();
+    let mut print_report = false;
+    let mut list = false;
+    let mut positional = Vec::new();
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--auto-view" => options.auto_view = true,
+            "--report" => print_report = true,
+            "--list" => list = true,
+            "--max-triangles" => {
+                let Some(budget) = iter.next().and_then(|s| s.parse::<usize>().ok()) else {
+                    eprintln!("Error: --max-triangles expects a number");
+                    process::exit(1);
+                };
+                // 0 disables simplification entirely
+                options.triangle_budget = (budget > 0).then_some(budget);
+            }
+            "--timeout" => {
+                let Some(ms) = iter.next().and_then(|s| s.parse::<u64>().ok()) else {
+                    eprintln!("Error: --timeout expects a number of milliseconds");
+                    process::exit(1);
+                };
+                options.budget = Budget::new().with_timeout(Duration::from_millis(ms));
+            }
+            "--animation" => {
+                let Some(name) = iter.next() else {
+                    eprintln!("Error: --animation expects an animation name");
+                    process::exit(1);
+                };
+                options
+                    .animation
+                    .get_or_insert_with(AnimationPose::default)
+                    .name = Some(name.clone());
+            }
+            "--time" => {
+                let Some(time) = iter.next().and_then(|s| s.parse::<f32>().ok()) else {
+                    eprintln!("Error: --time expects a number of seconds");
+                    process::exit(1);
+                };
+                options
+                    .animation
+                    .get_or_insert_with(AnimationPose::default)
+                    .time = time;
+            }
+            "--camera" => {
+                let Some(name) = iter.next() else {
+                    eprintln!("Error: --camera expects a camera name");
+                    process::exit(1);
+                };
+                options.camera = Some(CameraChoice::Named(name.clone()));
+            }
+            "--first-camera" => options.camera = Some(CameraChoice::First),
+            "--scene" => {
+                let Some(scene) = iter.next() else {
+                    eprintln!("Error: --scene expects a scene index or name");
+                    process::exit(1);
+                };
+                options.scene = Some(match scene.parse::<usize>() {
+                    Ok(index) => SceneChoice::Index(index),
+                    Err(_) => SceneChoice::Named(scene.clone()),
+                });
+            }
+            "--variant" => {
+                let Some(name) = iter.next() else {
+                    eprintln!("Error: --variant expects a variant name");
+                    process::exit(1);
+                };
+                options.variant = Some(name.clone());
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    if positional.is_empty() {
+        eprintln!(
+            "Usage: {} [--auto-view] [--max-triangles <n>] [--timeout <ms>] [--animation <name>] [--time <s>] [--camera <name> | --first-camera] [--scene <index|name>] [--variant <name>] [--list] [--report] <model_file> [size]",
+            args[0]
+        );
         eprintln!("  Renders a PNG thumbnail next to the input file.");
         eprintln!("  Default size: 256");
+        eprintln!("  --auto-view          pick the most informative camera angle");
+        eprintln!(
+            "  --max-triangles <n>  simplify larger models first (default {}, 0 = off)",
+            glimpse::renderer::DEFAULT_TRIANGLE_BUDGET
+        );
+        eprintln!("  --timeout <ms>       give up if loading and rendering take longer");
+        eprintln!("  --animation <name>   pose glTF models with this animation");
+        eprintln!("  --time <s>           animation time in seconds (default 0)");
+        eprintln!("  --camera <name>      render from this glTF camera");
+        eprintln!("  --first-camera       render from the first glTF camera");
+        eprintln!("  --scene <index|name> render this glTF scene instead of the default one");
+        eprintln!("  --variant <name>     apply this glTF material variant");
+        eprintln!("  --list               list the glTF scenes and material variants, then exit");
+        eprintln!("  --report             print render statistics and stage timings");
         process::exit(1);
     }
 
-    let input = PathBuf::from(&args[1]);
-    let size: u32 = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(256);
+    let input = PathBuf::from(positional[0]);
+    let size: u32 = positional
+        .get(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(256);
 
     if !input.exists() {
         eprintln!("Error: file not found: {}", input.display());
         process::exit(1);
     }
 
+    if list {
+        list_contents(&input);
+        return;
+    }
+
     let output = input.with_extension("png");
 
     eprintln!("Rendering {} ({}x{})...", input.display(), size, size);
 
-    let pixels = match glimpse::renderer::render_thumbnail_from_path(&input, size, size) {
-        Some(p) => p,
-        None => {
-            eprintln!("Error: failed to render (unsupported format or no geometry)");
+    let (pixels, report) = match glimpse::renderer::render_thumbnail_from_path_with_report(
+        &input, size, size, &options,
+    ) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Error: failed to render: {}", e);
             process::exit(1);
         }
     };
 
+    if print_report {
+        println!("{}", report);
+    }
+
     // Encode RGBA pixels to PNG
     use image::{ImageBuffer, Rgba};
     let img: ImageBuffer<Rgba<u8>, _> =
@@ -55,3 +173,39 @@ fn main() {
 
     eprintln!("Saved {}", output.display());
 }
+
+/// Prints the scenes and material variants of a glTF file.
+fn list_contents(input: &Path) {
+    let contents = std::fs::read(input)
+        .map_err(|e| e.to_string())
+        .and_then(|data| glimpse::formats::gltf::contents(&data).map_err(|e| e.to_string()));
+    let contents = match contents {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Error: failed to read glTF contents: {}", e);
+            process::exit(1);
+        }
+    };
+
+    println!("Scenes:");
+    for (index, name) in contents.scenes.iter().enumerate() {
+        let default = if contents.default_scene == Some(index) {
+            " (default)"
+        } else {
+            ""
+        };
+        println!(
+            "  {}: {}{}",
+            index,
+            name.as_deref().unwrap_or("<unnamed>"),
+            default
+        );
+    }
+    println!("Variants:");
+    if contents.variants.is_empty() {
+        println!("  <none>");
+    }
+    for name in &contents.variants {
+        println!("  {}", name);
+    }
+}
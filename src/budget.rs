@@ -0,0 +1,248 @@
+//! Provides cancellation and time budgets for loading and rendering.
+//!
+//! Explorer may hand glimpse pathological files, and a thumbnail worker must
+//! never hang forever. A [`Budget`] combines an optional [`CancellationToken`]
+//! with an optional deadline; loaders and the rasterizer poll it periodically
+//! and stop with an [`Interrupted`] error once it runs out.
+//!
+//! # Examples
+//! ```
+//! use std::time::Duration;
+//!
+//! use glimpse::budget::{Budget, CancellationToken};
+//!
+//! let token = CancellationToken::new();
+//! let budget = Budget::new()
+//!     .with_token(token.clone())
+//!     .with_timeout(Duration::from_secs(5));
+//! assert!(budget.check().is_ok());
+//!
+//! token.cancel();
+//! assert!(budget.check().is_err());
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A cloneable flag used to request cancellation from another thread.
+///
+/// # Examples
+/// ```
+/// use glimpse::budget::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let handle = token.clone();
+/// handle.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that has not been cancelled.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::budget::CancellationToken;
+    ///
+    /// assert!(!CancellationToken::new().is_cancelled());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation. All clones of this token observe the request.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::budget::CancellationToken;
+    ///
+    /// let token = CancellationToken::new();
+    /// token.cancel();
+    /// assert!(token.is_cancelled());
+    /// ```
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether cancellation has been requested.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::budget::CancellationToken;
+    ///
+    /// let token = CancellationToken::new();
+    /// assert!(!token.is_cancelled());
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Describes why work stopped before completing.
+///
+/// # Examples
+/// ```
+/// use glimpse::budget::Interrupted;
+///
+/// assert_eq!(format!("{}", Interrupted::TimedOut), "Time budget exceeded");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interrupted {
+    /// The cancellation token was triggered.
+    Cancelled,
+    /// The deadline passed.
+    TimedOut,
+}
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interrupted::Cancelled => write!(f, "Cancelled"),
+            Interrupted::TimedOut => write!(f, "Time budget exceeded"),
+        }
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Limits how long loading and rendering may run.
+///
+/// The default budget is unlimited.
+///
+/// # Examples
+/// ```
+/// use glimpse::budget::Budget;
+///
+/// let budget = Budget::default();
+/// assert!(budget.check().is_ok());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    /// Optional token that aborts work when cancelled.
+    pub token: Option<CancellationToken>,
+    /// Optional point in time after which work times out.
+    pub deadline: Option<Instant>,
+}
+
+impl Budget {
+    /// Creates an unlimited budget.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::budget::Budget;
+    ///
+    /// assert!(Budget::new().is_unlimited());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attaches a cancellation token.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::budget::{Budget, CancellationToken};
+    ///
+    /// let budget = Budget::new().with_token(CancellationToken::new());
+    /// assert!(!budget.is_unlimited());
+    /// ```
+    pub fn with_token(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Sets a deadline `timeout` from now.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use glimpse::budget::Budget;
+    ///
+    /// let budget = Budget::new().with_timeout(Duration::from_millis(500));
+    /// assert!(budget.deadline.is_some());
+    /// ```
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Sets an absolute deadline.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Instant;
+    ///
+    /// use glimpse::budget::{Budget, Interrupted};
+    ///
+    /// let budget = Budget::new().with_deadline(Instant::now());
+    /// assert_eq!(budget.check(), Err(Interrupted::TimedOut));
+    /// ```
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Returns whether neither a token nor a deadline is set.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::budget::Budget;
+    ///
+    /// assert!(Budget::default().is_unlimited());
+    /// ```
+    pub fn is_unlimited(&self) -> bool {
+        self.token.is_none() && self.deadline.is_none()
+    }
+
+    /// Checks whether work may continue.
+    ///
+    /// # Errors
+    /// Returns [`Interrupted::Cancelled`] if the token was cancelled, or
+    /// [`Interrupted::TimedOut`] if the deadline has passed.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::budget::Budget;
+    ///
+    /// assert!(Budget::new().check().is_ok());
+    /// ```
+    pub fn check(&self) -> Result<(), Interrupted> {
+        if self.token.as_ref().is_some_and(|t| t.is_cancelled()) {
+            return Err(Interrupted::Cancelled);
+        }
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(Interrupted::TimedOut);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_budget_never_interrupts() {
+        let budget = Budget::new();
+        assert!(budget.is_unlimited());
+        assert_eq!(budget.check(), Ok(()));
+    }
+
+    #[test]
+    fn test_cancellation_takes_priority_over_deadline() {
+        let token = CancellationToken::new();
+        let budget = Budget::new()
+            .with_token(token.clone())
+            .with_deadline(Instant::now());
+        token.cancel();
+        assert_eq!(budget.check(), Err(Interrupted::Cancelled));
+    }
+
+    #[test]
+    fn test_expired_deadline_times_out() {
+        let budget = Budget::new().with_timeout(Duration::ZERO);
+        assert_eq!(budget.check(), Err(Interrupted::TimedOut));
+    }
+}
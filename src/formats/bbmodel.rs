@@ -2,6 +2,8 @@
 //!
 //! Blockbench is a popular free 3D modeling tool for creating Minecraft-style
 //! block models. The .bbmodel format is JSON-based with embedded textures.
+//! Elements are cubes given by `from`/`to` corners, or free-form meshes whose
+//! polygonal faces carry per-vertex UVs.
 //!
 //! # Examples
 //! ```
@@ -11,19 +13,24 @@
 //! assert!(loader.extensions().contains(&"bbmodel"));
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::sync::Arc;
 
+use glam::{Mat4, Vec3 as GlamVec3};
 use serde::Deserialize;
 
-use super::shared::cube::{
-    apply_uv_rotation, compute_cube_vertices, quad_to_triangles, scale_vec3, BLOCK_SCALE,
-};
+use super::shared::cube::{apply_uv_rotation, compute_cube_vertices, scale_vec3, BLOCK_SCALE};
 use super::shared::json::{json_str_or_none, parse_vec3};
-use super::shared::rotation::{rotate_vertices, RotationOrder, RotationTransform};
+use super::shared::mesh::ModelBuilder;
+use super::shared::rotation::{
+    pivot_node_transform, rotate_model_y_180, RotationOrder, RotationTransform,
+};
 use super::shared::texture::load_texture_from_data_url;
-use super::{FormatLoader, LoadError, LoadResult, ModelData, TextureData, Triangle};
+use super::shared::triangulate::triangulate;
+use super::{
+    FormatLoader, LoadError, LoadOptions, LoadResult, Material, RenderMode, TextureData, Vec3,
+};
 
 /// The Blockbench format loader.
 ///
@@ -64,19 +71,25 @@ impl FormatLoader for BbmodelLoader {
     }
 
     fn load_from_bytes(&self, data: &[u8]) -> LoadResult {
+        self.load_from_bytes_with_options(data, &LoadOptions::default())
+    }
+
+    fn load_from_path(&self, path: &Path) -> LoadResult {
+        let data = std::fs::read(path)?;
+        self.load_from_bytes(&data)
+    }
+
+    fn load_from_bytes_with_options(&self, data: &[u8], options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
         let text = std::str::from_utf8(data)
             .map_err(|_| LoadError::InvalidData("Invalid UTF-8 in bbmodel file".to_string()))?;
 
         // Use json5 for more lenient parsing
         let model: BbmodelFile = json5::from_str(text)
             .map_err(|e| LoadError::InvalidData(format!("Failed to parse bbmodel: {}", e)))?;
+        options.mark_parsed();
 
-        convert_bbmodel_to_triangles(model)
-    }
-
-    fn load_from_path(&self, path: &Path) -> LoadResult {
-        let data = std::fs::read(path)?;
-        self.load_from_bytes(&data)
+        convert_bbmodel_to_meshes(model)
     }
 }
 
@@ -157,6 +170,12 @@ struct BbmodelTexture {
     uv_width: Option<u32>,
     #[serde(default)]
     uv_height: Option<u32>,
+    /// One of "default", "emissive", "additive" or "layered".
+    #[serde(default)]
+    render_mode: Option<String>,
+    /// One of "auto", "front" or "double".
+    #[serde(default)]
+    render_sides: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -164,6 +183,9 @@ struct BbmodelTexture {
 struct BbmodelElement {
     #[serde(default)]
     name: Option<String>,
+    /// "cube" (the default) or "mesh".
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
     #[serde(default)]
     from: [f32; 3],
     #[serde(default)]
@@ -178,6 +200,9 @@ struct BbmodelElement {
     uuid: serde_json::Value,
     #[serde(default)]
     color: Option<serde_json::Value>, // Can be integer color index
+    /// Mesh vertex positions by key, relative to `origin`.
+    #[serde(default)]
+    vertices: HashMap<String, [f32; 3]>,
 }
 
 #[derive(Deserialize, Default)]
@@ -188,6 +213,9 @@ struct BbmodelFaces {
     west: Option<BbmodelFace>,
     up: Option<BbmodelFace>,
     down: Option<BbmodelFace>,
+    /// Mesh faces, keyed by face ID; parsed leniently as `BbmodelMeshFace`.
+    #[serde(flatten)]
+    polygons: BTreeMap<String, serde_json::Value>,
 }
 
 /// Blockbench face vertex indices (maps Blockbench vertex order to internal order)
@@ -231,6 +259,19 @@ struct BbmodelFace {
     mirror_v: bool, // Flip texture vertically
 }
 
+/// A polygonal face of a mesh element.
+#[derive(Deserialize)]
+struct BbmodelMeshFace {
+    /// Per-corner UVs in pixel coordinates, keyed like `vertices`.
+    #[serde(default)]
+    uv: HashMap<String, [f32; 2]>,
+    /// Keys of the face's corners in the element's vertex map.
+    #[serde(default)]
+    vertices: Vec<String>,
+    #[serde(default)]
+    texture: Option<serde_json::Value>,
+}
+
 /// Parses rotation from either [x,y,z] array or {angle, axis} object.
 fn parse_element_rotation(value: &serde_json::Value) -> Option<([f32; 3], Option<[f32; 3]>)> {
     match value {
@@ -422,15 +463,154 @@ fn build_element_parent_rotation_map(
     element_parent_rotations
 }
 
-/// Converts a Blockbench model to triangles.
-fn convert_bbmodel_to_triangles(model: BbmodelFile) -> LoadResult {
-    let mut triangles = Vec::new();
+/// Lookup tables used while mirroring the outliner into scene nodes.
+struct OutlinerIndex<'a> {
+    groups: HashMap<&'a str, &'a BbmodelGroup>,
+    elements: HashMap<&'a str, &'a BbmodelElement>,
+    order: RotationOrder,
+}
+
+/// Returns the pivot rotation of an element, in unscaled Blockbench units.
+fn element_rotation_transform(
+    element: &BbmodelElement,
+    order: RotationOrder,
+) -> Option<RotationTransform> {
+    let (angles, rot_origin) = parse_element_rotation(element.rotation.as_ref()?)?;
+    RotationTransform::new_if_non_zero_with_order(
+        rot_origin.or(element.origin).unwrap_or([0.0; 3]),
+        angles,
+        order,
+    )
+}
+
+/// Adds a scene node for an element, unless it already has one.
+fn add_element_node(
+    element: &BbmodelElement,
+    uuid: &str,
+    index: &OutlinerIndex,
+    parent: Option<usize>,
+    builder: &mut ModelBuilder,
+    element_nodes: &mut HashMap<String, usize>,
+) {
+    if element_nodes.contains_key(uuid) {
+        return;
+    }
+    let transform = pivot_node_transform(
+        element_rotation_transform(element, index.order),
+        BLOCK_SCALE,
+    );
+    let node = builder.add_node(element.name.clone(), transform, parent);
+    element_nodes.insert(uuid.to_string(), node);
+}
+
+/// Mirrors an outliner entry into scene nodes: groups become parent nodes and
+/// elements become leaf nodes, recorded by UUID in `element_nodes`.
+fn collect_outliner_nodes(
+    node: &serde_json::Value,
+    index: &OutlinerIndex,
+    parent: Option<usize>,
+    builder: &mut ModelBuilder,
+    element_nodes: &mut HashMap<String, usize>,
+) {
+    let (uuid, inline) = match node {
+        serde_json::Value::Array(children) => {
+            for child in children {
+                collect_outliner_nodes(child, index, parent, builder, element_nodes);
+            }
+            return;
+        }
+        serde_json::Value::String(uuid) => (uuid.as_str(), None),
+        serde_json::Value::Object(obj) => (
+            obj.get("uuid").and_then(|v| v.as_str()).unwrap_or(""),
+            Some(obj),
+        ),
+        _ => return,
+    };
+
+    if let Some(group) = index.groups.get(uuid) {
+        let transform =
+            pivot_node_transform(group_rotation_transform(group, index.order), BLOCK_SCALE);
+        let name = (!group.name.is_empty()).then(|| group.name.clone());
+        let node = builder.add_node(name, transform, parent);
+        // Newer files list children in the outliner, older ones in the group
+        let children = inline
+            .and_then(|obj| obj.get("children"))
+            .and_then(|v| v.as_array())
+            .unwrap_or(&group.children);
+        for child in children {
+            collect_outliner_nodes(child, index, Some(node), builder, element_nodes);
+        }
+    } else if let Some(obj) = inline.filter(|obj| obj.contains_key("children")) {
+        // Legacy outliner groups carry their data inline
+        let transform =
+            pivot_node_transform(outliner_rotation_transform(obj, index.order), BLOCK_SCALE);
+        let name = obj.get("name").and_then(|v| v.as_str()).map(str::to_owned);
+        let node = builder.add_node(name, transform, parent);
+        if let Some(children) = obj.get("children").and_then(|v| v.as_array()) {
+            for child in children {
+                collect_outliner_nodes(child, index, Some(node), builder, element_nodes);
+            }
+        }
+    } else if let Some(element) = index.elements.get(uuid) {
+        add_element_node(element, uuid, index, parent, builder, element_nodes);
+    }
+}
+
+/// Builds the scene tree from the outliner and returns the node of each element by UUID.
+///
+/// Elements missing from the outliner become root nodes.
+fn build_scene_nodes(
+    model: &BbmodelFile,
+    order: RotationOrder,
+    builder: &mut ModelBuilder,
+) -> HashMap<String, usize> {
+    let index = OutlinerIndex {
+        groups: model
+            .groups
+            .iter()
+            .filter(|g| !g.uuid.is_empty())
+            .map(|g| (g.uuid.as_str(), g))
+            .collect(),
+        elements: model
+            .elements
+            .iter()
+            .filter_map(|e| Some((json_str_or_none(&e.uuid)?, e)))
+            .collect(),
+        order,
+    };
+
+    let mut element_nodes = HashMap::new();
+    for node in &model.outliner {
+        collect_outliner_nodes(node, &index, None, builder, &mut element_nodes);
+    }
+    for element in &model.elements {
+        if let Some(uuid) = json_str_or_none(&element.uuid) {
+            add_element_node(element, uuid, &index, None, builder, &mut element_nodes);
+        }
+    }
+    element_nodes
+}
+
+/// Default color for Blockbench faces (light gray).
+const DEFAULT_COLOR: [f32; 3] = [0.85, 0.85, 0.85];
+
+/// Converts a Blockbench model to meshes.
+fn convert_bbmodel_to_meshes(model: BbmodelFile) -> LoadResult {
+    let mut builder = ModelBuilder::new();
     let euler_order = euler_order_for_format(&model.meta.model_format);
     let element_parent_rotations = build_element_parent_rotation_map(&model, euler_order);
-
-    // Load textures
-    let textures: Vec<Option<Arc<TextureData>>> =
-        model.textures.iter().map(load_bbmodel_texture).collect();
+    let element_nodes = build_scene_nodes(&model, euler_order, &mut builder);
+
+    // One material per texture, plus a plain one for faces with missing textures
+    let untextured = builder.material(Material {
+        color: DEFAULT_COLOR,
+        ..Default::default()
+    });
+    let texture_materials: Vec<usize> = model
+        .textures
+        .iter()
+        .map(|texture| builder.material(bbmodel_material(texture)))
+        .collect();
 
     // Get UV resolution for normalization
     // In Blockbench, UVs are in pixel coordinates based on the project resolution,
@@ -452,61 +632,59 @@ fn convert_bbmodel_to_triangles(model: BbmodelFile) -> LoadResult {
         })
         .unwrap_or((uv_width, uv_height));
 
-    // Convert each element (cube) to triangles
+    // Convert each element to quads (cubes) or triangulated polygons (meshes)
     for element in &model.elements {
-        let parent_rotations = json_str_or_none(&element.uuid)
+        let uuid = json_str_or_none(&element.uuid);
+        let parent_rotations = uuid
             .and_then(|uuid| element_parent_rotations.get(uuid))
             .map(Vec::as_slice)
             .unwrap_or(&[]);
+        builder.set_node(uuid.and_then(|uuid| element_nodes.get(uuid)).copied());
 
-        let cubes = convert_cube_to_triangles(
-            element,
-            &textures,
-            tex_uv_width,
-            tex_uv_height,
-            parent_rotations,
-            euler_order,
-        );
-        triangles.extend(cubes);
+        let matrix = element_matrix(element, parent_rotations, euler_order);
+        let faces = FaceContext {
+            texture_materials: &texture_materials,
+            untextured,
+            uv_size: (tex_uv_width, tex_uv_height),
+        };
+        if element.kind.as_deref() == Some("mesh") {
+            convert_mesh(element, &mut builder, &faces, matrix);
+        } else {
+            convert_cube(element, &mut builder, &faces, matrix);
+        }
     }
 
-    if triangles.is_empty() {
+    if builder.is_empty() {
         return Err(LoadError::NoGeometry);
     }
 
     // Blockbench orientation is opposite of the expected thumbnail view.
     // Apply a bbmodel-only 180deg yaw so glTF/GLB behavior remains unchanged.
-    rotate_triangles_y_180(&mut triangles);
+    let mut model = builder.build();
+    rotate_model_y_180(&mut model);
 
-    Ok(ModelData { triangles })
+    Ok(model)
 }
 
-/// Rotates all triangles 180° around the Y axis through their collective center.
-fn rotate_triangles_y_180(triangles: &mut [Triangle]) {
-    if triangles.is_empty() {
-        return;
-    }
-
-    let (min, max) = {
-        let mut min = glam::Vec3::splat(f32::INFINITY);
-        let mut max = glam::Vec3::splat(f32::NEG_INFINITY);
-        for tri in triangles.iter() {
-            for v in &tri.verts {
-                let p = glam::Vec3::from_array(*v);
-                min = min.min(p);
-                max = max.max(p);
-            }
-        }
-        (min, max)
-    };
-    let center = (min + max) * 0.5;
-
-    // 180° Y rotation = reflect X and Z through center
-    for tri in triangles.iter_mut() {
-        for v in &mut tri.verts {
-            v[0] = 2.0 * center.x - v[0];
-            v[2] = 2.0 * center.z - v[2];
-        }
+/// Builds the material for a Blockbench texture, honoring its render mode and sides.
+fn bbmodel_material(texture: &BbmodelTexture) -> Material {
+    let data = load_bbmodel_texture(texture);
+    let render_mode = texture.render_mode.as_deref().unwrap_or("default");
+    let emissive = render_mode == "emissive";
+
+    Material {
+        name: (!texture.name.is_empty()).then(|| texture.name.clone()),
+        color: DEFAULT_COLOR,
+        emissive: if emissive { [1.0; 3] } else { [0.0; 3] },
+        emissive_texture: data.clone().filter(|_| emissive),
+        texture: data,
+        double_sided: texture.render_sides.as_deref() != Some("front"),
+        render_mode: if render_mode == "additive" {
+            RenderMode::Additive
+        } else {
+            RenderMode::Normal
+        },
+        ..Default::default()
     }
 }
 
@@ -515,43 +693,50 @@ fn load_bbmodel_texture(texture: &BbmodelTexture) -> Option<Arc<TextureData>> {
     load_texture_from_data_url(&texture.source)
 }
 
-/// Converts a cube element to 12 triangles (2 per face).
-fn convert_cube_to_triangles(
+/// Shared inputs for turning element faces into geometry.
+struct FaceContext<'a> {
+    /// Material of each texture, by index.
+    texture_materials: &'a [usize],
+    /// Material for faces whose texture is missing.
+    untextured: usize,
+    /// Pixel size UVs are normalized by.
+    uv_size: (f32, f32),
+}
+
+impl FaceContext<'_> {
+    /// Returns the material for a face's texture reference, or `None` if the
+    /// face should not be rendered.
+    fn material(&self, texture: &Option<serde_json::Value>) -> Option<usize> {
+        // In Blockbench, texture: null means the face should not be rendered
+        let texture = texture.as_ref().filter(|t| !t.is_null())?;
+        Some(
+            texture
+                .as_u64()
+                .and_then(|idx| self.texture_materials.get(idx as usize))
+                .copied()
+                .unwrap_or(self.untextured),
+        )
+    }
+}
+
+/// Combines an element's own rotation with its parent group rotations, in
+/// scaled model units.
+fn element_matrix(
     element: &BbmodelElement,
-    textures: &[Option<Arc<TextureData>>],
-    tex_width: f32,
-    tex_height: f32,
     parent_rotations: &[RotationTransform],
     euler_order: RotationOrder,
-) -> Vec<Triangle> {
-    let mut triangles = Vec::with_capacity(12);
-
-    // Blockbench uses Minecraft coordinate system where coordinates are in 1/16 blocks
-    // Scale factor to convert to a reasonable world space (16 units = 1 block)
+) -> Mat4 {
     let scale = BLOCK_SCALE;
 
-    // Get cube corners (from/to define opposite corners)
-    let from = scale_vec3(element.from, scale);
-    let to = scale_vec3(element.to, scale);
-
-    // Compute the 8 vertices of the cube
-    let vertices = compute_cube_vertices(from, to);
-
     // Apply this element's own rotation first.
-    let mut vertices = if let Some(ref rot_value) = element.rotation {
-        if let Some((angles, rot_origin)) = parse_element_rotation(rot_value) {
-            let origin = rot_origin
-                .or(element.origin)
-                .map(|o| scale_vec3(o, scale))
-                .unwrap_or([0.0; 3]);
-            let transform = RotationTransform::with_order(origin, angles, euler_order);
-            rotate_vertices(&vertices, &transform)
-        } else {
-            vertices
-        }
-    } else {
-        vertices
-    };
+    let mut matrix = Mat4::IDENTITY;
+    if let Some((angles, rot_origin)) = element.rotation.as_ref().and_then(parse_element_rotation) {
+        let origin = rot_origin
+            .or(element.origin)
+            .map(|o| scale_vec3(o, scale))
+            .unwrap_or([0.0; 3]);
+        matrix = RotationTransform::with_order(origin, angles, euler_order).to_matrix();
+    }
 
     // Then apply parent group rotations from nearest parent up to root.
     // Each parent transform already has the correct euler order stored.
@@ -561,31 +746,48 @@ fn convert_cube_to_triangles(
             parent.angles,
             parent.order,
         );
-        vertices = rotate_vertices(&vertices, &scaled);
+        matrix = scaled.to_matrix() * matrix;
     }
+    matrix
+}
+
+/// Applies an element transform to a point.
+fn transform_point(matrix: &Mat4, point: Vec3) -> Vec3 {
+    matrix
+        .transform_point3(GlamVec3::from_array(point))
+        .to_array()
+}
+
+/// Converts a cube element to up to six quads, one per face.
+fn convert_cube(
+    element: &BbmodelElement,
+    builder: &mut ModelBuilder,
+    faces: &FaceContext,
+    matrix: Mat4,
+) {
+    // Blockbench uses Minecraft coordinate system where coordinates are in 1/16 blocks
+    // Scale factor to convert to a reasonable world space (16 units = 1 block)
+    let scale = BLOCK_SCALE;
 
-    // Default color (light gray)
-    let default_color = [0.85, 0.85, 0.85];
+    // Get cube corners (from/to define opposite corners)
+    let from = scale_vec3(element.from, scale);
+    let to = scale_vec3(element.to, scale);
 
+    // Compute the 8 vertices of the cube
+    let vertices = compute_cube_vertices(from, to).map(|v| transform_point(&matrix, v));
+
+    let (tex_width, tex_height) = faces.uv_size;
     for (indices, face_opt) in element.faces.iter() {
         let face = match face_opt {
             Some(f) => f,
             None => continue,
         };
 
-        // Check if face has a texture reference
-        // In Blockbench, texture: null means the face should not be rendered
-        let texture_ref = match &face.texture {
-            Some(t) if !t.is_null() => t,
-            _ => continue, // Skip faces with null or missing texture
+        // Get material for this face, skipping faces without a texture
+        let Some(material) = faces.material(&face.texture) else {
+            continue;
         };
 
-        // Get texture for this face
-        let texture = texture_ref
-            .as_u64()
-            .and_then(|idx| textures.get(idx as usize))
-            .and_then(|t| t.clone());
-
         // Calculate UV coordinates from pixel coordinates
         // Blockbench UVs are in pixel coordinates [u1, v1, u2, v2]
         let uv = &face.uv;
@@ -608,10 +810,83 @@ fn convert_cube_to_triangles(
             face.rotation.unwrap_or(0.0),
         );
 
-        // Create two triangles for this face
-        let tris = quad_to_triangles(&vertices, indices, uvs, default_color, texture);
-        triangles.extend(tris);
+        builder.push_quad(material, indices.map(|i| vertices[i]), uvs);
     }
+}
+
+/// Converts a mesh element to triangles, triangulating each polygonal face.
+fn convert_mesh(
+    element: &BbmodelElement,
+    builder: &mut ModelBuilder,
+    faces: &FaceContext,
+    matrix: Mat4,
+) {
+    let scale = BLOCK_SCALE;
+    // Mesh vertices are stored relative to the element's origin
+    let origin = element.origin.unwrap_or([0.0; 3]);
+    let (tex_width, tex_height) = faces.uv_size;
 
-    triangles
+    for value in element.faces.polygons.values() {
+        let Ok(face) = BbmodelMeshFace::deserialize(value) else {
+            continue;
+        };
+        let Some(material) = faces.material(&face.texture) else {
+            continue;
+        };
+
+        let Some(positions) = face
+            .vertices
+            .iter()
+            .map(|key| {
+                let v = element.vertices.get(key)?;
+                let local = [v[0] + origin[0], v[1] + origin[1], v[2] + origin[2]];
+                Some(transform_point(&matrix, scale_vec3(local, scale)))
+            })
+            .collect::<Option<Vec<Vec3>>>()
+        else {
+            continue; // Face refers to a missing vertex
+        };
+        let uvs: Vec<[f32; 2]> = face
+            .vertices
+            .iter()
+            .map(|key| {
+                face.uv
+                    .get(key)
+                    .map_or([0.0; 2], |uv| [uv[0] / tex_width, uv[1] / tex_height])
+            })
+            .collect();
+
+        let order = corner_order(&positions);
+        let positions: Vec<Vec3> = order.iter().map(|&i| positions[i]).collect();
+        let uvs: Vec<[f32; 2]> = order.iter().map(|&i| uvs[i]).collect();
+        for [a, b, c] in triangulate(&positions) {
+            builder.push_triangle(
+                material,
+                [positions[a], positions[b], positions[c]],
+                [uvs[a], uvs[b], uvs[c]],
+            );
+        }
+    }
+}
+
+/// Returns the order in which to walk a mesh face's corners around its outline.
+///
+/// Blockbench stores quad corners in creation order, which need not run
+/// around the quad. Like Blockbench, this picks whichever of the three
+/// possible outlines encloses the most area; a crossed "bow tie" outline
+/// always encloses less. Other polygons keep their stored order.
+fn corner_order(positions: &[Vec3]) -> Vec<usize> {
+    if positions.len() != 4 {
+        return (0..positions.len()).collect();
+    }
+    let p = |i: usize| GlamVec3::from_array(positions[i]);
+    // Twice the area of quad abcd is |(c - a) x (d - b)|
+    let area = |[a, b, c, d]: [usize; 4]| (p(c) - p(a)).cross(p(d) - p(b)).length();
+    let mut best = [0, 1, 2, 3];
+    for candidate in [[2, 0, 1, 3], [0, 2, 1, 3]] {
+        if area(candidate) > area(best) {
+            best = candidate;
+        }
+    }
+    best.to_vec()
 }
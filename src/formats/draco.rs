@@ -0,0 +1,2881 @@
+//! Provides a decoder for Draco-compressed meshes.
+//!
+//! glTF files written with `KHR_draco_mesh_compression` replace a primitive's
+//! vertex and index data with a single Draco stream. [`decode`] expands such a
+//! stream into triangle [`Mesh::faces`] and one [`Attribute`] per compressed
+//! vertex attribute, which glTF primitives refer to by unique id. Both the
+//! sequential and the edgebreaker connectivity codecs of bitstream version 2.2
+//! are supported; point clouds and older bitstreams are rejected.
+//!
+//! # Examples
+//! ```
+//! use glimpse::formats::draco::{decode, AttributeKind};
+//!
+//! // A single triangle stored with the sequential codec
+//! let encoded = [
+//!     0x44, 0x52, 0x41, 0x43, 0x4f, 0x02, 0x02, 0x01, 0x00, 0x00, 0x00, 0x01, 0x03, 0x01,
+//!     0x00, 0x01, 0x02, 0x01, 0x01, 0x00, 0x09, 0x03, 0x00, 0x00, 0x02, 0x00, 0x01, 0x01,
+//!     0x00, 0x03, 0x03, 0xad, 0x2a, 0x55, 0x15, 0x03, 0xa0, 0x7a, 0x81, 0x88, 0x01, 0x00,
+//!     0x00, 0x00, 0x00, 0xff, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+//!     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x0b,
+//! ];
+//! let mesh = decode(&encoded).unwrap();
+//! assert_eq!(mesh.faces, [[0, 1, 2]]);
+//!
+//! let position = mesh.attribute(0).unwrap();
+//! assert_eq!(position.kind, AttributeKind::Position);
+//! assert_eq!(position.values, [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+//! ```
+
+use std::collections::HashMap;
+
+use super::LoadError;
+
+/// Magic bytes at the start of every Draco stream.
+const MAGIC: &[u8] = b"DRACO";
+/// Encoder type of triangle meshes, as opposed to point clouds.
+const TRIANGULAR_MESH: u8 = 1;
+/// Header flag marking streams that carry metadata before the geometry.
+const METADATA_FLAG: u16 = 0x8000;
+/// Marker for a missing corner or vertex.
+const INVALID: u32 = u32::MAX;
+
+/// Edgebreaker symbol that closes a triangle onto the active boundary.
+const TOPOLOGY_C: u32 = 0;
+/// Edgebreaker symbol that splits the active boundary in two.
+const TOPOLOGY_S: u32 = 1;
+/// Edgebreaker symbol that adds a vertex left of the active edge.
+const TOPOLOGY_L: u32 = 3;
+/// Edgebreaker symbol that adds a vertex right of the active edge.
+const TOPOLOGY_R: u32 = 5;
+/// Edgebreaker symbol that starts a new isolated triangle.
+const TOPOLOGY_E: u32 = 7;
+/// Symbols in the order valence-coded streams number them.
+const VALENCE_SYMBOLS: [u32; 5] = [TOPOLOGY_C, TOPOLOGY_S, TOPOLOGY_L, TOPOLOGY_R, TOPOLOGY_E];
+
+/// Probability scale of the binary rANS coder used for flags.
+const BIT_BASE: u32 = 4096;
+/// Upper bound for the magnitude of predicted normals.
+const NORMAL_BOUND: i64 = 1 << 29;
+
+// Attribute types as numbered in the bitstream
+const POSITION: u8 = 0;
+const NORMAL: u8 = 1;
+const COLOR: u8 = 2;
+const TEX_COORD: u8 = 3;
+
+// Attribute data types as numbered in the bitstream
+const INT8: u8 = 1;
+const UINT8: u8 = 2;
+const INT16: u8 = 3;
+const UINT16: u8 = 4;
+const INT32: u8 = 5;
+const UINT32: u8 = 6;
+const INT64: u8 = 7;
+const UINT64: u8 = 8;
+const FLOAT32: u8 = 9;
+const FLOAT64: u8 = 10;
+const BOOL: u8 = 11;
+
+/// A decoded Draco mesh.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::draco::{Attribute, AttributeKind, Mesh};
+///
+/// let mesh = Mesh {
+///     faces: vec![[0, 1, 2]],
+///     attributes: vec![Attribute {
+///         unique_id: 4,
+///         kind: AttributeKind::Position,
+///         components: 3,
+///         values: vec![0.0; 9],
+///     }],
+/// };
+/// assert_eq!(mesh.attribute(4).unwrap().components, 3);
+/// assert!(mesh.attribute(0).is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mesh {
+    /// Triangles as indices into the attribute values.
+    pub faces: Vec<[u32; 3]>,
+    /// Every attribute stored in the stream.
+    pub attributes: Vec<Attribute>,
+}
+
+impl Mesh {
+    /// Finds the attribute with the given unique id.
+    ///
+    /// glTF primitives name their compressed attributes by this id.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::draco::Mesh;
+    ///
+    /// let mesh = Mesh { faces: Vec::new(), attributes: Vec::new() };
+    /// assert!(mesh.attribute(0).is_none());
+    /// ```
+    pub fn attribute(&self, unique_id: u32) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.unique_id == unique_id)
+    }
+}
+
+/// One decoded vertex attribute.
+///
+/// Values are stored per point, `components` floats each, so they line up with
+/// the indices in [`Mesh::faces`]. Normalized integer attributes are mapped to
+/// 0..1 (or -1..1 when signed) like glTF accessors.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::draco::{Attribute, AttributeKind};
+///
+/// let uv = Attribute {
+///     unique_id: 1,
+///     kind: AttributeKind::TexCoord,
+///     components: 2,
+///     values: vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+/// };
+/// assert_eq!(uv.values.len() / uv.components, 3);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attribute {
+    /// Id the stream assigned to this attribute.
+    pub unique_id: u32,
+    /// What the attribute describes.
+    pub kind: AttributeKind,
+    /// Number of floats per point.
+    pub components: usize,
+    /// Values of all points, one after another.
+    pub values: Vec<f32>,
+}
+
+/// Meaning of a decoded attribute.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::draco::AttributeKind;
+///
+/// assert_ne!(AttributeKind::Position, AttributeKind::Normal);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeKind {
+    /// Vertex positions.
+    Position,
+    /// Vertex normals.
+    Normal,
+    /// Vertex colours.
+    Color,
+    /// Texture coordinates.
+    TexCoord,
+    /// Any other per-vertex data, such as joints or weights.
+    Generic,
+}
+
+/// Decodes a Draco mesh stream.
+///
+/// # Errors
+/// Returns [`LoadError::InvalidData`] if the data is truncated or corrupt, or
+/// if it uses a bitstream version or feature this decoder does not support.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::draco::decode;
+///
+/// assert!(decode(b"DRACO").is_err());
+/// assert!(decode(b"not a draco stream").is_err());
+/// ```
+pub fn decode(data: &[u8]) -> Result<Mesh, LoadError> {
+    let mut r = Reader::new(data);
+    if r.bytes(MAGIC.len()).ok() != Some(MAGIC) {
+        return Err(invalid("missing DRACO header"));
+    }
+    let (major, minor) = (r.u8()?, r.u8()?);
+    if (major, minor) != (2, 2) {
+        return Err(invalid(&format!(
+            "unsupported bitstream version {}.{}",
+            major, minor
+        )));
+    }
+    if r.u8()? != TRIANGULAR_MESH {
+        return Err(invalid("only triangle meshes are supported"));
+    }
+    let method = r.u8()?;
+    if r.u16()? & METADATA_FLAG != 0 {
+        skip_metadata(&mut r)?;
+    }
+
+    let connectivity = match method {
+        0 => decode_sequential(&mut r)?,
+        1 => match r.u8()? {
+            0 => decode_edgebreaker(&mut r, false)?,
+            2 => decode_edgebreaker(&mut r, true)?,
+            _ => return Err(invalid("unsupported edgebreaker traversal")),
+        },
+        _ => return Err(invalid("unknown connectivity method")),
+    };
+    let attributes = decode_attributes(&mut r, &connectivity)?;
+
+    let num_points = connectivity.num_points;
+    let attributes = attributes
+        .into_iter()
+        .map(|a| a.expand(num_points))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Mesh {
+        faces: connectivity.faces,
+        attributes,
+    })
+}
+
+fn invalid(reason: &str) -> LoadError {
+    LoadError::InvalidData(format!("Invalid Draco data: {}", reason))
+}
+
+// ===========================================================================
+// Stream readers
+// ===========================================================================
+
+/// Cursor over the little-endian byte stream.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    /// Returns the unread part of the stream without consuming it.
+    fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], LoadError> {
+        if len > self.remaining() {
+            return Err(invalid("unexpected end of data"));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, LoadError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn i8(&mut self) -> Result<i8, LoadError> {
+        Ok(self.u8()? as i8)
+    }
+
+    fn u16(&mut self) -> Result<u16, LoadError> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn i32(&mut self) -> Result<i32, LoadError> {
+        let b = self.bytes(4)?;
+        Ok(i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, LoadError> {
+        Ok(f32::from_bits(self.i32()? as u32))
+    }
+
+    fn varint(&mut self) -> Result<u32, LoadError> {
+        Ok(self.leb128(5)? as u32)
+    }
+
+    fn varint64(&mut self) -> Result<u64, LoadError> {
+        self.leb128(10)
+    }
+
+    fn leb128(&mut self, max_bytes: u32) -> Result<u64, LoadError> {
+        let mut value = 0u64;
+        for i in 0..max_bytes {
+            let b = self.u8()?;
+            value |= ((b & 0x7f) as u64) << (7 * i);
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint is too long"))
+    }
+}
+
+/// Reads bits least significant first; bits past the end read as zero.
+struct BitReader<'a> {
+    data: &'a [u8],
+    bit: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, bit: 0 }
+    }
+
+    fn read(&mut self, bits: u32) -> u32 {
+        let mut value = 0;
+        for i in 0..bits {
+            let Some(&byte) = self.data.get(self.bit / 8) else {
+                break;
+            };
+            value |= (((byte >> (self.bit % 8)) & 1) as u32) << i;
+            self.bit += 1;
+        }
+        value
+    }
+
+    fn bytes_used(&self) -> usize {
+        self.bit.div_ceil(8)
+    }
+}
+
+/// Reads the initial state of an rANS coder from the end of its data.
+///
+/// The last byte's top two bits give how many bytes hold the state.
+fn ans_init(data: &[u8], base: u32, max_len: usize) -> Result<(usize, u32), LoadError> {
+    let Some(&last) = data.last() else {
+        return Err(invalid("empty entropy coded block"));
+    };
+    let len = (last >> 6) as usize + 1;
+    if len > max_len || len > data.len() {
+        return Err(invalid("bad entropy coder state"));
+    }
+    let offset = data.len() - len;
+    let state = data[offset..]
+        .iter()
+        .rev()
+        .fold(0u32, |state, &b| (state << 8) | b as u32);
+    let state = (state & ((1 << (8 * len - 2)) - 1)) + base;
+    if state >= base * 256 {
+        return Err(invalid("bad entropy coder state"));
+    }
+    Ok((offset, state))
+}
+
+/// Multi-symbol rANS decoder with a transmitted probability table.
+struct SymbolReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    state: u32,
+    precision: u32,
+    base: u32,
+    /// Probability and cumulative probability of each symbol.
+    symbols: Vec<(u32, u32)>,
+    /// Symbol of each slot in `0..precision`.
+    lookup: Vec<u32>,
+}
+
+impl<'a> SymbolReader<'a> {
+    /// Reads the probability table and the coded data of symbols up to
+    /// `symbol_bits` wide.
+    fn new(r: &mut Reader<'a>, symbol_bits: u32) -> Result<Self, LoadError> {
+        let precision = 1u32 << (3 * symbol_bits / 2).clamp(12, 20);
+        let num_symbols = r.varint()? as usize;
+        if num_symbols == 0 || num_symbols / 64 > r.remaining() {
+            return Err(invalid("bad symbol table size"));
+        }
+
+        let mut probabilities = vec![0u32; num_symbols];
+        let mut i = 0;
+        while i < num_symbols {
+            let b = r.u8()?;
+            let token = b & 3;
+            if token == 3 {
+                // A run of zero probabilities
+                let run = (b >> 2) as usize;
+                if i + run >= num_symbols {
+                    return Err(invalid("symbol table overruns"));
+                }
+                i += run + 1;
+            } else {
+                let mut probability = (b >> 2) as u32;
+                for k in 0..token as u32 {
+                    probability |= (r.u8()? as u32) << (8 * (k + 1) - 2);
+                }
+                probabilities[i] = probability;
+                i += 1;
+            }
+        }
+
+        let mut symbols = Vec::with_capacity(num_symbols);
+        let mut lookup = vec![0u32; precision as usize];
+        let mut cumulative = 0u32;
+        for (symbol, &probability) in probabilities.iter().enumerate() {
+            let end = cumulative
+                .checked_add(probability)
+                .filter(|&end| end <= precision)
+                .ok_or_else(|| invalid("symbol probabilities overflow"))?;
+            lookup[cumulative as usize..end as usize].fill(symbol as u32);
+            symbols.push((probability, cumulative));
+            cumulative = end;
+        }
+        if cumulative != precision {
+            return Err(invalid("symbol probabilities do not add up"));
+        }
+
+        let size = r.varint64()?;
+        if size > r.remaining() as u64 {
+            return Err(invalid("symbol data overruns"));
+        }
+        let data = r.bytes(size as usize)?;
+        let base = precision * 4;
+        let (offset, state) = ans_init(data, base, 4)?;
+        Ok(Self {
+            data,
+            offset,
+            state,
+            precision,
+            base,
+            symbols,
+            lookup,
+        })
+    }
+
+    fn read(&mut self) -> u32 {
+        while self.state < self.base && self.offset > 0 {
+            self.offset -= 1;
+            self.state = self.state * 256 + self.data[self.offset] as u32;
+        }
+        let quotient = self.state / self.precision;
+        let remainder = self.state % self.precision;
+        let symbol = self.lookup[remainder as usize];
+        let (probability, cumulative) = self.symbols[symbol as usize];
+        self.state = quotient * probability + remainder - cumulative;
+        symbol
+    }
+}
+
+/// Binary rANS decoder with a fixed probability, used for flags.
+struct BitDecoder<'a> {
+    data: &'a [u8],
+    offset: usize,
+    state: u32,
+    probability_zero: u32,
+}
+
+impl<'a> BitDecoder<'a> {
+    fn new(r: &mut Reader<'a>) -> Result<Self, LoadError> {
+        let probability_zero = r.u8()? as u32;
+        let size = r.varint()? as usize;
+        if size > r.remaining() {
+            return Err(invalid("flag data overruns"));
+        }
+        let data = r.bytes(size)?;
+        let (offset, state) = ans_init(data, BIT_BASE, 3)?;
+        Ok(Self {
+            data,
+            offset,
+            state,
+            probability_zero,
+        })
+    }
+
+    fn read(&mut self) -> bool {
+        let p = 256 - self.probability_zero;
+        if self.state < BIT_BASE && self.offset > 0 {
+            self.offset -= 1;
+            self.state = self.state * 256 + self.data[self.offset] as u32;
+        }
+        let quotient = self.state / 256;
+        let remainder = self.state % 256;
+        let xn = quotient * p;
+        if remainder < p {
+            self.state = xn + remainder;
+            true
+        } else {
+            self.state -= xn + p;
+            false
+        }
+    }
+}
+
+/// Decodes `count` entropy-coded unsigned values in groups of `components`.
+fn decode_symbols(r: &mut Reader, count: usize, components: usize) -> Result<Vec<u32>, LoadError> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    match r.u8()? {
+        0 => {
+            // Each group stores its bit length as a symbol, then raw bits
+            let mut lengths = SymbolReader::new(r, 5)?;
+            let mut bits = BitReader::new(r.rest());
+            let mut values = Vec::new();
+            while values.len() < count {
+                let len = lengths.read();
+                if len > 32 {
+                    return Err(invalid("bad symbol bit length"));
+                }
+                values.extend((0..components).map(|_| bits.read(len)));
+            }
+            r.bytes(bits.bytes_used())?;
+            values.truncate(count);
+            Ok(values)
+        }
+        1 => {
+            let max_bits = r.u8()? as u32;
+            if !(1..=18).contains(&max_bits) {
+                return Err(invalid("bad symbol bit length"));
+            }
+            let mut symbols = SymbolReader::new(r, max_bits)?;
+            Ok((0..count).map(|_| symbols.read()).collect())
+        }
+        _ => Err(invalid("unknown symbol coding")),
+    }
+}
+
+/// Skips the metadata block, which only holds names and opaque entries.
+fn skip_metadata(r: &mut Reader) -> Result<(), LoadError> {
+    let num_attribute_metadata = r.varint()?;
+    for _ in 0..num_attribute_metadata {
+        r.varint()?;
+        skip_metadata_block(r, false)?;
+    }
+    skip_metadata_block(r, false)
+}
+
+/// Skips one metadata block and all of its named sub-blocks.
+fn skip_metadata_block(r: &mut Reader, named: bool) -> Result<(), LoadError> {
+    let mut pending = 1u64;
+    let mut named = named;
+    while pending > 0 {
+        pending -= 1;
+        if named {
+            let len = r.u8()? as usize;
+            r.bytes(len)?;
+        }
+        for _ in 0..r.varint()? {
+            let len = r.u8()? as usize;
+            r.bytes(len)?;
+            let size = r.varint()? as usize;
+            if size == 0 {
+                return Err(invalid("empty metadata entry"));
+            }
+            r.bytes(size)?;
+        }
+        let num_sub_blocks = r.varint()?;
+        if num_sub_blocks as usize > r.remaining() {
+            return Err(invalid("too many metadata blocks"));
+        }
+        pending += num_sub_blocks as u64;
+        named = true;
+    }
+    Ok(())
+}
+
+// ===========================================================================
+// Corner tables
+// ===========================================================================
+
+/// Connectivity stored per corner, three corners per face.
+struct CornerTable {
+    /// Vertex of each corner.
+    vertices: Vec<u32>,
+    /// Corner across the edge facing each corner.
+    opposites: Vec<u32>,
+    /// A corner of each vertex from which swinging right reaches all others.
+    left_most: Vec<u32>,
+}
+
+impl CornerTable {
+    fn num_faces(&self) -> usize {
+        self.vertices.len() / 3
+    }
+
+    fn num_vertices(&self) -> usize {
+        self.left_most.len()
+    }
+
+    fn next(corner: u32) -> u32 {
+        match corner {
+            INVALID => INVALID,
+            c if c % 3 == 2 => c - 2,
+            c => c + 1,
+        }
+    }
+
+    fn prev(corner: u32) -> u32 {
+        match corner {
+            INVALID => INVALID,
+            c if c % 3 == 0 => c + 2,
+            c => c - 1,
+        }
+    }
+
+    fn vertex(&self, corner: u32) -> u32 {
+        self.vertices
+            .get(corner as usize)
+            .copied()
+            .unwrap_or(INVALID)
+    }
+
+    fn opposite(&self, corner: u32) -> u32 {
+        self.opposites
+            .get(corner as usize)
+            .copied()
+            .unwrap_or(INVALID)
+    }
+
+    fn left_most(&self, vertex: u32) -> u32 {
+        self.left_most
+            .get(vertex as usize)
+            .copied()
+            .unwrap_or(INVALID)
+    }
+
+    fn swing_left(&self, corner: u32) -> u32 {
+        Self::next(self.opposite(Self::next(corner)))
+    }
+
+    fn swing_right(&self, corner: u32) -> u32 {
+        Self::prev(self.opposite(Self::prev(corner)))
+    }
+
+    /// Corner of the face across the edge left of `corner`.
+    fn left_corner(&self, corner: u32) -> u32 {
+        self.opposite(Self::prev(corner))
+    }
+
+    /// Corner of the face across the edge right of `corner`.
+    fn right_corner(&self, corner: u32) -> u32 {
+        self.opposite(Self::next(corner))
+    }
+
+    fn is_on_boundary(&self, vertex: u32) -> bool {
+        self.swing_left(self.left_most(vertex)) == INVALID
+    }
+
+    fn map(&mut self, corner: u32, vertex: u32) {
+        if let Some(v) = self.vertices.get_mut(corner as usize) {
+            *v = vertex;
+        }
+    }
+
+    fn set_opposites(&mut self, a: u32, b: u32) {
+        if let Some(o) = self.opposites.get_mut(a as usize) {
+            *o = b;
+        }
+        if let Some(o) = self.opposites.get_mut(b as usize) {
+            *o = a;
+        }
+    }
+
+    fn set_left_most(&mut self, vertex: u32, corner: u32) {
+        if let Some(c) = self.left_most.get_mut(vertex as usize) {
+            *c = corner;
+        }
+    }
+
+    fn add_vertex(&mut self) -> u32 {
+        self.left_most.push(INVALID);
+        self.left_most.len() as u32 - 1
+    }
+
+    /// Iterates the corners sharing the vertex of `corner`, starting with it.
+    fn corners_around(&self, corner: u32) -> CornersAround<'_> {
+        CornersAround {
+            table: self,
+            start: corner,
+            corner,
+            left: true,
+            remaining: self.vertices.len(),
+        }
+    }
+}
+
+/// Iterates the corners around a vertex, swinging left first and then right.
+struct CornersAround<'a> {
+    table: &'a CornerTable,
+    start: u32,
+    corner: u32,
+    left: bool,
+    /// Guards against cycles in corrupt tables.
+    remaining: usize,
+}
+
+impl Iterator for CornersAround<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.corner == INVALID || self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let current = self.corner;
+        if self.left {
+            self.corner = self.table.swing_left(current);
+            if self.corner == INVALID {
+                self.corner = self.table.swing_right(self.start);
+                self.left = false;
+            } else if self.corner == self.start {
+                self.corner = INVALID;
+            }
+        } else {
+            self.corner = self.table.swing_right(current);
+        }
+        Some(current)
+    }
+}
+
+// ===========================================================================
+// Connectivity
+// ===========================================================================
+
+/// Faces of the mesh and the corner tables attribute decoding walks.
+struct Connectivity {
+    faces: Vec<[u32; 3]>,
+    num_points: usize,
+    /// Present for edgebreaker streams only.
+    edgebreaker: Option<Edgebreaker>,
+}
+
+/// Corner tables rebuilt by the edgebreaker codec.
+struct Edgebreaker {
+    table: CornerTable,
+    /// Tables of attributes that are cut along their own seams.
+    attribute_tables: Vec<CornerTable>,
+}
+
+/// Decodes faces stored as plain or delta-coded index lists.
+fn decode_sequential(r: &mut Reader) -> Result<Connectivity, LoadError> {
+    let num_faces = r.varint()? as usize;
+    let num_points = r.varint()? as usize;
+    if num_faces > r.remaining() / 3 {
+        return Err(invalid("too many faces"));
+    }
+
+    let indices: Vec<u32> = if r.u8()? == 0 {
+        let mut last = 0i32;
+        let mut indices = Vec::with_capacity(num_faces * 3);
+        for encoded in decode_symbols(r, num_faces * 3, 1)? {
+            let diff = (encoded >> 1) as i32;
+            last = if encoded & 1 != 0 {
+                if diff > last {
+                    return Err(invalid("negative index"));
+                }
+                last - diff
+            } else {
+                last.checked_add(diff)
+                    .ok_or_else(|| invalid("index overflows"))?
+            };
+            indices.push(last as u32);
+        }
+        indices
+    } else if num_points < 1 << 8 {
+        r.bytes(num_faces * 3)?.iter().map(|&i| i as u32).collect()
+    } else if num_points < 1 << 16 {
+        let bytes = r.bytes(num_faces * 6)?;
+        bytes
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as u32)
+            .collect()
+    } else if num_points < 1 << 21 {
+        (0..num_faces * 3)
+            .map(|_| r.varint())
+            .collect::<Result<_, _>>()?
+    } else {
+        (0..num_faces * 3)
+            .map(|_| r.i32().map(|i| i as u32))
+            .collect::<Result<_, _>>()?
+    };
+
+    if indices.iter().any(|&i| i as usize >= num_points) {
+        return Err(invalid("face index out of range"));
+    }
+    let faces = indices
+        .chunks_exact(3)
+        .map(|f| [f[0], f[1], f[2]])
+        .collect();
+    Ok(Connectivity {
+        faces,
+        num_points,
+        edgebreaker: None,
+    })
+}
+
+/// Where the encoder's traversal split off a second boundary.
+struct TopologySplit {
+    /// Encoder symbol that reaches the split.
+    source: u32,
+    /// Encoder symbol that created it.
+    split: u32,
+    right_edge: bool,
+}
+
+fn decode_topology_splits(r: &mut Reader, num_faces: u32) -> Result<Vec<TopologySplit>, LoadError> {
+    let count = r.varint()?;
+    if count > num_faces {
+        return Err(invalid("too many topology splits"));
+    }
+    let mut splits = Vec::new();
+    let mut last = 0u32;
+    for _ in 0..count {
+        let source = r.varint()?.wrapping_add(last);
+        let delta = r.varint()?;
+        if delta > source {
+            return Err(invalid("bad topology split"));
+        }
+        splits.push(TopologySplit {
+            source,
+            split: source - delta,
+            right_edge: false,
+        });
+        last = source;
+    }
+    if count > 0 {
+        let mut bits = BitReader::new(r.rest());
+        for split in &mut splits {
+            split.right_edge = bits.read(1) == 1;
+        }
+        r.bytes(bits.bytes_used())?;
+    }
+    Ok(splits)
+}
+
+/// Source of edgebreaker symbols, start face flags and attribute seam flags.
+struct Traversal<'a> {
+    symbols: Symbols<'a>,
+    start_faces: BitDecoder<'a>,
+    seams: Vec<BitDecoder<'a>>,
+}
+
+/// How edgebreaker symbols are stored.
+enum Symbols<'a> {
+    /// One bit for C and three bits for every other symbol.
+    Standard(BitReader<'a>),
+    /// Symbols grouped by the valence of the vertex they start from.
+    Valence {
+        valences: Vec<u32>,
+        contexts: Vec<Vec<u32>>,
+        active: Option<usize>,
+        last: u32,
+    },
+}
+
+impl<'a> Traversal<'a> {
+    fn start(
+        r: &mut Reader<'a>,
+        valence: bool,
+        num_attribute_data: usize,
+        max_vertices: usize,
+        num_faces: u32,
+    ) -> Result<Self, LoadError> {
+        let symbol_bits = if valence {
+            None
+        } else {
+            let size = r.varint64()?;
+            let bits = BitReader::new(r.rest());
+            if size > r.remaining() as u64 {
+                return Err(invalid("traversal data overruns"));
+            }
+            r.bytes(size as usize)?;
+            Some(bits)
+        };
+        let start_faces = BitDecoder::new(r)?;
+        let seams = (0..num_attribute_data)
+            .map(|_| BitDecoder::new(r))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let symbols = match symbol_bits {
+            Some(bits) => Symbols::Standard(bits),
+            None => {
+                let mut contexts = Vec::with_capacity(6);
+                for _ in 0..6 {
+                    let count = r.varint()?;
+                    if count > num_faces {
+                        return Err(invalid("too many valence symbols"));
+                    }
+                    contexts.push(decode_symbols(r, count as usize, 1)?);
+                }
+                Symbols::Valence {
+                    valences: vec![0; max_vertices],
+                    contexts,
+                    active: None,
+                    last: INVALID,
+                }
+            }
+        };
+        Ok(Self {
+            symbols,
+            start_faces,
+            seams,
+        })
+    }
+
+    fn next_symbol(&mut self) -> Result<u32, LoadError> {
+        match &mut self.symbols {
+            Symbols::Standard(bits) => match bits.read(1) {
+                TOPOLOGY_C => Ok(TOPOLOGY_C),
+                symbol => Ok(symbol | (bits.read(2) << 1)),
+            },
+            Symbols::Valence {
+                contexts,
+                active,
+                last,
+                ..
+            } => {
+                let symbol = match *active {
+                    Some(context) => {
+                        let id = contexts[context]
+                            .pop()
+                            .ok_or_else(|| invalid("valence context ran out of symbols"))?;
+                        *VALENCE_SYMBOLS
+                            .get(id as usize)
+                            .ok_or_else(|| invalid("bad valence symbol"))?
+                    }
+                    None => TOPOLOGY_E,
+                };
+                *last = symbol;
+                Ok(symbol)
+            }
+        }
+    }
+
+    /// Updates vertex valences once `corner` became the active corner.
+    fn new_active_corner(&mut self, table: &CornerTable, corner: u32) {
+        let Symbols::Valence {
+            valences,
+            active,
+            last,
+            ..
+        } = &mut self.symbols
+        else {
+            return;
+        };
+        let next = table.vertex(CornerTable::next(corner));
+        let prev = table.vertex(CornerTable::prev(corner));
+        let tip = table.vertex(corner);
+        let increments = match *last {
+            TOPOLOGY_C | TOPOLOGY_S => [(next, 1), (prev, 1)].to_vec(),
+            TOPOLOGY_R => [(tip, 1), (next, 1), (prev, 2)].to_vec(),
+            TOPOLOGY_L => [(tip, 1), (next, 2), (prev, 1)].to_vec(),
+            TOPOLOGY_E => [(tip, 2), (next, 2), (prev, 2)].to_vec(),
+            _ => Vec::new(),
+        };
+        for (vertex, increment) in increments {
+            if let Some(v) = valences.get_mut(vertex as usize) {
+                *v += increment;
+            }
+        }
+        let valence = valences.get(next as usize).copied().unwrap_or(0);
+        *active = Some(valence.clamp(2, 7) as usize - 2);
+    }
+
+    fn merge_vertices(&mut self, dest: u32, source: u32) {
+        if let Symbols::Valence { valences, .. } = &mut self.symbols {
+            let source = valences.get(source as usize).copied().unwrap_or(0);
+            if let Some(v) = valences.get_mut(dest as usize) {
+                *v += source;
+            }
+        }
+    }
+}
+
+/// Rebuilds the corner table from edgebreaker symbols.
+fn decode_edgebreaker(r: &mut Reader, valence: bool) -> Result<Connectivity, LoadError> {
+    let num_encoded_vertices = r.varint()?;
+    let num_faces = r.varint()?;
+    if num_faces > u32::MAX / 3 || num_encoded_vertices > num_faces * 3 {
+        return Err(invalid("bad face count"));
+    }
+    let vertices = num_encoded_vertices as u64;
+    if vertices.wrapping_mul(vertices.wrapping_sub(1)) / 2 < (3 * num_faces / 2) as u64 {
+        return Err(invalid("too few vertices for the faces"));
+    }
+    let num_attribute_data = r.u8()? as usize;
+    let num_symbols = r.varint()?;
+    if num_faces < num_symbols || num_faces > num_symbols + num_symbols / 3 {
+        return Err(invalid("bad symbol count"));
+    }
+    let num_split_symbols = r.varint()?;
+    if num_split_symbols > num_symbols {
+        return Err(invalid("bad split symbol count"));
+    }
+
+    let max_vertices = num_encoded_vertices as usize + num_split_symbols as usize;
+    let num_corners = num_faces as usize * 3;
+    let mut table = CornerTable {
+        vertices: vec![INVALID; num_corners],
+        opposites: vec![INVALID; num_corners],
+        left_most: Vec::new(),
+    };
+    let mut is_vert_hole = vec![true; max_vertices];
+    let mut splits = decode_topology_splits(r, num_faces)?;
+    let mut traversal = Traversal::start(r, valence, num_attribute_data, max_vertices, num_faces)?;
+
+    let mut active: Vec<u32> = Vec::new();
+    let mut split_corners: HashMap<u32, u32> = HashMap::new();
+    let mut invalid_vertices = Vec::new();
+    for symbol_id in 0..num_symbols {
+        let corner = 3 * symbol_id;
+        let mut check_split = false;
+        match traversal.next_symbol()? {
+            TOPOLOGY_C => {
+                let &corner_a = active.last().ok_or_else(|| invalid("no active corner"))?;
+                let vertex_x = table.vertex(CornerTable::next(corner_a));
+                let corner_b = CornerTable::next(table.left_most(vertex_x));
+                if corner_b == INVALID
+                    || corner_a == corner_b
+                    || table.opposite(corner_a) != INVALID
+                    || table.opposite(corner_b) != INVALID
+                {
+                    return Err(invalid("bad C symbol"));
+                }
+                table.set_opposites(corner_a, corner + 1);
+                table.set_opposites(corner_b, corner + 2);
+                let vert_a_prev = table.vertex(CornerTable::prev(corner_a));
+                let vert_b_next = table.vertex(CornerTable::next(corner_b));
+                if vertex_x == vert_a_prev || vertex_x == vert_b_next {
+                    return Err(invalid("bad C symbol"));
+                }
+                table.map(corner, vertex_x);
+                table.map(corner + 1, vert_b_next);
+                table.map(corner + 2, vert_a_prev);
+                table.set_left_most(vert_a_prev, corner + 2);
+                if let Some(hole) = is_vert_hole.get_mut(vertex_x as usize) {
+                    *hole = false;
+                }
+                *active.last_mut().unwrap() = corner;
+            }
+            symbol @ (TOPOLOGY_R | TOPOLOGY_L) => {
+                let &corner_a = active.last().ok_or_else(|| invalid("no active corner"))?;
+                if table.opposite(corner_a) != INVALID {
+                    return Err(invalid("bad R or L symbol"));
+                }
+                let (opp_corner, corner_l, corner_r) = if symbol == TOPOLOGY_R {
+                    (corner + 2, corner + 1, corner)
+                } else {
+                    (corner + 1, corner, corner + 2)
+                };
+                table.set_opposites(opp_corner, corner_a);
+                let new_vertex = table.add_vertex();
+                if table.num_vertices() > max_vertices {
+                    return Err(invalid("too many vertices"));
+                }
+                table.map(opp_corner, new_vertex);
+                table.set_left_most(new_vertex, opp_corner);
+                let vertex_r = table.vertex(CornerTable::prev(corner_a));
+                table.map(corner_r, vertex_r);
+                table.set_left_most(vertex_r, corner_r);
+                table.map(corner_l, table.vertex(CornerTable::next(corner_a)));
+                *active.last_mut().unwrap() = corner;
+                check_split = true;
+            }
+            TOPOLOGY_S => {
+                let corner_b = active.pop().ok_or_else(|| invalid("no active corner"))?;
+                if let Some(&split_corner) = split_corners.get(&symbol_id) {
+                    active.push(split_corner);
+                }
+                let &corner_a = active.last().ok_or_else(|| invalid("no active corner"))?;
+                if corner_a == corner_b
+                    || table.opposite(corner_a) != INVALID
+                    || table.opposite(corner_b) != INVALID
+                {
+                    return Err(invalid("bad S symbol"));
+                }
+                table.set_opposites(corner_a, corner + 2);
+                table.set_opposites(corner_b, corner + 1);
+                let vertex_p = table.vertex(CornerTable::prev(corner_a));
+                table.map(corner, vertex_p);
+                table.map(corner + 1, table.vertex(CornerTable::next(corner_a)));
+                let vert_b_prev = table.vertex(CornerTable::prev(corner_b));
+                table.map(corner + 2, vert_b_prev);
+                table.set_left_most(vert_b_prev, corner + 2);
+
+                // Merge the vertex of corner_b's next corner into vertex_p
+                let first = CornerTable::next(corner_b);
+                let vertex_n = table.vertex(first);
+                traversal.merge_vertices(vertex_p, vertex_n);
+                table.set_left_most(vertex_p, table.left_most(vertex_n));
+                let mut corner_n = first;
+                let mut steps = 0;
+                while corner_n != INVALID {
+                    table.map(corner_n, vertex_p);
+                    corner_n = table.swing_left(corner_n);
+                    steps += 1;
+                    if corner_n == first || steps > num_corners {
+                        return Err(invalid("bad S symbol"));
+                    }
+                }
+                table.set_left_most(vertex_n, INVALID);
+                if num_attribute_data == 0 {
+                    invalid_vertices.push(vertex_n);
+                }
+                *active.last_mut().unwrap() = corner;
+            }
+            TOPOLOGY_E => {
+                let first = table.add_vertex();
+                table.add_vertex();
+                table.add_vertex();
+                if table.num_vertices() > max_vertices {
+                    return Err(invalid("too many vertices"));
+                }
+                for i in 0..3 {
+                    table.map(corner + i, first + i);
+                    table.set_left_most(first + i, corner + i);
+                }
+                active.push(corner);
+                check_split = true;
+            }
+            _ => return Err(invalid("bad edgebreaker symbol")),
+        }
+
+        let top = *active.last().unwrap();
+        traversal.new_active_corner(&table, top);
+        if check_split {
+            let encoder_symbol_id = num_symbols - symbol_id - 1;
+            while let Some(split) = splits.last() {
+                if split.source > encoder_symbol_id {
+                    return Err(invalid("topology split out of order"));
+                }
+                if split.source != encoder_symbol_id {
+                    break;
+                }
+                let top = *active.last().unwrap();
+                let new_active = if split.right_edge {
+                    CornerTable::next(top)
+                } else {
+                    CornerTable::prev(top)
+                };
+                split_corners.insert(num_symbols - split.split - 1, new_active);
+                splits.pop();
+            }
+        }
+    }
+
+    // Close the remaining boundaries, some of which are interior faces
+    let mut num_decoded_faces = num_symbols;
+    while let Some(corner) = active.pop() {
+        if !traversal.start_faces.read() {
+            continue;
+        }
+        if num_decoded_faces >= num_faces {
+            return Err(invalid("too many faces"));
+        }
+        let vert_n = table.vertex(CornerTable::next(corner));
+        let corner_b = CornerTable::next(table.left_most(vert_n));
+        let vert_x = table.vertex(CornerTable::next(corner_b));
+        let corner_c = CornerTable::next(table.left_most(vert_x));
+        if corner_b == INVALID
+            || corner_c == INVALID
+            || corner == corner_b
+            || corner == corner_c
+            || corner_b == corner_c
+            || table.opposite(corner) != INVALID
+            || table.opposite(corner_b) != INVALID
+            || table.opposite(corner_c) != INVALID
+        {
+            return Err(invalid("bad interior start face"));
+        }
+        let vert_p = table.vertex(CornerTable::next(corner_c));
+        let new_corner = 3 * num_decoded_faces;
+        num_decoded_faces += 1;
+        table.set_opposites(new_corner, corner);
+        table.set_opposites(new_corner + 1, corner_b);
+        table.set_opposites(new_corner + 2, corner_c);
+        table.map(new_corner, vert_x);
+        table.map(new_corner + 1, vert_p);
+        table.map(new_corner + 2, vert_n);
+        for vertex in [vert_x, vert_p, vert_n] {
+            if let Some(hole) = is_vert_hole.get_mut(vertex as usize) {
+                *hole = false;
+            }
+        }
+    }
+    if num_decoded_faces != num_faces {
+        return Err(invalid("face count mismatch"));
+    }
+    if table.vertices.contains(&INVALID) {
+        return Err(invalid("corner without a vertex"));
+    }
+
+    // Fill the holes merged vertices left by moving the last vertices down
+    let mut num_vertices = table.num_vertices();
+    for &invalid_vertex in &invalid_vertices {
+        loop {
+            if num_vertices == 0 {
+                return Err(invalid("no vertices left"));
+            }
+            if table.left_most(num_vertices as u32 - 1) != INVALID {
+                break;
+            }
+            num_vertices -= 1;
+        }
+        let source = num_vertices as u32 - 1;
+        if source < invalid_vertex {
+            continue;
+        }
+        let corners: Vec<u32> = table.corners_around(table.left_most(source)).collect();
+        for corner in corners {
+            if table.vertex(corner) != source {
+                return Err(invalid("bad vertex fan"));
+            }
+            table.map(corner, invalid_vertex);
+        }
+        table.set_left_most(invalid_vertex, table.left_most(source));
+        table.set_left_most(source, INVALID);
+        is_vert_hole[invalid_vertex as usize] = is_vert_hole[source as usize];
+        is_vert_hole[source as usize] = false;
+        num_vertices -= 1;
+    }
+
+    if num_attribute_data == 0 {
+        let faces = table
+            .vertices
+            .chunks_exact(3)
+            .map(|f| [f[0], f[1], f[2]])
+            .collect();
+        return Ok(Connectivity {
+            faces,
+            num_points: num_vertices,
+            edgebreaker: Some(Edgebreaker {
+                table,
+                attribute_tables: Vec::new(),
+            }),
+        });
+    }
+
+    // Boundary edges are always seams; interior edges carry one flag per attribute
+    let mut seams = vec![Vec::new(); num_attribute_data];
+    for corner in (0..num_corners as u32).step_by(3) {
+        for c in [corner, CornerTable::next(corner), CornerTable::prev(corner)] {
+            let opposite = table.opposite(c);
+            if opposite == INVALID {
+                seams.iter_mut().for_each(|s| s.push(c));
+            } else if opposite / 3 >= corner / 3 {
+                for (s, flags) in seams.iter_mut().zip(&mut traversal.seams) {
+                    if flags.read() {
+                        s.push(c);
+                    }
+                }
+            }
+        }
+    }
+    let attributes = seams
+        .iter()
+        .map(|s| attribute_table(&table, s))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (faces, num_points) = assign_points(&table, &attributes, &is_vert_hole)?;
+    Ok(Connectivity {
+        faces,
+        num_points,
+        edgebreaker: Some(Edgebreaker {
+            table,
+            attribute_tables: attributes.into_iter().map(|a| a.table).collect(),
+        }),
+    })
+}
+
+/// Connectivity of one attribute, split into separate vertices at its seams.
+struct AttributeTable {
+    table: CornerTable,
+    /// Whether each mesh vertex touches a seam of this attribute.
+    seam_vertices: Vec<bool>,
+}
+
+fn attribute_table(mesh: &CornerTable, seams: &[u32]) -> Result<AttributeTable, LoadError> {
+    let num_corners = mesh.vertices.len();
+    let mut seam_edges = vec![false; num_corners];
+    let mut seam_vertices = vec![false; mesh.num_vertices()];
+    let mut mark = |corner: u32| {
+        if let Some(edge) = seam_edges.get_mut(corner as usize) {
+            *edge = true;
+        }
+        for c in [CornerTable::next(corner), CornerTable::prev(corner)] {
+            if let Some(v) = seam_vertices.get_mut(mesh.vertex(c) as usize) {
+                *v = true;
+            }
+        }
+    };
+    for &corner in seams {
+        mark(corner);
+        let opposite = mesh.opposite(corner);
+        if opposite != INVALID {
+            mark(opposite);
+        }
+    }
+
+    let opposites = mesh
+        .opposites
+        .iter()
+        .zip(&seam_edges)
+        .map(|(&o, &seam)| if seam { INVALID } else { o })
+        .collect();
+    let mut table = CornerTable {
+        vertices: vec![INVALID; num_corners],
+        opposites,
+        left_most: Vec::new(),
+    };
+    for (&corner, &on_seam) in mesh.left_most.iter().zip(&seam_vertices) {
+        if corner == INVALID {
+            continue;
+        }
+        let mut vertex = table.left_most.len() as u32;
+        let mut first = corner;
+        if on_seam {
+            // Start from the corner where this attribute's fan begins
+            let mut act = table.swing_left(first);
+            let mut steps = 0;
+            while act != INVALID {
+                first = act;
+                act = table.swing_left(act);
+                steps += 1;
+                if act == corner || steps > num_corners {
+                    return Err(invalid("bad attribute seam"));
+                }
+            }
+        }
+        table.map(first, vertex);
+        table.left_most.push(first);
+        let mut act = mesh.swing_right(first);
+        let mut steps = 0;
+        while act != INVALID && act != first {
+            if seam_edges[CornerTable::next(act) as usize] {
+                vertex = table.left_most.len() as u32;
+                table.left_most.push(act);
+            }
+            table.map(act, vertex);
+            act = mesh.swing_right(act);
+            steps += 1;
+            if steps > num_corners {
+                return Err(invalid("bad vertex fan"));
+            }
+        }
+    }
+    Ok(AttributeTable {
+        table,
+        seam_vertices,
+    })
+}
+
+/// Gives each distinct combination of attribute vertices its own point.
+fn assign_points(
+    mesh: &CornerTable,
+    attributes: &[AttributeTable],
+    is_vert_hole: &[bool],
+) -> Result<(Vec<[u32; 3]>, usize), LoadError> {
+    let num_corners = mesh.vertices.len();
+    let mut corner_to_point = vec![0u32; num_corners];
+    let mut num_points = 0u32;
+    for v in 0..mesh.num_vertices() {
+        let corner = mesh.left_most[v];
+        if corner == INVALID {
+            continue;
+        }
+        // Start at a seam so the points of one fan are numbered in order
+        let mut first = corner;
+        if !is_vert_hole.get(v).copied().unwrap_or(true) {
+            for attribute in attributes {
+                if !attribute.seam_vertices[mesh.vertex(corner) as usize] {
+                    continue;
+                }
+                let vertex = attribute.table.vertex(corner);
+                let mut act = mesh.swing_right(corner);
+                let mut found = false;
+                let mut steps = 0;
+                while act != corner {
+                    if act == INVALID || steps > num_corners {
+                        return Err(invalid("bad vertex fan"));
+                    }
+                    if attribute.table.vertex(act) != vertex {
+                        first = act;
+                        found = true;
+                        break;
+                    }
+                    act = mesh.swing_right(act);
+                    steps += 1;
+                }
+                if found {
+                    break;
+                }
+            }
+        }
+
+        corner_to_point[first as usize] = num_points;
+        num_points += 1;
+        let mut prev = first;
+        let mut act = mesh.swing_right(first);
+        let mut steps = 0;
+        while act != INVALID && act != first {
+            let seam = attributes
+                .iter()
+                .any(|a| a.table.vertex(act) != a.table.vertex(prev));
+            corner_to_point[act as usize] = if seam {
+                num_points += 1;
+                num_points - 1
+            } else {
+                corner_to_point[prev as usize]
+            };
+            prev = act;
+            act = mesh.swing_right(act);
+            steps += 1;
+            if steps > num_corners {
+                return Err(invalid("bad vertex fan"));
+            }
+        }
+    }
+    let faces = corner_to_point
+        .chunks_exact(3)
+        .map(|f| [f[0], f[1], f[2]])
+        .collect();
+    Ok((faces, num_points as usize))
+}
+
+// ===========================================================================
+// Traversal sequencers
+// ===========================================================================
+
+/// Order in which one attribute decoder visits the mesh.
+enum Sequencer<'a> {
+    /// Points in index order, used by the sequential codec.
+    Linear,
+    /// Vertices in the order a walk over `table` first reaches them.
+    Traversal {
+        table: &'a CornerTable,
+        prediction_degree: bool,
+    },
+}
+
+/// Points one attribute decoder stores values for, in stream order.
+struct Sequence<'a> {
+    point_ids: Vec<u32>,
+    /// Attribute value of each point, or `None` when they coincide.
+    mapping: Option<Vec<u32>>,
+    /// Connectivity for mesh prediction schemes.
+    mesh: Option<MeshContext<'a>>,
+}
+
+/// Connectivity that mesh prediction schemes walk.
+struct MeshContext<'a> {
+    table: &'a CornerTable,
+    /// Corner through which each value was first reached.
+    value_to_corner: Vec<u32>,
+    /// Value index of each vertex of `table`.
+    vertex_to_value: Vec<u32>,
+}
+
+impl MeshContext<'_> {
+    fn value_at(&self, corner: u32) -> Option<usize> {
+        let vertex = self.table.vertex(corner);
+        self.vertex_to_value
+            .get(vertex as usize)
+            .map(|&v| v as usize)
+    }
+}
+
+impl<'a> Sequencer<'a> {
+    /// Reads the traversal an edgebreaker attribute decoder uses.
+    fn read(
+        r: &mut Reader,
+        edgebreaker: &'a Edgebreaker,
+        claimed: &mut [bool],
+        position_claimed: &mut bool,
+    ) -> Result<Self, LoadError> {
+        let data_id = r.i8()?;
+        let decoder_type = r.u8()?;
+        let method = r.u8()?;
+        let data = if data_id >= 0 {
+            let claimed = claimed
+                .get_mut(data_id as usize)
+                .filter(|c| !**c)
+                .ok_or_else(|| invalid("bad attribute data id"))?;
+            *claimed = true;
+            Some(data_id as usize)
+        } else if *position_claimed {
+            return Err(invalid("position data claimed twice"));
+        } else {
+            *position_claimed = true;
+            None
+        };
+        if method >= 2 {
+            return Err(invalid("unknown traversal method"));
+        }
+
+        // Vertex attributes follow the mesh; corner attributes their own seams
+        if decoder_type == 0 {
+            Ok(Self::Traversal {
+                table: &edgebreaker.table,
+                prediction_degree: method == 1,
+            })
+        } else {
+            let (Some(data), 0) = (data, method) else {
+                return Err(invalid("bad corner attribute traversal"));
+            };
+            Ok(Self::Traversal {
+                table: &edgebreaker.attribute_tables[data],
+                prediction_degree: false,
+            })
+        }
+    }
+
+    fn generate(&self, connectivity: &Connectivity) -> Result<Sequence<'a>, LoadError> {
+        let num_points = connectivity.num_points;
+        let &Self::Traversal {
+            table,
+            prediction_degree,
+        } = self
+        else {
+            return Ok(Sequence {
+                point_ids: (0..num_points as u32).collect(),
+                mapping: None,
+                mesh: None,
+            });
+        };
+
+        let mut traverser = Traverser::new(table, &connectivity.faces);
+        for face in 0..table.num_faces() as u32 {
+            if prediction_degree {
+                traverser.prediction_degree(3 * face)?;
+            } else {
+                traverser.depth_first(3 * face)?;
+            }
+        }
+
+        let mut mapping = vec![INVALID; num_points];
+        for (corner, &point) in connectivity.faces.iter().flatten().enumerate() {
+            let vertex = table.vertex(corner as u32);
+            let entry = traverser
+                .vertex_to_value
+                .get(vertex as usize)
+                .copied()
+                .ok_or_else(|| invalid("corner without a vertex"))?;
+            if point as usize >= num_points || entry as usize >= num_points {
+                return Err(invalid("point out of range"));
+            }
+            mapping[point as usize] = entry;
+        }
+        Ok(Sequence {
+            point_ids: traverser.point_ids,
+            mapping: Some(mapping),
+            mesh: Some(MeshContext {
+                table,
+                value_to_corner: traverser.value_to_corner,
+                vertex_to_value: traverser.vertex_to_value,
+            }),
+        })
+    }
+}
+
+/// Walks a corner table, numbering vertices in the order they are reached.
+struct Traverser<'a> {
+    table: &'a CornerTable,
+    faces: &'a [[u32; 3]],
+    face_visited: Vec<bool>,
+    vertex_visited: Vec<bool>,
+    /// Number of times each unvisited vertex was offered as a next corner.
+    degree: Vec<u32>,
+    point_ids: Vec<u32>,
+    value_to_corner: Vec<u32>,
+    vertex_to_value: Vec<u32>,
+}
+
+impl<'a> Traverser<'a> {
+    fn new(table: &'a CornerTable, faces: &'a [[u32; 3]]) -> Self {
+        Self {
+            table,
+            faces,
+            face_visited: vec![false; table.num_faces()],
+            vertex_visited: vec![false; table.num_vertices()],
+            degree: vec![0; table.num_vertices()],
+            point_ids: Vec::new(),
+            value_to_corner: Vec::new(),
+            vertex_to_value: vec![0; table.num_vertices()],
+        }
+    }
+
+    fn is_face_visited(&self, corner: u32) -> bool {
+        self.face_visited
+            .get(corner as usize / 3)
+            .copied()
+            .unwrap_or(true)
+    }
+
+    fn is_vertex_visited(&self, vertex: u32) -> Result<bool, LoadError> {
+        self.vertex_visited
+            .get(vertex as usize)
+            .copied()
+            .ok_or_else(|| invalid("corner without a vertex"))
+    }
+
+    /// Gives the vertex at `corner` the next value index, unless it has one.
+    fn visit(&mut self, corner: u32) -> Result<(), LoadError> {
+        let vertex = self.table.vertex(corner);
+        if self.is_vertex_visited(vertex)? {
+            return Ok(());
+        }
+        self.vertex_visited[vertex as usize] = true;
+        let face = self
+            .faces
+            .get(corner as usize / 3)
+            .ok_or_else(|| invalid("bad corner"))?;
+        self.point_ids.push(face[corner as usize % 3]);
+        self.vertex_to_value[vertex as usize] = self.value_to_corner.len() as u32;
+        self.value_to_corner.push(corner);
+        Ok(())
+    }
+
+    fn depth_first(&mut self, start: u32) -> Result<(), LoadError> {
+        if self.is_face_visited(start) {
+            return Ok(());
+        }
+        self.visit(CornerTable::next(start))?;
+        self.visit(CornerTable::prev(start))?;
+        let mut stack = vec![start];
+        while let Some(&top) = stack.last() {
+            if self.is_face_visited(top) {
+                stack.pop();
+                continue;
+            }
+            let mut corner = top;
+            loop {
+                self.face_visited[corner as usize / 3] = true;
+                let vertex = self.table.vertex(corner);
+                if !self.is_vertex_visited(vertex)? {
+                    let on_boundary = self.table.is_on_boundary(vertex);
+                    self.visit(corner)?;
+                    if !on_boundary {
+                        corner = self.table.right_corner(corner);
+                        if corner == INVALID {
+                            return Err(invalid("bad corner table"));
+                        }
+                        continue;
+                    }
+                }
+                let right = self.table.right_corner(corner);
+                let left = self.table.left_corner(corner);
+                match (self.is_face_visited(right), self.is_face_visited(left)) {
+                    (true, true) => {
+                        stack.pop();
+                        break;
+                    }
+                    (true, false) => corner = left,
+                    (false, true) => corner = right,
+                    (false, false) => {
+                        *stack.last_mut().unwrap() = left;
+                        stack.push(right);
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Traverses preferring corners whose tip vertex many faces already predict.
+    fn prediction_degree(&mut self, start: u32) -> Result<(), LoadError> {
+        if self.degree.is_empty() {
+            return Ok(());
+        }
+        let mut stacks: [Vec<u32>; 3] = Default::default();
+        stacks[0].push(start);
+        let mut best = 0;
+        self.visit(CornerTable::next(start))?;
+        self.visit(CornerTable::prev(start))?;
+        self.visit(start)?;
+
+        while let Some(priority) = (best..3).find(|&i| !stacks[i].is_empty()) {
+            best = priority;
+            let mut corner = stacks[priority].pop().unwrap();
+            if self.is_face_visited(corner) {
+                continue;
+            }
+            loop {
+                self.face_visited[corner as usize / 3] = true;
+                self.visit(corner)?;
+                let right = self.table.right_corner(corner);
+                let left = self.table.left_corner(corner);
+                let right_visited = self.is_face_visited(right);
+                if !self.is_face_visited(left) {
+                    let priority = self.priority(left)?;
+                    if right_visited && priority <= best {
+                        corner = left;
+                        continue;
+                    }
+                    stacks[priority].push(left);
+                    best = best.min(priority);
+                }
+                if !right_visited {
+                    let priority = self.priority(right)?;
+                    if priority <= best {
+                        corner = right;
+                        continue;
+                    }
+                    stacks[priority].push(right);
+                    best = best.min(priority);
+                }
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn priority(&mut self, corner: u32) -> Result<usize, LoadError> {
+        let tip = self.table.vertex(corner);
+        if self.is_vertex_visited(tip)? {
+            return Ok(0);
+        }
+        let degree = &mut self.degree[tip as usize];
+        *degree += 1;
+        Ok(if *degree > 1 { 1 } else { 2 })
+    }
+}
+
+// ===========================================================================
+// Attributes
+// ===========================================================================
+
+/// How an attribute's values are stored.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Coding {
+    /// Raw values of the attribute's data type.
+    Generic,
+    /// Entropy-coded integers.
+    Integer,
+    /// Floats quantized to integers.
+    Quantized,
+    /// Unit vectors quantized to two octahedral coordinates.
+    Normal,
+}
+
+/// An attribute as declared in the stream, filled in as it is decoded.
+struct PointAttribute {
+    kind: u8,
+    data_type: u8,
+    components: usize,
+    normalized: bool,
+    unique_id: u32,
+    coding: Coding,
+    mapping: Option<Vec<u32>>,
+    /// Integer values before dequantization, which later predictions may use.
+    portable: Option<Vec<i32>>,
+    values: Vec<f32>,
+}
+
+impl PointAttribute {
+    fn portable_components(&self) -> usize {
+        if self.coding == Coding::Normal {
+            2
+        } else {
+            self.components
+        }
+    }
+
+    /// Lays the values out per point and converts them to [`Attribute`].
+    fn expand(self, num_points: usize) -> Result<Attribute, LoadError> {
+        let n = self.components;
+        let mut values = Vec::with_capacity(num_points * n);
+        for point in 0..num_points {
+            let index = match &self.mapping {
+                Some(mapping) => mapping[point] as usize,
+                None => point,
+            };
+            let value = self
+                .values
+                .get(index * n..(index + 1) * n)
+                .ok_or_else(|| invalid("point without a value"))?;
+            values.extend_from_slice(value);
+        }
+        if self.normalized {
+            let (max, signed) = match self.data_type {
+                INT8 => (i8::MAX as f32, true),
+                UINT8 => (u8::MAX as f32, false),
+                INT16 => (i16::MAX as f32, true),
+                UINT16 => (u16::MAX as f32, false),
+                INT32 => (i32::MAX as f32, true),
+                UINT32 => (u32::MAX as f32, false),
+                _ => (1.0, false),
+            };
+            for v in &mut values {
+                *v /= max;
+                if signed {
+                    *v = v.max(-1.0);
+                }
+            }
+        }
+        let kind = match self.kind {
+            POSITION => AttributeKind::Position,
+            NORMAL => AttributeKind::Normal,
+            COLOR => AttributeKind::Color,
+            TEX_COORD => AttributeKind::TexCoord,
+            _ => AttributeKind::Generic,
+        };
+        Ok(Attribute {
+            unique_id: self.unique_id,
+            kind,
+            components: n,
+            values,
+        })
+    }
+}
+
+fn decode_attributes(
+    r: &mut Reader,
+    connectivity: &Connectivity,
+) -> Result<Vec<PointAttribute>, LoadError> {
+    let num_decoders = r.u8()? as usize;
+    let mut sequencers = Vec::with_capacity(num_decoders);
+    if let Some(edgebreaker) = &connectivity.edgebreaker {
+        let mut claimed = vec![false; edgebreaker.attribute_tables.len()];
+        let mut position_claimed = false;
+        for _ in 0..num_decoders {
+            sequencers.push(Sequencer::read(
+                r,
+                edgebreaker,
+                &mut claimed,
+                &mut position_claimed,
+            )?);
+        }
+    } else {
+        sequencers.resize_with(num_decoders, || Sequencer::Linear);
+    }
+
+    let mut attributes = Vec::new();
+    let mut decoders = Vec::with_capacity(num_decoders);
+    for _ in 0..num_decoders {
+        decoders.push(read_attribute_declarations(r, &mut attributes)?);
+    }
+
+    for (sequencer, ids) in sequencers.iter().zip(&decoders) {
+        let sequence = sequencer.generate(connectivity)?;
+        for &id in ids {
+            attributes[id].mapping = sequence.mapping.clone();
+        }
+        for &id in ids {
+            if attributes[id].coding == Coding::Generic {
+                attributes[id].values =
+                    read_generic_values(r, &attributes[id], sequence.point_ids.len())?;
+            } else {
+                let portable = decode_integer_values(r, &attributes, id, &sequence)?;
+                attributes[id].portable = Some(portable);
+            }
+        }
+        let transforms = ids
+            .iter()
+            .map(|&id| Dequantize::read(r, &attributes[id]))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (&id, transform) in ids.iter().zip(transforms) {
+            let attribute = &mut attributes[id];
+            if let Some(portable) = &attribute.portable {
+                attribute.values = transform.apply(portable, attribute)?;
+            }
+        }
+    }
+    Ok(attributes)
+}
+
+/// Reads the attributes one decoder declares, returning their indices.
+fn read_attribute_declarations(
+    r: &mut Reader,
+    attributes: &mut Vec<PointAttribute>,
+) -> Result<Vec<usize>, LoadError> {
+    let count = r.varint()? as usize;
+    if count == 0 || count > 5 * r.remaining() {
+        return Err(invalid("bad attribute count"));
+    }
+    let first = attributes.len();
+    for _ in 0..count {
+        let kind = r.u8()?;
+        let data_type = r.u8()?;
+        let components = r.u8()? as usize;
+        let normalized = r.u8()? > 0;
+        if kind > 4 || !(INT8..=BOOL).contains(&data_type) || components == 0 {
+            return Err(invalid("bad attribute declaration"));
+        }
+        attributes.push(PointAttribute {
+            kind,
+            data_type,
+            components,
+            normalized,
+            unique_id: r.varint()?,
+            coding: Coding::Generic,
+            mapping: None,
+            portable: None,
+            values: Vec::new(),
+        });
+    }
+    for attribute in &mut attributes[first..] {
+        attribute.coding = match r.u8()? {
+            0 => Coding::Generic,
+            1 => Coding::Integer,
+            2 if attribute.data_type == FLOAT32 => Coding::Quantized,
+            3 if attribute.data_type == FLOAT32 && attribute.components == 3 => Coding::Normal,
+            _ => return Err(invalid("unsupported attribute coding")),
+        };
+    }
+    Ok((first..attributes.len()).collect())
+}
+
+fn data_type_size(data_type: u8) -> usize {
+    match data_type {
+        INT8 | UINT8 | BOOL => 1,
+        INT16 | UINT16 => 2,
+        INT32 | UINT32 | FLOAT32 => 4,
+        INT64 | UINT64 | FLOAT64 => 8,
+        _ => unreachable!("data types are checked when declared"),
+    }
+}
+
+/// Reads values stored raw in the attribute's own data type.
+fn read_generic_values(
+    r: &mut Reader,
+    attribute: &PointAttribute,
+    count: usize,
+) -> Result<Vec<f32>, LoadError> {
+    let size = data_type_size(attribute.data_type);
+    let len = count
+        .checked_mul(attribute.components * size)
+        .ok_or_else(|| invalid("too many values"))?;
+    let bytes = r.bytes(len)?;
+    Ok(bytes
+        .chunks_exact(size)
+        .map(|b| match attribute.data_type {
+            INT8 => b[0] as i8 as f32,
+            UINT8 | BOOL => b[0] as f32,
+            INT16 => i16::from_le_bytes([b[0], b[1]]) as f32,
+            UINT16 => u16::from_le_bytes([b[0], b[1]]) as f32,
+            INT32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            UINT32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            FLOAT32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            INT64 => i64::from_le_bytes(b.try_into().unwrap()) as f32,
+            UINT64 => u64::from_le_bytes(b.try_into().unwrap()) as f32,
+            _ => f64::from_le_bytes(b.try_into().unwrap()) as f32,
+        })
+        .collect())
+}
+
+/// Converts decoded integers back to the attribute's final values.
+enum Dequantize {
+    /// Integers cast to the attribute's data type.
+    Cast,
+    /// Linear mapping of each component from its quantized range.
+    Linear { min: Vec<f32>, step: f32 },
+    /// Octahedral coordinates mapped back to unit vectors.
+    Octahedral(Octahedron),
+}
+
+impl Dequantize {
+    fn read(r: &mut Reader, attribute: &PointAttribute) -> Result<Self, LoadError> {
+        match attribute.coding {
+            Coding::Generic | Coding::Integer => Ok(Self::Cast),
+            Coding::Quantized => {
+                let min = (0..attribute.components)
+                    .map(|_| r.f32())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let range = r.f32()?;
+                let bits = r.u8()? as u32;
+                if !(1..=30).contains(&bits) {
+                    return Err(invalid("bad quantization bits"));
+                }
+                Ok(Self::Linear {
+                    min,
+                    step: range / ((1u32 << bits) - 1) as f32,
+                })
+            }
+            Coding::Normal => Ok(Self::Octahedral(Octahedron::new(r.u8()? as u32)?)),
+        }
+    }
+
+    fn apply(&self, portable: &[i32], attribute: &PointAttribute) -> Result<Vec<f32>, LoadError> {
+        match self {
+            Self::Cast => portable
+                .iter()
+                .map(|&v| match attribute.data_type {
+                    INT8 => Ok(v as i8 as f32),
+                    UINT8 => Ok(v as u8 as f32),
+                    INT16 => Ok(v as i16 as f32),
+                    UINT16 => Ok(v as u16 as f32),
+                    INT32 => Ok(v as f32),
+                    UINT32 => Ok(v as u32 as f32),
+                    _ => Err(invalid("integer coding of a non-integer attribute")),
+                })
+                .collect(),
+            Self::Linear { min, step } => Ok(portable
+                .chunks_exact(min.len())
+                .flat_map(|v| v.iter().zip(min).map(|(&q, &min)| q as f32 * step + min))
+                .collect()),
+            Self::Octahedral(octahedron) => Ok(portable
+                .chunks_exact(2)
+                .flat_map(|st| octahedron.to_unit_vector(st[0], st[1]))
+                .collect()),
+        }
+    }
+}
+
+/// Prediction scheme applied to an attribute's integer values.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Predictor {
+    /// Each value predicts the next one.
+    Delta,
+    /// The triangle across the opposite edge completes a parallelogram.
+    Parallelogram,
+    /// Several parallelograms around a vertex, minus flagged crease edges.
+    MultiParallelogram,
+    /// Texture coordinates projected from the triangle's positions.
+    TexCoords,
+    /// Normals predicted from the surrounding triangles' positions.
+    GeometricNormal,
+}
+
+impl Predictor {
+    fn select(method: i8, mesh: bool, normals: bool) -> Result<Self, LoadError> {
+        if !mesh {
+            return Ok(Self::Delta);
+        }
+        Ok(match (method, normals) {
+            (6, true) => Self::GeometricNormal,
+            (_, true) => Self::Delta,
+            (1, _) => Self::Parallelogram,
+            (4, _) => Self::MultiParallelogram,
+            (5, _) => Self::TexCoords,
+            (2 | 3 | 6, _) => return Err(invalid("unsupported prediction scheme")),
+            _ => Self::Delta,
+        })
+    }
+}
+
+/// Integer positions that texture coordinate and normal predictions build on.
+struct Positions<'a> {
+    values: &'a [i32],
+    mapping: Option<&'a [u32]>,
+}
+
+impl<'a> Positions<'a> {
+    fn find(attributes: &'a [PointAttribute]) -> Result<Self, LoadError> {
+        let position = attributes
+            .iter()
+            .find(|a| a.kind == POSITION)
+            .ok_or_else(|| invalid("prediction needs positions"))?;
+        match &position.portable {
+            Some(values) if position.portable_components() == 3 => Ok(Self {
+                values,
+                mapping: position.mapping.as_deref(),
+            }),
+            _ => Err(invalid("prediction needs quantized positions")),
+        }
+    }
+
+    fn get(&self, point: u32) -> Result<[i64; 3], LoadError> {
+        let index = match self.mapping {
+            Some(mapping) => mapping.get(point as usize).copied().unwrap_or(INVALID),
+            None => point,
+        } as usize;
+        let v = self
+            .values
+            .get(index * 3..index * 3 + 3)
+            .ok_or_else(|| invalid("point without a position"))?;
+        Ok([v[0] as i64, v[1] as i64, v[2] as i64])
+    }
+}
+
+/// Decodes the integer values of one attribute and undoes their prediction.
+fn decode_integer_values(
+    r: &mut Reader,
+    attributes: &[PointAttribute],
+    id: usize,
+    sequence: &Sequence,
+) -> Result<Vec<i32>, LoadError> {
+    let attribute = &attributes[id];
+    let normals = attribute.coding == Coding::Normal;
+    let components = attribute.portable_components();
+
+    let method = r.i8()?;
+    if !(-2..7).contains(&method) {
+        return Err(invalid("unknown prediction scheme"));
+    }
+    let predictor = if method == -2 {
+        None
+    } else {
+        let transform = r.i8()?;
+        if !(-1..4).contains(&transform) {
+            return Err(invalid("unknown prediction transform"));
+        }
+        match (normals, transform) {
+            (true, 3) | (false, 1) => {
+                Some(Predictor::select(method, sequence.mesh.is_some(), normals)?)
+            }
+            (true, 2) => return Err(invalid("unsupported normal transform")),
+            _ => None,
+        }
+    };
+    let positions = match predictor {
+        Some(Predictor::TexCoords | Predictor::GeometricNormal) => {
+            Some(Positions::find(attributes)?)
+        }
+        _ => None,
+    };
+
+    let count = sequence
+        .point_ids
+        .len()
+        .checked_mul(components)
+        .ok_or_else(|| invalid("too many values"))?;
+    let mut values: Vec<i32> = if r.u8()? > 0 {
+        decode_symbols(r, count, components)?
+            .into_iter()
+            .map(|v| v as i32)
+            .collect()
+    } else {
+        let size = r.u8()? as usize;
+        if size > 4 {
+            return Err(invalid("bad value size"));
+        }
+        let len = count
+            .checked_mul(size)
+            .ok_or_else(|| invalid("too many values"))?;
+        let bytes = r.bytes(len)?;
+        if size == 0 {
+            vec![0; count]
+        } else {
+            bytes
+                .chunks_exact(size)
+                .map(|b| b.iter().rev().fold(0u32, |v, &b| (v << 8) | b as u32) as i32)
+                .collect()
+        }
+    };
+    if count > 0 && !(predictor.is_some() && normals) {
+        for v in &mut values {
+            let u = *v as u32;
+            *v = (u >> 1) as i32 ^ -((u & 1) as i32);
+        }
+    }
+    let Some(predictor) = predictor else {
+        return Ok(values);
+    };
+
+    // Prediction data, then the transform's own parameters
+    let mesh = sequence.mesh.as_ref();
+    let mut creases: [Vec<bool>; 4] = Default::default();
+    let mut orientations = Vec::new();
+    if predictor == Predictor::MultiParallelogram {
+        let num_corners = mesh.map_or(0, |m| m.table.vertices.len());
+        for flags in &mut creases {
+            let count = r.varint()? as usize;
+            if count > num_corners {
+                return Err(invalid("too many crease flags"));
+            }
+            if count > 0 {
+                let mut decoder = BitDecoder::new(r)?;
+                *flags = (0..count).map(|_| decoder.read()).collect();
+            }
+        }
+    } else if predictor == Predictor::TexCoords {
+        let count = r.i32()?;
+        if count < 0 || count as usize > sequence.point_ids.len() {
+            return Err(invalid("bad orientation count"));
+        }
+        let mut decoder = BitDecoder::new(r)?;
+        let mut last = true;
+        for _ in 0..count {
+            if !decoder.read() {
+                last = !last;
+            }
+            orientations.push(last);
+        }
+    }
+    let transform = Transform::read(r, normals)?;
+    let flips = match predictor {
+        Predictor::GeometricNormal => Some(BitDecoder::new(r)?),
+        _ => None,
+    };
+    if count == 0 {
+        return Ok(values);
+    }
+
+    match (predictor, mesh) {
+        (Predictor::Parallelogram, Some(mesh)) => {
+            predict_parallelogram(mesh, &transform, &mut values, components);
+        }
+        (Predictor::MultiParallelogram, Some(mesh)) => {
+            predict_multi_parallelogram(mesh, &transform, &mut values, components, &creases)?;
+        }
+        (Predictor::TexCoords, Some(mesh)) => {
+            if components != 2 {
+                return Err(invalid(
+                    "texture coordinate prediction needs two components",
+                ));
+            }
+            let positions = positions.unwrap();
+            for (p, &corner) in mesh.value_to_corner.iter().enumerate() {
+                let prediction = predict_tex_coord(
+                    mesh,
+                    &positions,
+                    &sequence.point_ids,
+                    &values,
+                    p,
+                    corner,
+                    &mut orientations,
+                )?;
+                transform.restore(&mut values, p * 2, &prediction);
+            }
+        }
+        (Predictor::GeometricNormal, Some(mesh)) => {
+            let (Transform::Octahedral(octahedron), Some(mut flips)) = (&transform, flips) else {
+                return Err(invalid("normal prediction needs an octahedral transform"));
+            };
+            let positions = positions.unwrap();
+            for (p, &corner) in mesh.value_to_corner.iter().enumerate() {
+                let mut normal = predict_normal(mesh, &positions, &sequence.point_ids, corner)?;
+                octahedron.canonicalize_vector(&mut normal);
+                if flips.read() {
+                    normal = normal.map(i32::wrapping_neg);
+                }
+                let prediction = octahedron.vector_to_coords(normal);
+                transform.restore(&mut values, p * 2, &prediction);
+            }
+        }
+        _ => predict_delta(&transform, &mut values, components),
+    }
+    Ok(values)
+}
+
+// ===========================================================================
+// Prediction transforms
+// ===========================================================================
+
+/// Combines a prediction with a stored correction.
+enum Transform {
+    /// Corrections wrap around within the value range.
+    Wrap { min: i32, max: i32, max_dif: i32 },
+    /// Corrections of octahedral normal coordinates.
+    Octahedral(Octahedron),
+}
+
+impl Transform {
+    fn read(r: &mut Reader, normals: bool) -> Result<Self, LoadError> {
+        if normals {
+            let max_quantized = r.i32()?;
+            let _center = r.i32()?;
+            if max_quantized <= 0 || max_quantized % 2 == 0 {
+                return Err(invalid("bad normal quantization"));
+            }
+            return Ok(Self::Octahedral(Octahedron::new(
+                32 - max_quantized.leading_zeros(),
+            )?));
+        }
+        let min = r.i32()?;
+        let max = r.i32()?;
+        let dif = max as i64 - min as i64;
+        if dif < 0 || dif >= i32::MAX as i64 {
+            return Err(invalid("bad value range"));
+        }
+        Ok(Self::Wrap {
+            min,
+            max,
+            max_dif: dif as i32 + 1,
+        })
+    }
+
+    /// Replaces the correction at `offset` with the value it encodes.
+    fn restore(&self, values: &mut [i32], offset: usize, prediction: &[i32]) {
+        match *self {
+            Self::Wrap { min, max, max_dif } => {
+                for (v, &p) in values[offset..offset + prediction.len()]
+                    .iter_mut()
+                    .zip(prediction)
+                {
+                    let mut value = p.clamp(min, max).wrapping_add(*v);
+                    if value > max {
+                        value = value.wrapping_sub(max_dif);
+                    } else if value < min {
+                        value = value.wrapping_add(max_dif);
+                    }
+                    *v = value;
+                }
+            }
+            Self::Octahedral(octahedron) => {
+                let correction = [values[offset], values[offset + 1]];
+                let value = octahedron.restore([prediction[0], prediction[1]], correction);
+                values[offset..offset + 2].copy_from_slice(&value);
+            }
+        }
+    }
+}
+
+/// Octahedral encoding of unit vectors at one quantization depth.
+#[derive(Clone, Copy)]
+struct Octahedron {
+    max_quantized: i32,
+    max: i32,
+    center: i32,
+    scale: f32,
+}
+
+impl Octahedron {
+    fn new(bits: u32) -> Result<Self, LoadError> {
+        if !(2..=30).contains(&bits) {
+            return Err(invalid("bad normal quantization bits"));
+        }
+        let max_quantized = (1 << bits) - 1;
+        let max = max_quantized - 1;
+        Ok(Self {
+            max_quantized,
+            max,
+            center: max / 2,
+            scale: 2.0 / max as f32,
+        })
+    }
+
+    fn to_unit_vector(self, s: i32, t: i32) -> [f32; 3] {
+        let mut y = s as f32 * self.scale - 1.0;
+        let mut z = t as f32 * self.scale - 1.0;
+        let x = 1.0 - y.abs() - z.abs();
+        let offset = (-x).max(0.0);
+        y += if y < 0.0 { offset } else { -offset };
+        z += if z < 0.0 { offset } else { -offset };
+        let norm_squared = x * x + y * y + z * z;
+        if norm_squared < 1e-6 {
+            return [0.0; 3];
+        }
+        let d = 1.0 / norm_squared.sqrt();
+        [x * d, y * d, z * d]
+    }
+
+    /// Scales an integer vector so its absolute components sum to `center`.
+    fn canonicalize_vector(&self, v: &mut [i32; 3]) {
+        let sum: i64 = v.iter().map(|c| c.unsigned_abs() as i64).sum();
+        if sum == 0 {
+            v[0] = self.center;
+            return;
+        }
+        v[0] = (v[0] as i64 * self.center as i64 / sum) as i32;
+        v[1] = (v[1] as i64 * self.center as i64 / sum) as i32;
+        let rest = self.center - v[0].abs() - v[1].abs();
+        v[2] = if v[2] >= 0 { rest } else { -rest };
+    }
+
+    fn vector_to_coords(&self, v: [i32; 3]) -> [i32; 2] {
+        let (s, t) = if v[0] >= 0 {
+            (v[1] + self.center, v[2] + self.center)
+        } else {
+            (
+                if v[1] < 0 {
+                    v[2].abs()
+                } else {
+                    self.max - v[2].abs()
+                },
+                if v[2] < 0 {
+                    v[1].abs()
+                } else {
+                    self.max - v[1].abs()
+                },
+            )
+        };
+        self.canonicalize_coords(s, t)
+    }
+
+    /// Picks one representation for points on the octahedron's edges.
+    fn canonicalize_coords(&self, s: i32, t: i32) -> [i32; 2] {
+        let (max, center) = (self.max, self.center);
+        if (s == 0 && (t == 0 || t == max)) || (s == max && t == 0) {
+            [max, max]
+        } else if s == 0 && t > center {
+            [s, center - (t - center)]
+        } else if s == max && t < center {
+            [s, center + (center - t)]
+        } else if t == max && s < center {
+            [center + (center - s), t]
+        } else if t == 0 && s > center {
+            [center - (s - center), t]
+        } else {
+            [s, t]
+        }
+    }
+
+    fn restore(&self, prediction: [i32; 2], correction: [i32; 2]) -> [i32; 2] {
+        let mut pred = prediction.map(|p| p.wrapping_sub(self.center));
+        let in_diamond = pred[0].unsigned_abs() + pred[1].unsigned_abs() <= self.center as u32;
+        if !in_diamond {
+            pred = self.invert_diamond(pred);
+        }
+        let bottom_left = (pred[0] == 0 && pred[1] == 0) || (pred[0] < 0 && pred[1] <= 0);
+        let rotation = match (pred[0].signum(), pred[1].signum()) {
+            (0, 0) => 0,
+            (0, 1) => 3,
+            (0, _) => 1,
+            (1, -1) => 1,
+            (1, _) => 2,
+            (_, 1) => 3,
+            _ => 0,
+        };
+        if !bottom_left {
+            pred = rotate(pred, rotation);
+        }
+        let mut value = [
+            self.mod_max(pred[0].wrapping_add(correction[0])),
+            self.mod_max(pred[1].wrapping_add(correction[1])),
+        ];
+        if !bottom_left {
+            value = rotate(value, (4 - rotation) % 4);
+        }
+        if !in_diamond {
+            value = self.invert_diamond(value);
+        }
+        value.map(|v| v.wrapping_add(self.center))
+    }
+
+    /// Mirrors points between the inner diamond and the outer triangles.
+    fn invert_diamond(&self, [s, t]: [i32; 2]) -> [i32; 2] {
+        let (sign_s, sign_t) = if s >= 0 && t >= 0 {
+            (1, 1)
+        } else if s <= 0 && t <= 0 {
+            (-1, -1)
+        } else {
+            (if s > 0 { 1 } else { -1 }, if t > 0 { 1 } else { -1 })
+        };
+        let corner_s = (sign_s * self.center) as u32;
+        let corner_t = (sign_t * self.center) as u32;
+        let mut us = (s as u32).wrapping_mul(2).wrapping_sub(corner_s);
+        let mut ut = (t as u32).wrapping_mul(2).wrapping_sub(corner_t);
+        if sign_s * sign_t >= 0 {
+            (us, ut) = (ut.wrapping_neg(), us.wrapping_neg());
+        } else {
+            (us, ut) = (ut, us);
+        }
+        us = us.wrapping_add(corner_s);
+        ut = ut.wrapping_add(corner_t);
+        [us as i32 / 2, ut as i32 / 2]
+    }
+
+    fn mod_max(&self, x: i32) -> i32 {
+        if x > self.center {
+            x.wrapping_sub(self.max_quantized)
+        } else if x < -self.center {
+            x.wrapping_add(self.max_quantized)
+        } else {
+            x
+        }
+    }
+}
+
+fn rotate([x, y]: [i32; 2], count: u32) -> [i32; 2] {
+    match count {
+        1 => [y, x.wrapping_neg()],
+        2 => [x.wrapping_neg(), y.wrapping_neg()],
+        3 => [y.wrapping_neg(), x],
+        _ => [x, y],
+    }
+}
+
+// ===========================================================================
+// Prediction schemes
+// ===========================================================================
+
+fn predict_delta(transform: &Transform, values: &mut [i32], components: usize) {
+    let mut prediction = vec![0; components];
+    transform.restore(values, 0, &prediction);
+    for offset in (components..values.len()).step_by(components) {
+        prediction.copy_from_slice(&values[offset - components..offset]);
+        transform.restore(values, offset, &prediction);
+    }
+}
+
+/// Predicts the value at the tip of `corner` from the triangle across from it.
+fn parallelogram(
+    mesh: &MeshContext,
+    p: usize,
+    corner: u32,
+    values: &[i32],
+    components: usize,
+    prediction: &mut [i32],
+) -> bool {
+    let opposite = mesh.table.opposite(corner);
+    if opposite == INVALID {
+        return false;
+    }
+    let entries = (
+        mesh.value_at(opposite),
+        mesh.value_at(CornerTable::next(opposite)),
+        mesh.value_at(CornerTable::prev(opposite)),
+    );
+    let (Some(opp), Some(next), Some(prev)) = entries else {
+        return false;
+    };
+    if opp >= p || next >= p || prev >= p {
+        return false;
+    }
+    for (c, out) in prediction.iter_mut().enumerate() {
+        let next = values[next * components + c] as i64;
+        let prev = values[prev * components + c] as i64;
+        let opp = values[opp * components + c] as i64;
+        *out = (next + prev - opp) as i32;
+    }
+    true
+}
+
+fn predict_parallelogram(
+    mesh: &MeshContext,
+    transform: &Transform,
+    values: &mut [i32],
+    components: usize,
+) {
+    let mut prediction = vec![0; components];
+    transform.restore(values, 0, &prediction);
+    for (p, &corner) in mesh.value_to_corner.iter().enumerate().skip(1) {
+        let offset = p * components;
+        if !parallelogram(mesh, p, corner, values, components, &mut prediction) {
+            prediction.copy_from_slice(&values[offset - components..offset]);
+        }
+        transform.restore(values, offset, &prediction);
+    }
+}
+
+fn predict_multi_parallelogram(
+    mesh: &MeshContext,
+    transform: &Transform,
+    values: &mut [i32],
+    components: usize,
+    creases: &[Vec<bool>; 4],
+) -> Result<(), LoadError> {
+    let mut predictions = vec![vec![0; components]; 4];
+    let mut prediction = vec![0; components];
+    let mut crease_positions = [0usize; 4];
+    transform.restore(values, 0, &prediction);
+
+    for (p, &start) in mesh.value_to_corner.iter().enumerate().skip(1) {
+        // Collect up to four parallelograms around the vertex
+        let mut count = 0;
+        let mut first_pass = true;
+        let mut corner = start;
+        let mut steps = 0;
+        while corner != INVALID && steps <= mesh.table.vertices.len() {
+            steps += 1;
+            if parallelogram(mesh, p, corner, values, components, &mut predictions[count]) {
+                count += 1;
+                if count == 4 {
+                    break;
+                }
+            }
+            corner = if first_pass {
+                mesh.table.swing_left(corner)
+            } else {
+                mesh.table.swing_right(corner)
+            };
+            if corner == start {
+                break;
+            }
+            if corner == INVALID && first_pass {
+                first_pass = false;
+                corner = mesh.table.swing_right(start);
+            }
+        }
+
+        let mut used = 0;
+        prediction.fill(0);
+        if count > 0 {
+            let context = count - 1;
+            for parallelogram in &predictions[..count] {
+                let position = crease_positions[context];
+                crease_positions[context] += 1;
+                let is_crease = *creases[context]
+                    .get(position)
+                    .ok_or_else(|| invalid("missing crease flag"))?;
+                if !is_crease {
+                    used += 1;
+                    for (sum, &v) in prediction.iter_mut().zip(parallelogram) {
+                        *sum = sum.wrapping_add(v);
+                    }
+                }
+            }
+        }
+
+        let offset = p * components;
+        if used == 0 {
+            prediction.copy_from_slice(&values[offset - components..offset]);
+        } else {
+            prediction.iter_mut().for_each(|v| *v /= used);
+        }
+        transform.restore(values, offset, &prediction);
+    }
+    Ok(())
+}
+
+/// Predicts a texture coordinate by projecting the tip onto the opposite edge.
+fn predict_tex_coord(
+    mesh: &MeshContext,
+    positions: &Positions,
+    point_ids: &[u32],
+    values: &[i32],
+    p: usize,
+    corner: u32,
+    orientations: &mut Vec<bool>,
+) -> Result<[i32; 2], LoadError> {
+    let missing = || invalid("corner without a vertex");
+    let next = mesh
+        .value_at(CornerTable::next(corner))
+        .ok_or_else(missing)?;
+    let prev = mesh
+        .value_at(CornerTable::prev(corner))
+        .ok_or_else(missing)?;
+    let position = |entry: usize| positions.get(point_ids[entry]);
+    let uv = |entry: usize| [values[entry * 2] as i64, values[entry * 2 + 1] as i64];
+
+    if prev < p && next < p {
+        let n_uv = uv(next);
+        let p_uv = uv(prev);
+        if p_uv == n_uv {
+            return Ok([p_uv[0] as i32, p_uv[1] as i32]);
+        }
+        let tip_pos = position(p)?;
+        let next_pos = position(next)?;
+        let prev_pos = position(prev)?;
+        let pn = sub3(prev_pos, next_pos);
+        let pn_norm2 = dot3(pn, pn) as u64;
+        if let Some(n_uv_limit) = (i64::MAX as u64).checked_div(pn_norm2) {
+            let cn = sub3(tip_pos, next_pos);
+            let cn_dot_pn = dot3(pn, cn);
+            let pn_uv = [p_uv[0].wrapping_sub(n_uv[0]), p_uv[1].wrapping_sub(n_uv[1])];
+            let overflow = || invalid("texture coordinate prediction overflows");
+
+            let n_uv_absmax = n_uv[0].unsigned_abs().max(n_uv[1].unsigned_abs());
+            if n_uv_absmax > n_uv_limit {
+                return Err(overflow());
+            }
+            let pn_uv_absmax = pn_uv[0].wrapping_abs().max(pn_uv[1].wrapping_abs());
+            if cn_dot_pn.wrapping_abs() > i64::MAX / pn_uv_absmax {
+                return Err(overflow());
+            }
+            let norm2 = pn_norm2 as i64;
+            let x_uv = [0, 1].map(|i| {
+                n_uv[i]
+                    .wrapping_mul(norm2)
+                    .wrapping_add(cn_dot_pn.wrapping_mul(pn_uv[i]))
+            });
+            let pn_absmax = pn.iter().map(|v| v.wrapping_abs()).max().unwrap();
+            if cn_dot_pn.wrapping_abs() > i64::MAX / pn_absmax {
+                return Err(overflow());
+            }
+            let x_pos = [0, 1, 2].map(|i| {
+                next_pos[i].wrapping_add(cn_dot_pn.wrapping_mul(pn[i]).wrapping_div(norm2))
+            });
+            let cx = sub3(tip_pos, x_pos);
+            let cx_norm2 = dot3(cx, cx) as u64;
+            let norm = int_sqrt(cx_norm2.wrapping_mul(pn_norm2)) as i64;
+            let cx_uv = [
+                pn_uv[1].wrapping_mul(norm),
+                pn_uv[0].wrapping_neg().wrapping_mul(norm),
+            ];
+
+            let orientation = orientations
+                .pop()
+                .ok_or_else(|| invalid("missing orientation"))?;
+            let predicted = [0, 1].map(|i| {
+                let (x, c) = (x_uv[i] as u64, cx_uv[i] as u64);
+                let sum = if orientation {
+                    x.wrapping_add(c)
+                } else {
+                    x.wrapping_sub(c)
+                };
+                (sum as i64).wrapping_div(norm2) as i32
+            });
+            return Ok(predicted);
+        }
+    }
+
+    let source = if next < p {
+        next
+    } else if p > 0 {
+        p - 1
+    } else {
+        return Ok([0, 0]);
+    };
+    Ok([values[source * 2], values[source * 2 + 1]])
+}
+
+fn sub3(a: [i64; 3], b: [i64; 3]) -> [i64; 3] {
+    [0, 1, 2].map(|i| a[i].wrapping_sub(b[i]))
+}
+
+fn dot3(a: [i64; 3], b: [i64; 3]) -> i64 {
+    (0..3).fold(0i64, |sum, i| sum.wrapping_add(a[i].wrapping_mul(b[i])))
+}
+
+fn int_sqrt(number: u64) -> u64 {
+    if number == 0 {
+        return 0;
+    }
+    let mut act = number;
+    let mut root = 1u64;
+    while act >= 2 {
+        root *= 2;
+        act /= 4;
+    }
+    loop {
+        root = root.wrapping_add(number / root) / 2;
+        if root.wrapping_mul(root) <= number {
+            return root;
+        }
+    }
+}
+
+/// Sums the area-weighted normals of the triangles around a corner's vertex.
+fn predict_normal(
+    mesh: &MeshContext,
+    positions: &Positions,
+    point_ids: &[u32],
+    corner: u32,
+) -> Result<[i32; 3], LoadError> {
+    let position = |corner: u32| {
+        let entry = mesh
+            .value_at(corner)
+            .ok_or_else(|| invalid("corner without a vertex"))?;
+        let point = *point_ids
+            .get(entry)
+            .ok_or_else(|| invalid("bad vertex value"))?;
+        positions.get(point)
+    };
+    let center = position(corner)?;
+    let mut normal = [0i64; 3];
+    for c in mesh.table.corners_around(corner) {
+        let a = sub3(position(CornerTable::next(c))?, center);
+        let b = sub3(position(CornerTable::prev(c))?, center);
+        let cross = [
+            a[1].wrapping_mul(b[2])
+                .wrapping_sub(a[2].wrapping_mul(b[1])),
+            a[2].wrapping_mul(b[0])
+                .wrapping_sub(a[0].wrapping_mul(b[2])),
+            a[0].wrapping_mul(b[1])
+                .wrapping_sub(a[1].wrapping_mul(b[0])),
+        ];
+        for (n, c) in normal.iter_mut().zip(cross) {
+            *n = n.wrapping_add(c);
+        }
+    }
+    let abs_sum = normal.iter().fold(0i64, |sum, v| {
+        sum.saturating_add(v.unsigned_abs().min(i64::MAX as u64) as i64)
+    });
+    if abs_sum > NORMAL_BOUND {
+        let quotient = abs_sum / NORMAL_BOUND;
+        normal = normal.map(|v| v / quotient);
+    }
+    Ok(normal.map(|v| v as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The triangle (0,0,0), (1,0,0), (0,1,0) from the module example.
+    const TRIANGLE: [u8; 66] = [
+        0x44, 0x52, 0x41, 0x43, 0x4f, 0x02, 0x02, 0x01, 0x00, 0x00, 0x00, 0x01, 0x03, 0x01, 0x00,
+        0x01, 0x02, 0x01, 0x01, 0x00, 0x09, 0x03, 0x00, 0x00, 0x02, 0x00, 0x01, 0x01, 0x00, 0x03,
+        0x03, 0xad, 0x2a, 0x55, 0x15, 0x03, 0xa0, 0x7a, 0x81, 0x88, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x80, 0x3f, 0x0b,
+    ];
+
+    /// An edgebreaker-coded unit quad with UVs equal to its XY positions and
+    /// normalized byte colours.
+    const QUAD: [u8; 162] = [
+        0x44, 0x52, 0x41, 0x43, 0x4f, 0x02, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x04, 0x02, 0x02,
+        0x02, 0x00, 0x00, 0x01, 0x1f, 0xff, 0x01, 0x11, 0xff, 0x01, 0x11, 0xff, 0x01, 0x11, 0x03,
+        0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x09, 0x03, 0x00, 0x00,
+        0x02, 0x01, 0x03, 0x09, 0x02, 0x00, 0x01, 0x02, 0x01, 0x02, 0x02, 0x03, 0x01, 0x02, 0x01,
+        0x01, 0x01, 0x01, 0x00, 0x03, 0x03, 0x01, 0x30, 0x01, 0x10, 0x03, 0x00, 0x28, 0x82, 0x98,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x08, 0x01, 0x01, 0x01, 0x00,
+        0x03, 0x03, 0x01, 0x30, 0x01, 0x10, 0x03, 0x00, 0x28, 0x82, 0x2c, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x80, 0x3f, 0x08, 0x01, 0x01, 0x01, 0x00, 0x03, 0x03, 0x01, 0x30, 0x01, 0x10, 0x03, 0x00,
+        0x24, 0x82, 0x91, 0x26, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn test_sequential_triangle() {
+        let mesh = decode(&TRIANGLE).unwrap();
+        assert_eq!(mesh.faces, [[0, 1, 2]]);
+        assert_eq!(mesh.attributes.len(), 1);
+        let position = &mesh.attributes[0];
+        assert_eq!(position.components, 3);
+        assert_eq!(
+            position.values,
+            [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_edgebreaker_quad_with_uvs_and_colors() {
+        let mesh = decode(&QUAD).unwrap();
+        assert_eq!(mesh.faces.len(), 2);
+        let position = mesh.attribute(0).unwrap();
+        let uv = mesh.attribute(1).unwrap();
+        let color = mesh.attribute(2).unwrap();
+        assert_eq!(
+            (uv.kind, color.kind),
+            (AttributeKind::TexCoord, AttributeKind::Color)
+        );
+        assert_eq!(position.values.len(), 4 * 3);
+
+        // Every corner of the square appears once, with matching UV and colour
+        let mut corners = Vec::new();
+        for &i in mesh.faces.iter().flatten() {
+            let i = i as usize;
+            let p = &position.values[i * 3..i * 3 + 3];
+            assert_eq!(&uv.values[i * 2..i * 2 + 2], &p[..2]);
+            corners.push((
+                p[0] as u8,
+                p[1] as u8,
+                color.values[i * 3..i * 3 + 3].to_vec(),
+            ));
+        }
+        corners.sort_by_key(|c| (c.0, c.1));
+        corners.dedup();
+        assert_eq!(
+            corners,
+            [
+                (0, 0, vec![1.0, 0.0, 0.0]),
+                (0, 1, vec![1.0, 1.0, 1.0]),
+                (1, 0, vec![0.0, 1.0, 0.0]),
+                (1, 1, vec![0.0, 0.0, 1.0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_truncated_streams_are_rejected() {
+        for len in 0..TRIANGLE.len() {
+            assert!(
+                decode(&TRIANGLE[..len]).is_err(),
+                "prefix of {} bytes decoded",
+                len
+            );
+        }
+        for len in 0..QUAD.len() {
+            // Only checks for panics, as the last block's trailing zeros are optional
+            let _ = decode(&QUAD[..len]);
+        }
+    }
+
+    #[test]
+    fn test_octahedron_round_trip() {
+        let octahedron = Octahedron::new(8).unwrap();
+        for v in [
+            [1.0f32, 0.0, 0.0],
+            [0.0, -1.0, 0.0],
+            [0.0, 0.6, -0.8],
+            [-0.48, 0.6, -0.64],
+        ] {
+            let mut scaled = v.map(|c| (c * 1000.0) as i32);
+            octahedron.canonicalize_vector(&mut scaled);
+            let [s, t] = octahedron.vector_to_coords(scaled);
+            let decoded = octahedron.to_unit_vector(s, t);
+            for (a, b) in decoded.iter().zip(v) {
+                assert!((a - b).abs() < 0.02, "{:?} decoded as {:?}", v, decoded);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_other_versions_and_point_clouds() {
+        let mut old = TRIANGLE;
+        old[6] = 1;
+        assert!(decode(&old).is_err());
+        let mut cloud = TRIANGLE;
+        cloud[7] = 0;
+        assert!(decode(&cloud).is_err());
+    }
+}
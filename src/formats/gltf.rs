@@ -1,6 +1,14 @@
 //! Provides a glTF/GLB format loader.
 //!
 //! Supports both binary GLB and JSON glTF files with embedded or external resources.
+//! Vertex normals give smooth shading. Morph targets and skins deform meshes
+//! and their normals, and an animation can be sampled at a chosen time
+//! through [`LoadOptions::animation`].
+//! Geometry compressed with `EXT_meshopt_compression` is decoded by
+//! [`meshopt`](super::meshopt), and `KHR_mesh_quantization` integer attributes
+//! are dequantized. `KHR_draco_mesh_compression` primitives are decoded by
+//! [`draco`](super::draco) when the `draco` feature is enabled, and rejected
+//! with a clear error otherwise.
 //!
 //! # Examples
 //! ```
@@ -13,9 +21,22 @@
 use std::path::Path;
 use std::sync::Arc;
 
+use glam::{Mat3 as GlamMat3, Mat4 as GlamMat4, Quat, Vec3 as GlamVec3, Vec4 as GlamVec4};
+use gltf::accessor::{self, util::Item, DataType};
+use gltf::animation::util::ReadOutputs;
+use gltf::animation::{Interpolation, Property};
+
+use super::resolver::{self, DirectoryResolver, ResourceResolver};
+use super::shared::texture::{decode_texture, load_texture_from_data_url};
 use super::{
-    FormatLoader, LoadError, LoadResult, Mat4, ModelData, TextureData, Triangle, Vec2, Vec3,
+    meshopt, AlphaMode, AnimationPose, Camera, FormatLoader, Light, LightKind, LoadError,
+    LoadOptions, LoadResult, Mat4, Material, Mesh, ModelData, Node, Projection, RenderMode,
+    SceneChoice, TextureData, Vec2, Vec3, WrapMode,
 };
+use crate::budget::Budget;
+
+#[cfg(feature = "draco")]
+use super::draco;
 
 /// The glTF format loader.
 ///
@@ -66,234 +87,1163 @@ impl FormatLoader for GltfLoader {
     }
 
     fn load_from_bytes(&self, data: &[u8]) -> LoadResult {
-        // Try the standard import first (works for GLB and fully-embedded glTF)
-        if let Ok((document, buffers, images)) = gltf::import_slice(data) {
-            return load_from_gltf(document, buffers, images);
+        self.load_from_bytes_with_options(data, &LoadOptions::default())
+    }
+
+    fn load_from_path(&self, path: &Path) -> LoadResult {
+        self.load_from_path_with_options(path, &LoadOptions::default())
+    }
+
+    fn load_from_bytes_with_options(&self, data: &[u8], options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
+        let resolver = options.resolver.as_deref();
+
+        // Try the standard import first (works for GLB, fully-embedded glTF
+        // and external files the resolver can read)
+        if let Ok((document, buffers)) = import_slice(data, resolver) {
+            return load_from_gltf(document, buffers, resolver, options);
         }
 
-        // Fall back to lenient parsing for JSON glTF with external references
-        let gltf_data = gltf::Gltf::from_slice(data)
-            .map_err(|e| LoadError::InvalidData(format!("Failed to parse glTF: {}", e)))?;
+        // Fall back to lenient parsing, leaving unreadable buffers empty
+        let gltf_data = parse_gltf(data)?;
         let document = gltf_data.document;
+        let mut blob = gltf_data.blob;
 
-        // Try to load embedded buffers (data URIs)
         let mut buffers: Vec<gltf::buffer::Data> = Vec::new();
         for buffer in document.buffers() {
-            match buffer.source() {
-                gltf::buffer::Source::Bin => {
-                    if let Some(blob) = gltf_data.blob.as_ref() {
-                        buffers.push(gltf::buffer::Data(blob.clone()));
-                    }
-                }
-                gltf::buffer::Source::Uri(uri) => {
-                    if let Some(data) = decode_data_uri(uri) {
-                        buffers.push(gltf::buffer::Data(data));
-                    } else {
-                        buffers.push(gltf::buffer::Data(Vec::new()));
-                    }
-                }
+            if is_meshopt_fallback(&buffer) {
+                buffers.push(gltf::buffer::Data(Vec::new()));
+                continue;
             }
+            let data = read_buffer(&buffer, resolver, &mut blob).unwrap_or_default();
+            buffers.push(gltf::buffer::Data(data));
         }
 
-        // Try to load embedded images (data URIs)
-        let mut images: Vec<gltf::image::Data> = Vec::new();
-        for image in document.images() {
-            match image.source() {
-                gltf::image::Source::View { view, mime_type: _ } => {
-                    let buffer_index = view.buffer().index();
-                    if buffer_index < buffers.len() && !buffers[buffer_index].0.is_empty() {
-                        let start = view.offset();
-                        let end = start + view.length();
-                        if end <= buffers[buffer_index].0.len() {
-                            let img_data = &buffers[buffer_index].0[start..end];
-                            if let Some(img) = decode_image_data(img_data) {
-                                images.push(img);
-                                continue;
-                            }
-                        }
-                    }
-                }
-                gltf::image::Source::Uri { uri, mime_type: _ } => {
-                    if let Some(data) = decode_data_uri(uri) {
-                        if let Some(img) = decode_image_data(&data) {
-                            images.push(img);
-                            continue;
-                        }
-                    }
+        load_from_gltf(document, buffers, resolver, options)
+    }
+
+    fn load_from_path_with_options(&self, path: &Path, options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
+        let directory = DirectoryResolver::for_model(path);
+        let resolver = options.resolver.as_deref().unwrap_or(&directory);
+        let (document, buffers) = import(path, resolver)?;
+        load_from_gltf(document, buffers, Some(resolver), options)
+    }
+}
+
+/// Scenes and material variants offered by a glTF document.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::gltf;
+///
+/// let json = br#"{"asset": {"version": "2.0"}, "scenes": [{"name": "Main", "nodes": []}, {"nodes": []}]}"#;
+/// let contents = gltf::contents(json).unwrap();
+/// assert_eq!(contents.scenes, vec![Some("Main".to_string()), None]);
+/// assert_eq!(contents.default_scene, Some(0));
+/// assert!(contents.variants.is_empty());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Contents {
+    /// Scene names in index order; unnamed scenes are `None`.
+    pub scenes: Vec<Option<String>>,
+    /// Index of the scene loaded when no scene is requested.
+    pub default_scene: Option<usize>,
+    /// Names of the `KHR_materials_variants` material variants.
+    pub variants: Vec<String>,
+}
+
+/// Lists the scenes and material variants of a glTF or GLB document without
+/// loading its buffers or images.
+///
+/// # Errors
+/// Returns [`LoadError::InvalidData`] if the document cannot be parsed.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::gltf;
+///
+/// assert!(gltf::contents(b"not gltf").is_err());
+/// ```
+pub fn contents(data: &[u8]) -> Result<Contents, LoadError> {
+    let document = parse_gltf(data)?.document;
+    let scenes: Vec<Option<String>> = document
+        .scenes()
+        .map(|scene| scene.name().map(str::to_owned))
+        .collect();
+    let default_scene = document
+        .default_scene()
+        .map(|scene| scene.index())
+        .or((!scenes.is_empty()).then_some(0));
+    let variants = document
+        .variants()
+        .into_iter()
+        .flatten()
+        .map(|variant| variant.name().to_owned())
+        .collect();
+    Ok(Contents {
+        scenes,
+        default_scene,
+        variants,
+    })
+}
+
+/// A parsed document with its loaded buffers.
+type Import = (gltf::Document, Vec<gltf::buffer::Data>);
+
+/// Extensions this loader handles itself, so files requiring them still validate.
+#[cfg(not(feature = "draco"))]
+const DECODED_EXTENSIONS: &[&str] = &["EXT_meshopt_compression", "KHR_mesh_quantization"];
+/// Extensions this loader handles itself, so files requiring them still validate.
+#[cfg(feature = "draco")]
+const DECODED_EXTENSIONS: &[&str] = &[
+    "EXT_meshopt_compression",
+    "KHR_mesh_quantization",
+    "KHR_draco_mesh_compression",
+];
+
+/// Parses a glTF or GLB document, accepting the required extensions in [`DECODED_EXTENSIONS`].
+///
+/// # Errors
+/// Returns [`LoadError::InvalidData`] for malformed documents, and for files
+/// that require Draco mesh compression when the `draco` feature is disabled.
+fn parse_gltf(data: &[u8]) -> Result<gltf::Gltf, LoadError> {
+    let parse_error =
+        |e: gltf::Error| LoadError::InvalidData(format!("Failed to parse glTF: {}", e));
+
+    let (json, blob) = if data.starts_with(b"glTF") {
+        let glb = gltf::Glb::from_slice(data).map_err(parse_error)?;
+        (glb.json, glb.bin.map(|bin| bin.into_owned()))
+    } else {
+        (std::borrow::Cow::Borrowed(data), None)
+    };
+    let mut root = gltf::json::Root::from_slice(&json)
+        .map_err(|e| parse_error(gltf::Error::Deserialize(e)))?;
+
+    #[cfg(not(feature = "draco"))]
+    if root
+        .extensions_required
+        .iter()
+        .any(|ext| ext == "KHR_draco_mesh_compression")
+    {
+        return Err(LoadError::InvalidData(
+            "Draco-compressed meshes (KHR_draco_mesh_compression) need the draco feature".into(),
+        ));
+    }
+    #[cfg(feature = "draco")]
+    borrow_draco_views(&mut root);
+    root.extensions_required
+        .retain(|ext| !DECODED_EXTENSIONS.contains(&ext.as_str()));
+
+    let document = gltf::Document::from_json(root).map_err(parse_error)?;
+    Ok(gltf::Gltf { document, blob })
+}
+
+/// Imports a glTF or GLB file from disk, reading external buffers through `resolver`.
+fn import(path: &Path, resolver: &dyn ResourceResolver) -> Result<Import, LoadError> {
+    let import_error =
+        |e: gltf::Error| LoadError::InvalidData(format!("Failed to import glTF: {}", e));
+    let data = std::fs::read(path).map_err(|e| import_error(e.into()))?;
+    import_resources(parse_gltf(&data)?, Some(resolver))
+}
+
+/// Imports a glTF or GLB file from memory; external buffers need a `resolver`.
+fn import_slice(data: &[u8], resolver: Option<&dyn ResourceResolver>) -> Result<Import, LoadError> {
+    import_resources(parse_gltf(data)?, resolver)
+}
+
+/// Loads every buffer of a parsed document.
+///
+/// Meshopt fallback buffers have no data of their own; they start empty and
+/// are filled when their compressed views are decoded.
+fn import_resources(
+    gltf_data: gltf::Gltf,
+    resolver: Option<&dyn ResourceResolver>,
+) -> Result<Import, LoadError> {
+    let gltf::Gltf { document, mut blob } = gltf_data;
+
+    let mut buffers = Vec::new();
+    for buffer in document.buffers() {
+        if is_meshopt_fallback(&buffer) {
+            buffers.push(gltf::buffer::Data(Vec::new()));
+            continue;
+        }
+        let data = read_buffer(&buffer, resolver, &mut blob)?;
+        if data.len() < buffer.length() {
+            return Err(LoadError::InvalidData(format!(
+                "Buffer {} is shorter than its declared length",
+                buffer.index()
+            )));
+        }
+        buffers.push(gltf::buffer::Data(data));
+    }
+
+    Ok((document, buffers))
+}
+
+/// Reads a buffer from the GLB binary chunk, a data URI or an external file.
+///
+/// Like the `gltf` crate, pads the data to a multiple of four bytes.
+fn read_buffer(
+    buffer: &gltf::Buffer,
+    resolver: Option<&dyn ResourceResolver>,
+    blob: &mut Option<Vec<u8>>,
+) -> Result<Vec<u8>, LoadError> {
+    let import_error =
+        |message: String| LoadError::InvalidData(format!("Failed to import glTF: {}", message));
+    let mut data = match buffer.source() {
+        gltf::buffer::Source::Bin => blob
+            .take()
+            .ok_or_else(|| import_error("missing binary chunk".to_string()))?,
+        gltf::buffer::Source::Uri(uri) if uri.starts_with("data:") => decode_data_uri(uri)
+            .ok_or_else(|| {
+                import_error(format!("invalid data URI in buffer {}", buffer.index()))
+            })?,
+        gltf::buffer::Source::Uri(uri) => {
+            let resolver = resolver
+                .ok_or_else(|| import_error(format!("no resolver for external buffer {}", uri)))?;
+            resolver
+                .read(&resolver::join("", &percent_decode(uri)))
+                .map_err(|e| import_error(format!("{}: {}", uri, e)))?
+        }
+    };
+    while !data.len().is_multiple_of(4) {
+        data.push(0);
+    }
+    Ok(data)
+}
+
+/// Returns true for buffers that only exist as decoding targets of `EXT_meshopt_compression`.
+fn is_meshopt_fallback(buffer: &gltf::Buffer) -> bool {
+    buffer
+        .extension_value("EXT_meshopt_compression")
+        .and_then(|ext| ext.get("fallback"))
+        .and_then(|fallback| fallback.as_bool())
+        .unwrap_or(false)
+}
+
+/// Settings of a buffer view compressed with `EXT_meshopt_compression`.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MeshoptView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: usize,
+    count: usize,
+    mode: String,
+    #[serde(default)]
+    filter: Option<String>,
+}
+
+/// Decodes every meshopt-compressed buffer view into the buffer it describes.
+fn decode_meshopt_views(
+    document: &gltf::Document,
+    buffers: &mut [gltf::buffer::Data],
+    budget: &Budget,
+) -> Result<(), LoadError> {
+    for view in document.views() {
+        let Some(ext) = view.extension_value("EXT_meshopt_compression") else {
+            continue;
+        };
+        budget.check()?;
+
+        let settings: MeshoptView = serde_json::from_value(ext.clone())
+            .map_err(|e| LoadError::InvalidData(format!("Invalid meshopt view: {}", e)))?;
+        let mode = match settings.mode.as_str() {
+            "ATTRIBUTES" => meshopt::Mode::Attributes,
+            "TRIANGLES" => meshopt::Mode::Triangles,
+            "INDICES" => meshopt::Mode::Indices,
+            other => {
+                return Err(LoadError::InvalidData(format!(
+                    "Unknown meshopt mode {:?}",
+                    other
+                )))
+            }
+        };
+        let filter = match settings.filter.as_deref() {
+            None | Some("NONE") => meshopt::Filter::None,
+            Some("OCTAHEDRAL") => meshopt::Filter::Octahedral,
+            Some("QUATERNION") => meshopt::Filter::Quaternion,
+            Some("EXPONENTIAL") => meshopt::Filter::Exponential,
+            Some(other) => {
+                return Err(LoadError::InvalidData(format!(
+                    "Unknown meshopt filter {:?}",
+                    other
+                )))
+            }
+        };
+
+        let source = buffers
+            .get(settings.buffer)
+            .and_then(|data| {
+                data.0.get(
+                    settings.byte_offset..settings.byte_offset.checked_add(settings.byte_length)?,
+                )
+            })
+            .ok_or_else(|| {
+                LoadError::InvalidData("Meshopt view points outside its buffer".into())
+            })?;
+        let decoded = meshopt::decode(mode, filter, settings.count, settings.byte_stride, source)?;
+
+        let target = &mut buffers[view.buffer().index()].0;
+        let end = view.offset() + decoded.len();
+        if target.len() < end {
+            target.resize(end, 0);
+        }
+        target[view.offset()..end].copy_from_slice(&decoded);
+    }
+    Ok(())
+}
+
+/// Settings of a primitive compressed with `KHR_draco_mesh_compression`.
+#[cfg(feature = "draco")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DracoPrimitive {
+    buffer_view: u32,
+    /// Unique ids of the compressed attributes by glTF semantic.
+    attributes: std::collections::BTreeMap<String, u32>,
+}
+
+#[cfg(feature = "draco")]
+impl DracoPrimitive {
+    /// Reads the extension of a primitive, if it has a well-formed one.
+    fn of(primitive: &gltf::json::mesh::Primitive) -> Option<Result<Self, LoadError>> {
+        let ext = primitive
+            .extensions
+            .as_ref()?
+            .others
+            .get("KHR_draco_mesh_compression")?;
+        Some(
+            serde_json::from_value(ext.clone())
+                .map_err(|e| LoadError::InvalidData(format!("Invalid Draco primitive: {}", e))),
+        )
+    }
+
+    /// Accessors of the primitive that the stream replaces.
+    fn accessors(&self, primitive: &gltf::json::mesh::Primitive) -> Vec<u32> {
+        primitive
+            .attributes
+            .iter()
+            .filter(|(semantic, _)| self.attributes.contains_key(&semantic.to_string()))
+            .map(|(_, accessor)| accessor.value() as u32)
+            .chain(primitive.indices.map(|indices| indices.value() as u32))
+            .collect()
+    }
+}
+
+/// Points Draco-compressed accessors without a buffer view at the compressed
+/// view, so the document validates before [`decode_draco_primitives`] replaces them.
+#[cfg(feature = "draco")]
+fn borrow_draco_views(root: &mut gltf::json::Root) {
+    for primitive in root.meshes.iter().flat_map(|mesh| &mesh.primitives) {
+        let Some(Ok(draco)) = DracoPrimitive::of(primitive) else {
+            continue;
+        };
+        for accessor in draco.accessors(primitive) {
+            if let Some(accessor) = root.accessors.get_mut(accessor as usize) {
+                if accessor.buffer_view.is_none() && accessor.sparse.is_none() {
+                    accessor.buffer_view = Some(gltf::json::Index::new(draco.buffer_view));
                 }
             }
         }
+    }
+}
+
+/// Decodes every Draco-compressed primitive into a new buffer and points the
+/// primitive's accessors at it, so the rest of the loader reads plain data.
+#[cfg(feature = "draco")]
+fn decode_draco_primitives(
+    document: gltf::Document,
+    buffers: &mut Vec<gltf::buffer::Data>,
+    budget: &Budget,
+) -> Result<gltf::Document, LoadError> {
+    use gltf::json::accessor::{ComponentType, GenericComponentType, Type};
+    use gltf::json::validation::Checked::Valid;
 
-        load_from_gltf(document, buffers, images)
+    let compressed = document
+        .meshes()
+        .flat_map(|mesh| mesh.primitives())
+        .any(|primitive| {
+            primitive
+                .extension_value("KHR_draco_mesh_compression")
+                .is_some()
+        });
+    if !compressed {
+        return Ok(document);
     }
 
-    fn load_from_path(&self, path: &Path) -> LoadResult {
-        let (document, buffers, images) = gltf::import(path)
-            .map_err(|e| LoadError::InvalidData(format!("Failed to import glTF: {}", e)))?;
-        load_from_gltf(document, buffers, images)
+    let mut root = document.into_json();
+    let buffer = gltf::json::Index::new(root.buffers.len() as u32);
+    let mut decoded = Vec::new();
+    for (mesh_index, primitive_index) in root
+        .meshes
+        .iter()
+        .enumerate()
+        .flat_map(|(m, mesh)| (0..mesh.primitives.len()).map(move |p| (m, p)))
+        .collect::<Vec<_>>()
+    {
+        let primitive = &root.meshes[mesh_index].primitives[primitive_index];
+        let Some(draco) = DracoPrimitive::of(primitive).transpose()? else {
+            continue;
+        };
+        budget.check()?;
+
+        let source = root
+            .buffer_views
+            .get(draco.buffer_view as usize)
+            .and_then(|view| {
+                let start = view.byte_offset.map_or(0, |offset| offset.0 as usize);
+                let end = start.checked_add(view.byte_length.0 as usize)?;
+                buffers.get(view.buffer.value())?.0.get(start..end)
+            })
+            .ok_or_else(|| {
+                LoadError::InvalidData("Draco primitive points outside its buffer".into())
+            })?;
+        let mesh = draco::decode(source)?;
+        let count = mesh.faces.len() * 3;
+
+        // Attributes the stream holds, with the accessors they replace
+        let mut targets = Vec::new();
+        for (semantic, &accessor) in &primitive.attributes {
+            let name = semantic.to_string();
+            let Some(&unique_id) = draco.attributes.get(&name) else {
+                continue;
+            };
+            let attribute = mesh.attribute(unique_id).ok_or_else(|| {
+                LoadError::InvalidData(format!("Draco stream has no attribute {}", unique_id))
+            })?;
+            targets.push((accessor.value(), name.starts_with("JOINTS_"), attribute));
+        }
+        let indices = primitive.indices.map(|indices| indices.value());
+
+        for (accessor, joints, attribute) in targets {
+            let json = root
+                .accessors
+                .get(accessor)
+                .ok_or_else(|| LoadError::InvalidData(format!("Missing accessor {}", accessor)))?;
+            let components = match json.type_ {
+                Valid(type_) => type_.multiplicity(),
+                _ => 0,
+            };
+            if components != attribute.components {
+                return Err(LoadError::InvalidData(format!(
+                    "Draco attribute {} does not match its accessor",
+                    attribute.unique_id
+                )));
+            }
+            // Joint indices must stay integers for the skinning reader
+            let (component_type, bytes) = if joints {
+                let bytes = attribute
+                    .values
+                    .iter()
+                    .flat_map(|&v| (v as u16).to_le_bytes());
+                (ComponentType::U16, bytes.collect::<Vec<_>>())
+            } else {
+                let bytes = attribute.values.iter().flat_map(|v| v.to_le_bytes());
+                (ComponentType::F32, bytes.collect())
+            };
+            let view = push_view(&mut root, buffer, &mut decoded, &bytes);
+            let json = &mut root.accessors[accessor];
+            json.buffer_view = Some(view);
+            json.byte_offset = None;
+            json.count = (attribute.values.len() / attribute.components).into();
+            json.component_type = Valid(GenericComponentType(component_type));
+            json.normalized = false;
+            json.sparse = None;
+        }
+
+        let bytes: Vec<u8> = mesh
+            .faces
+            .iter()
+            .flatten()
+            .flat_map(|i| i.to_le_bytes())
+            .collect();
+        let view = push_view(&mut root, buffer, &mut decoded, &bytes);
+        let accessor = match indices.filter(|&i| i < root.accessors.len()) {
+            Some(accessor) => accessor,
+            None => {
+                let mut json = root.accessors.first().cloned().ok_or_else(|| {
+                    LoadError::InvalidData("Draco primitive has no accessors".into())
+                })?;
+                json.min = None;
+                json.max = None;
+                root.accessors.push(json);
+                root.accessors.len() - 1
+            }
+        };
+        let json = &mut root.accessors[accessor];
+        json.buffer_view = Some(view);
+        json.byte_offset = None;
+        json.count = count.into();
+        json.component_type = Valid(GenericComponentType(ComponentType::U32));
+        json.type_ = Valid(Type::Scalar);
+        json.normalized = false;
+        json.sparse = None;
+
+        // Draco streams only hold triangle lists
+        let primitive = &mut root.meshes[mesh_index].primitives[primitive_index];
+        primitive.indices = Some(gltf::json::Index::new(accessor as u32));
+        primitive.mode = Valid(gltf::mesh::Mode::Triangles);
     }
+
+    root.buffers.push(gltf::json::Buffer {
+        byte_length: decoded.len().into(),
+        name: None,
+        uri: None,
+        extensions: None,
+        extras: Default::default(),
+    });
+    buffers.push(gltf::buffer::Data(decoded));
+    gltf::Document::from_json(root)
+        .map_err(|e| LoadError::InvalidData(format!("Failed to parse glTF: {}", e)))
 }
 
-const IDENTITY: Mat4 = [
-    [1.0, 0.0, 0.0, 0.0],
-    [0.0, 1.0, 0.0, 0.0],
-    [0.0, 0.0, 1.0, 0.0],
-    [0.0, 0.0, 0.0, 1.0],
-];
+/// Appends `bytes` to the decoded buffer as a new view.
+#[cfg(feature = "draco")]
+fn push_view(
+    root: &mut gltf::json::Root,
+    buffer: gltf::json::Index<gltf::json::Buffer>,
+    decoded: &mut Vec<u8>,
+    bytes: &[u8],
+) -> gltf::json::Index<gltf::json::buffer::View> {
+    let offset = decoded.len();
+    decoded.extend_from_slice(bytes);
+    root.push(gltf::json::buffer::View {
+        buffer,
+        byte_length: bytes.len().into(),
+        byte_offset: Some(offset.into()),
+        byte_stride: None,
+        name: None,
+        target: None,
+        extensions: None,
+        extras: Default::default(),
+    })
+}
+
+/// Number of triangles emitted between budget checks.
+const BUDGET_CHECK_INTERVAL: usize = 16 * 1024;
 
-/// Loads triangles from a parsed glTF document.
+/// Loads meshes from a parsed glTF document.
+///
+/// External images are read through `resolver` when it is given.
 fn load_from_gltf(
     document: gltf::Document,
-    buffers: Vec<gltf::buffer::Data>,
-    images: Vec<gltf::image::Data>,
+    mut buffers: Vec<gltf::buffer::Data>,
+    resolver: Option<&dyn ResourceResolver>,
+    options: &LoadOptions,
 ) -> LoadResult {
-    // Load textures
+    let budget = &options.budget;
+    decode_meshopt_views(&document, &mut buffers, budget)?;
+    #[cfg(feature = "draco")]
+    let document = decode_draco_primitives(document, &mut buffers, budget)?;
+
+    // Load textures, using the first image source that decodes
+    let images: Vec<Option<Arc<TextureData>>> = document
+        .images()
+        .map(|image| load_image(&image, &buffers, resolver))
+        .collect();
     let textures: Vec<Option<Arc<TextureData>>> = document
         .textures()
-        .map(|tex| {
-            let source = tex.source();
-            let img_index = source.index();
-            if img_index < images.len() {
-                let img = &images[img_index];
-                let rgba_pixels = convert_to_rgba(&img.pixels, img.format);
-                Some(Arc::new(TextureData {
-                    width: img.width,
-                    height: img.height,
-                    data: rgba_pixels,
-                }))
-            } else {
-                None
-            }
+        .map(|texture| {
+            texture_sources(&texture)
+                .into_iter()
+                .find_map(|index| images.get(index).cloned().flatten())
         })
         .collect();
+    options.mark_parsed();
 
-    // Extract triangles from scene hierarchy
-    let mut triangles = Vec::new();
+    // Material table: one entry per glTF material, followed by the default material
+    let mut model = ModelData::default();
+    for material in document.materials() {
+        model.add_material(convert_material(&material, &textures));
+    }
+    model.add_material(default_material());
 
-    let scene = document
-        .default_scene()
-        .or_else(|| document.scenes().next())
-        .ok_or(LoadError::NoGeometry)?;
+    let scene = select_scene(&document, options.scene.as_ref())?;
+    let variant = match &options.variant {
+        Some(name) => Some(find_variant(&document, name)?),
+        None => None,
+    };
 
+    let pose = Pose::new(&document, &buffers, options.animation.as_ref())?;
     for node in scene.nodes() {
-        extract_node_triangles(&node, &buffers, &textures, &mut triangles, IDENTITY);
+        extract_node_meshes(&node, &buffers, &mut model, &pose, variant, None, budget)?;
     }
 
-    if triangles.is_empty() {
+    if model.is_empty() {
         return Err(LoadError::NoGeometry);
     }
 
-    Ok(ModelData { triangles })
+    Ok(model)
 }
 
-/// Recursively walks the glTF scene graph and collects world-space triangles.
-fn extract_node_triangles(
+/// Returns the requested scene, or the default scene (else the first one).
+///
+/// # Errors
+/// Returns [`LoadError::InvalidData`] if the requested scene does not exist,
+/// or [`LoadError::NoGeometry`] if the document has no scenes at all.
+fn select_scene<'a>(
+    document: &'a gltf::Document,
+    choice: Option<&SceneChoice>,
+) -> Result<gltf::Scene<'a>, LoadError> {
+    match choice {
+        None => document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+            .ok_or(LoadError::NoGeometry),
+        Some(SceneChoice::Index(index)) => document
+            .scenes()
+            .nth(*index)
+            .ok_or_else(|| LoadError::InvalidData(format!("No scene at index {}", index))),
+        Some(SceneChoice::Named(name)) => document
+            .scenes()
+            .find(|scene| scene.name() == Some(name.as_str()))
+            .ok_or_else(|| LoadError::InvalidData(format!("No scene named {:?}", name))),
+    }
+}
+
+/// Returns the index of the `KHR_materials_variants` variant called `name`.
+///
+/// # Errors
+/// Returns [`LoadError::InvalidData`] if the document has no such variant.
+fn find_variant(document: &gltf::Document, name: &str) -> Result<u32, LoadError> {
+    document
+        .variants()
+        .into_iter()
+        .flatten()
+        .position(|variant| variant.name() == name)
+        .map(|index| index as u32)
+        .ok_or_else(|| LoadError::InvalidData(format!("No material variant named {:?}", name)))
+}
+
+/// Reads an accessor of `N` components as floats, whatever its component type.
+///
+/// `KHR_mesh_quantization` allows positions, morph deltas and UVs to be stored
+/// as 8 or 16-bit integers; normalized ones map onto [-1, 1] or [0, 1].
+fn read_floats<const N: usize>(
+    accessor: gltf::Accessor,
+    buffers: &[gltf::buffer::Data],
+) -> Option<Vec<[f32; N]>>
+where
+    [f32; N]: Item,
+    [i8; N]: Item,
+    [u8; N]: Item,
+    [i16; N]: Item,
+    [u16; N]: Item,
+{
+    if accessor.dimensions().multiplicity() != N {
+        return None;
+    }
+    let get = |buffer: gltf::Buffer| buffers.get(buffer.index()).map(|d| &*d.0);
+    let normalized = accessor.normalized();
+    match accessor.data_type() {
+        DataType::F32 => Some(accessor::Iter::<[f32; N]>::new(accessor, get)?.collect()),
+        DataType::I8 => Some(
+            accessor::Iter::<[i8; N]>::new(accessor, get)?
+                .map(|v| dequantize(v, normalized.then_some(127.0)))
+                .collect(),
+        ),
+        DataType::U8 => Some(
+            accessor::Iter::<[u8; N]>::new(accessor, get)?
+                .map(|v| dequantize(v, normalized.then_some(255.0)))
+                .collect(),
+        ),
+        DataType::I16 => Some(
+            accessor::Iter::<[i16; N]>::new(accessor, get)?
+                .map(|v| dequantize(v, normalized.then_some(32767.0)))
+                .collect(),
+        ),
+        DataType::U16 => Some(
+            accessor::Iter::<[u16; N]>::new(accessor, get)?
+                .map(|v| dequantize(v, normalized.then_some(65535.0)))
+                .collect(),
+        ),
+        DataType::U32 => None,
+    }
+}
+
+/// Converts integer components to floats, dividing normalized values by their type's maximum.
+fn dequantize<T: Copy + Into<f32>, const N: usize>(v: [T; N], max: Option<f32>) -> [f32; N] {
+    v.map(|c| match max {
+        Some(max) => (c.into() / max).max(-1.0),
+        None => c.into(),
+    })
+}
+
+/// Image indices a texture can use, in order of preference.
+///
+/// `KHR_texture_basisu` and `EXT_texture_webp` sources come first; the core
+/// `source` is the fallback for when their codec is disabled or fails.
+fn texture_sources(texture: &gltf::Texture) -> Vec<usize> {
+    ["KHR_texture_basisu", "EXT_texture_webp"]
+        .iter()
+        .filter_map(|name| texture.extension_value(name)?.get("source")?.as_u64())
+        .map(|index| index as usize)
+        .chain(texture.source().map(|image| image.index()))
+        .collect()
+}
+
+/// Decodes an image from a buffer view, a data URI or a file next to the model.
+fn load_image(
+    image: &gltf::Image,
+    buffers: &[gltf::buffer::Data],
+    resolver: Option<&dyn ResourceResolver>,
+) -> Option<Arc<TextureData>> {
+    match image.source() {
+        gltf::image::Source::View { view, mime_type } => {
+            let data = buffers.get(view.buffer().index())?;
+            let bytes = data.0.get(view.offset()..view.offset() + view.length())?;
+            decode_texture(bytes, Some(mime_type))
+        }
+        gltf::image::Source::Uri { uri, .. } if uri.starts_with("data:") => {
+            load_texture_from_data_url(uri)
+        }
+        gltf::image::Source::Uri { uri, mime_type } => {
+            let path = resolver::join("", &percent_decode(uri));
+            let bytes = resolver?.read(&path).ok()?;
+            let extension = Path::new(&path).extension().and_then(|ext| ext.to_str());
+            decode_texture(&bytes, mime_type.or(extension))
+        }
+    }
+}
+
+/// Decodes `%XX` escapes in a relative URI.
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Converts a glTF material to a [`Material`].
+fn convert_material(material: &gltf::Material, textures: &[Option<Arc<TextureData>>]) -> Material {
+    let pbr = material.pbr_metallic_roughness();
+    let base_factor = pbr.base_color_factor();
+
+    // Get base color texture if present
+    let texture = pbr.base_color_texture().and_then(|info| {
+        let tex_index = info.texture().index();
+        textures.get(tex_index).and_then(|t| t.clone())
+    });
+
+    let emissive_texture = material.emissive_texture().and_then(|info| {
+        let tex_index = info.texture().index();
+        textures.get(tex_index).and_then(|t| t.clone())
+    });
+
+    let alpha_mode = match material.alpha_mode() {
+        gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
+        gltf::material::AlphaMode::Mask => AlphaMode::Mask {
+            cutoff: material.alpha_cutoff().unwrap_or(0.5),
+        },
+        gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+    };
+
+    let emissive_strength = material.emissive_strength().unwrap_or(1.0);
+
+    Material {
+        name: material.name().map(str::to_owned),
+        color: [base_factor[0], base_factor[1], base_factor[2]],
+        alpha: base_factor[3],
+        texture,
+        alpha_mode,
+        double_sided: material.double_sided(),
+        emissive: material.emissive_factor().map(|c| c * emissive_strength),
+        emissive_texture,
+        roughness: pbr.roughness_factor(),
+        metallic: pbr.metallic_factor(),
+        render_mode: RenderMode::Normal,
+        unlit: material.unlit(),
+        specular: [1.0; 3],
+        wrap: WrapMode::Repeat,
+    }
+}
+
+/// Returns the material the glTF specification prescribes for primitives without one.
+fn default_material() -> Material {
+    Material {
+        alpha_mode: AlphaMode::Opaque,
+        double_sided: false,
+        roughness: 1.0,
+        metallic: 1.0,
+        ..Default::default()
+    }
+}
+
+/// Selects and transforms the UVs sampled by a material's base color texture.
+///
+/// Follows `KHR_texture_transform`: UVs are scaled, rotated counter-clockwise
+/// and offset, and the extension's `texCoord` overrides the texture's own.
+/// The emissive texture shares these UVs.
+struct UvMapping {
+    tex_coord: u32,
+    offset: Vec2,
+    rotation: f32,
+    scale: Vec2,
+}
+
+impl UvMapping {
+    /// Returns the mapping of `material`'s base color texture, or plain `TEXCOORD_0`.
+    fn for_material(material: &gltf::Material) -> Self {
+        let mut mapping = UvMapping {
+            tex_coord: 0,
+            offset: [0.0, 0.0],
+            rotation: 0.0,
+            scale: [1.0, 1.0],
+        };
+        if let Some(info) = material.pbr_metallic_roughness().base_color_texture() {
+            mapping.tex_coord = info.tex_coord();
+            if let Some(transform) = info.texture_transform() {
+                mapping.tex_coord = transform.tex_coord().unwrap_or(mapping.tex_coord);
+                mapping.offset = transform.offset();
+                mapping.rotation = transform.rotation();
+                mapping.scale = transform.scale();
+            }
+        }
+        mapping
+    }
+
+    /// Applies the transform to one UV coordinate.
+    fn apply(&self, uv: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let u = uv[0] * self.scale[0];
+        let v = uv[1] * self.scale[1];
+        [
+            cos * u + sin * v + self.offset[0],
+            -sin * u + cos * v + self.offset[1],
+        ]
+    }
+}
+
+/// Recursively walks the glTF scene graph, collecting world-space meshes and
+/// mirroring each visited node into the model's scene tree.
+fn extract_node_meshes(
     node: &gltf::Node,
     buffers: &[gltf::buffer::Data],
-    textures: &[Option<Arc<TextureData>>],
-    triangles: &mut Vec<Triangle>,
-    parent_transform: Mat4,
-) {
-    let local: Mat4 = node.transform().matrix();
-    let world = mat4_mul(parent_transform, local);
+    model: &mut ModelData,
+    pose: &Pose,
+    variant: Option<u32>,
+    parent: Option<usize>,
+    budget: &Budget,
+) -> Result<(), LoadError> {
+    budget.check()?;
+
+    let local = pose.locals[node.index()];
+    let world = pose.worlds[node.index()];
+    // Skinned meshes ignore their node's transform and follow the joints instead
+    let joints = node.skin().map(|skin| pose.joint_matrices(&skin, buffers));
+
+    let node_index = model.nodes.len();
+    if let Some(parent) = parent {
+        model.nodes[parent].children.push(node_index);
+    }
+    let first_mesh = model.meshes.len();
+    model.nodes.push(Node {
+        name: node.name().map(str::to_owned),
+        transform: local,
+        parent,
+        children: Vec::new(),
+        meshes: first_mesh..first_mesh,
+    });
+    if let Some(camera) = node.camera() {
+        model
+            .cameras
+            .push(convert_camera(&camera, node.name(), world));
+    }
+    if let Some(light) = node.light() {
+        model.lights.push(convert_light(&light, node.name(), world));
+    }
+
+    // Mirroring transforms flip the winding, which matters for back-face culling
+    let mirrored = match joints.as_ref().and_then(|j| j.first()) {
+        Some(joint) => joint.determinant() < 0.0,
+        None => mat3_determinant(world) < 0.0,
+    };
 
     if let Some(mesh) = node.mesh() {
         for primitive in mesh.primitives() {
-            if primitive.mode() != gltf::mesh::Mode::Triangles {
-                continue;
-            }
-
             let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|d| &*d.0));
 
-            let positions: Vec<Vec3> = match reader.read_positions() {
-                Some(iter) => iter.collect(),
-                None => continue,
+            let Some(mut raw_positions) = primitive
+                .get(&gltf::Semantic::Positions)
+                .and_then(|accessor| read_floats::<3>(accessor, buffers))
+            else {
+                continue;
             };
+            let vertex_count = raw_positions.len();
+            // Zero normals are padding; the renderer shades those corners flat
+            let mut raw_normals = primitive
+                .get(&gltf::Semantic::Normals)
+                .and_then(|accessor| read_floats::<3>(accessor, buffers))
+                .map(|mut normals| {
+                    normals.resize(vertex_count, [0.0; 3]);
+                    normals
+                });
+
+            // Blend morph target deltas in before skinning, as the spec orders them
+            let morph_weights = pose.weights[node.index()]
+                .as_deref()
+                .or(node.weights())
+                .or(mesh.weights())
+                .unwrap_or_default();
+            for (target, &weight) in primitive.morph_targets().zip(morph_weights) {
+                if weight == 0.0 {
+                    continue;
+                }
+                if let Some(deltas) = target
+                    .positions()
+                    .and_then(|accessor| read_floats::<3>(accessor, buffers))
+                {
+                    add_morph_deltas(&mut raw_positions, &deltas, weight);
+                }
+                if let (Some(normals), Some(deltas)) = (
+                    raw_normals.as_mut(),
+                    target
+                        .normals()
+                        .and_then(|accessor| read_floats::<3>(accessor, buffers)),
+                ) {
+                    add_morph_deltas(normals, &deltas, weight);
+                }
+            }
 
-            // Read UV coordinates (TEXCOORD_0)
-            let uvs: Vec<Vec2> = reader
-                .read_tex_coords(0)
-                .map(|iter| iter.into_f32().collect())
-                .unwrap_or_else(|| vec![[0.0, 0.0]; positions.len()]);
-
-            // Get material properties
-            let material = primitive.material();
-            let pbr = material.pbr_metallic_roughness();
-            let base_factor = pbr.base_color_factor();
-            let material_color = [base_factor[0], base_factor[1], base_factor[2]];
-
-            // Get base color texture if present
-            let texture = pbr.base_color_texture().and_then(|info| {
-                let tex_index = info.texture().index();
-                textures.get(tex_index).and_then(|t| t.clone())
+            let influences = joints.as_ref().and_then(|joints| {
+                let ids = reader.read_joints(0)?.into_u16();
+                let weights = reader.read_weights(0)?.into_f32();
+                Some((joints, ids.zip(weights).collect::<Vec<_>>()))
             });
+            let world_normal = normal_matrix(&GlamMat4::from_cols_array_2d(&world));
+            let (positions, normals): (Vec<Vec3>, Option<Vec<Vec3>>) = match influences {
+                Some((joints, influences)) => {
+                    let joint_normals: Vec<GlamMat3> = joints.iter().map(normal_matrix).collect();
+                    let positions = raw_positions
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &p)| match influences.get(i) {
+                            Some(&(ids, weights)) => skin_point(joints, ids, weights, p)
+                                .unwrap_or_else(|| transform_point(world, p)),
+                            None => transform_point(world, p),
+                        })
+                        .collect();
+                    let normals = raw_normals.map(|normals| {
+                        normals
+                            .iter()
+                            .enumerate()
+                            .map(|(i, &n)| match influences.get(i) {
+                                Some(&(ids, weights)) => {
+                                    skin_normal(&joint_normals, ids, weights, n)
+                                        .unwrap_or_else(|| transform_normal(world_normal, n))
+                                }
+                                None => transform_normal(world_normal, n),
+                            })
+                            .collect()
+                    });
+                    (positions, normals)
+                }
+                None => (
+                    raw_positions
+                        .iter()
+                        .map(|&p| transform_point(world, p))
+                        .collect(),
+                    raw_normals.map(|normals| {
+                        normals
+                            .iter()
+                            .map(|&n| transform_normal(world_normal, n))
+                            .collect()
+                    }),
+                ),
+            };
+
+            // The selected variant's mapping overrides the primitive's own material
+            let gltf_material = variant
+                .and_then(|variant| {
+                    primitive
+                        .mappings()
+                        .find(|mapping| mapping.variants().contains(&variant))
+                })
+                .map_or_else(|| primitive.material(), |mapping| mapping.material());
+
+            // Read the UV set used by the base color texture, applying its transform
+            let mapping = UvMapping::for_material(&gltf_material);
+            let mut uvs: Vec<Vec2> = primitive
+                .get(&gltf::Semantic::TexCoords(mapping.tex_coord))
+                .and_then(|accessor| read_floats::<2>(accessor, buffers))
+                .map(|uvs| uvs.into_iter().map(|uv| mapping.apply(uv)).collect())
+                .unwrap_or_default();
+            uvs.resize(vertex_count, [0.0, 0.0]);
 
             // Read vertex colors if available
-            let vertex_colors: Option<Vec<[f32; 4]>> = reader
-                .read_colors(0)
-                .map(|iter| iter.into_rgba_f32().collect());
+            let colors: Option<Vec<[f32; 3]>> = reader.read_colors(0).map(|iter| {
+                let mut colors: Vec<[f32; 3]> =
+                    iter.into_rgba_f32().map(|c| [c[0], c[1], c[2]]).collect();
+                colors.resize(vertex_count, [1.0, 1.0, 1.0]);
+                colors
+            });
 
-            // Index buffer
-            let indices: Vec<u32> = match reader.read_indices() {
+            // Index buffer, dropping primitives that reference missing vertices
+            let raw_indices: Vec<u32> = match reader.read_indices() {
                 Some(iter) => iter.into_u32().collect(),
-                None => (0..positions.len() as u32).collect(),
+                None => (0..vertex_count as u32).collect(),
             };
-
-            for tri_indices in indices.chunks_exact(3) {
-                let i0 = tri_indices[0] as usize;
-                let i1 = tri_indices[1] as usize;
-                let i2 = tri_indices[2] as usize;
-
-                if i0 >= positions.len() || i1 >= positions.len() || i2 >= positions.len() {
-                    continue;
+            let in_range = |i: &u32| (*i as usize) < vertex_count;
+            let (triangles, lines, points) = assemble(primitive.mode(), raw_indices);
+            let lines: Vec<u32> = lines
+                .chunks_exact(2)
+                .filter(|line| line.iter().all(in_range))
+                .flatten()
+                .copied()
+                .collect();
+            let points: Vec<u32> = points.into_iter().filter(in_range).collect();
+            let mut indices = Vec::with_capacity(triangles.len());
+            for (tri_num, tri) in triangles.chunks_exact(3).enumerate() {
+                if tri_num % BUDGET_CHECK_INTERVAL == BUDGET_CHECK_INTERVAL - 1 {
+                    budget.check()?;
                 }
-
-                let v0 = transform_point(world, positions[i0]);
-                let v1 = transform_point(world, positions[i1]);
-                let v2 = transform_point(world, positions[i2]);
-
-                let uv0 = if i0 < uvs.len() { uvs[i0] } else { [0.0, 0.0] };
-                let uv1 = if i1 < uvs.len() { uvs[i1] } else { [0.0, 0.0] };
-                let uv2 = if i2 < uvs.len() { uvs[i2] } else { [0.0, 0.0] };
-
-                // Combine vertex colors with material color
-                let color = if let Some(ref vc) = vertex_colors {
-                    let c0 = if i0 < vc.len() {
-                        vc[i0]
-                    } else {
-                        [1.0, 1.0, 1.0, 1.0]
-                    };
-                    let c1 = if i1 < vc.len() {
-                        vc[i1]
+                if tri.iter().all(in_range) {
+                    if mirrored {
+                        indices.extend_from_slice(&[tri[0], tri[2], tri[1]]);
                     } else {
-                        [1.0, 1.0, 1.0, 1.0]
-                    };
-                    let c2 = if i2 < vc.len() {
-                        vc[i2]
-                    } else {
-                        [1.0, 1.0, 1.0, 1.0]
-                    };
-                    [
-                        ((c0[0] + c1[0] + c2[0]) / 3.0) * material_color[0],
-                        ((c0[1] + c1[1] + c2[1]) / 3.0) * material_color[1],
-                        ((c0[2] + c1[2] + c2[2]) / 3.0) * material_color[2],
-                    ]
-                } else {
-                    material_color
-                };
-
-                triangles.push(Triangle {
-                    verts: [v0, v1, v2],
-                    uvs: [uv0, uv1, uv2],
-                    color,
-                    texture: texture.clone(),
-                });
+                        indices.extend_from_slice(tri);
+                    }
+                }
             }
+            if indices.is_empty() && lines.is_empty() && points.is_empty() {
+                continue;
+            }
+
+            // Primitives without a material use the trailing default material
+            let material = gltf_material.index().unwrap_or(model.materials.len() - 1);
+
+            model.meshes.push(Mesh {
+                positions,
+                uvs,
+                normals,
+                colors,
+                indices,
+                lines,
+                points,
+                material,
+            });
         }
     }
 
+    model.nodes[node_index].meshes = first_mesh..model.meshes.len();
+
     for child in node.children() {
-        extract_node_triangles(&child, buffers, textures, triangles, world);
+        extract_node_meshes(
+            &child,
+            buffers,
+            model,
+            pose,
+            variant,
+            Some(node_index),
+            budget,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Converts a glTF camera placed at `world` to a [`Camera`].
+fn convert_camera(camera: &gltf::Camera, node_name: Option<&str>, world: Mat4) -> Camera {
+    let projection = match camera.projection() {
+        gltf::camera::Projection::Perspective(p) => Projection::Perspective {
+            yfov: p.yfov(),
+            znear: p.znear(),
+            zfar: p.zfar(),
+        },
+        gltf::camera::Projection::Orthographic(o) => Projection::Orthographic {
+            ymag: o.ymag(),
+            znear: o.znear(),
+            zfar: o.zfar(),
+        },
+    };
+    Camera {
+        name: camera.name().or(node_name).map(str::to_owned),
+        transform: world,
+        projection,
+    }
+}
+
+/// Converts a `KHR_lights_punctual` light placed at `world` to a [`Light`].
+fn convert_light(
+    light: &gltf::khr_lights_punctual::Light,
+    node_name: Option<&str>,
+    world: Mat4,
+) -> Light {
+    let kind = match light.kind() {
+        gltf::khr_lights_punctual::Kind::Directional => LightKind::Directional,
+        gltf::khr_lights_punctual::Kind::Point => LightKind::Point,
+        gltf::khr_lights_punctual::Kind::Spot {
+            inner_cone_angle,
+            outer_cone_angle,
+        } => LightKind::Spot {
+            inner_cone_angle,
+            outer_cone_angle,
+        },
+    };
+    Light {
+        name: light.name().or(node_name).map(str::to_owned),
+        transform: world,
+        kind,
+        color: light.color(),
+        intensity: light.intensity(),
+        range: light.range(),
+    }
+}
+
+/// Splits a primitive's index stream into triangle, line and point lists.
+///
+/// Strips, fans and line strips/loops are expanded to lists with the winding
+/// the glTF specification gives them.
+fn assemble(mode: gltf::mesh::Mode, indices: Vec<u32>) -> (Vec<u32>, Vec<u32>, Vec<u32>) {
+    use gltf::mesh::Mode;
+
+    let n = indices.len();
+    match mode {
+        Mode::Triangles => (indices, Vec::new(), Vec::new()),
+        Mode::TriangleStrip => {
+            let triangles = (0..n.saturating_sub(2))
+                .flat_map(|i| match i % 2 {
+                    0 => [indices[i], indices[i + 1], indices[i + 2]],
+                    _ => [indices[i], indices[i + 2], indices[i + 1]],
+                })
+                .collect();
+            (triangles, Vec::new(), Vec::new())
+        }
+        Mode::TriangleFan => {
+            let triangles = (0..n.saturating_sub(2))
+                .flat_map(|i| [indices[i + 1], indices[i + 2], indices[0]])
+                .collect();
+            (triangles, Vec::new(), Vec::new())
+        }
+        Mode::Lines => (Vec::new(), indices, Vec::new()),
+        Mode::LineStrip | Mode::LineLoop => {
+            let mut lines: Vec<u32> = indices.windows(2).flatten().copied().collect();
+            if mode == Mode::LineLoop && n > 2 {
+                lines.extend_from_slice(&[indices[n - 1], indices[0]]);
+            }
+            (Vec::new(), lines, Vec::new())
+        }
+        Mode::Points => (Vec::new(), Vec::new(), indices),
     }
 }
 
@@ -312,77 +1262,329 @@ fn decode_data_uri(uri: &str) -> Option<Vec<u8>> {
         .ok()
 }
 
-/// Decodes image data (PNG, JPEG, etc.) to RGBA pixels.
-fn decode_image_data(data: &[u8]) -> Option<gltf::image::Data> {
-    use image::GenericImageView;
-
-    let img = image::load_from_memory(data).ok()?;
-    let (width, height) = img.dimensions();
-    let rgba = img.to_rgba8();
+// Posing: animation sampling and skinning
 
-    Some(gltf::image::Data {
-        width,
-        height,
-        format: gltf::image::Format::R8G8B8A8,
-        pixels: rgba.into_raw(),
-    })
+/// Local and world transforms of every node in the document, indexed by node index.
+struct Pose {
+    locals: Vec<Mat4>,
+    worlds: Vec<Mat4>,
+    /// Animated morph target weights, overriding the node's and mesh's defaults.
+    weights: Vec<Option<Vec<f32>>>,
 }
 
-/// Converts pixel data to RGBA format if needed.
-fn convert_to_rgba(pixels: &[u8], format: gltf::image::Format) -> Vec<u8> {
-    use gltf::image::Format;
-    match format {
-        Format::R8G8B8A8 => pixels.to_vec(),
-        Format::R8G8B8 => {
-            let mut rgba = Vec::with_capacity(pixels.len() / 3 * 4);
-            for chunk in pixels.chunks_exact(3) {
-                rgba.push(chunk[0]);
-                rgba.push(chunk[1]);
-                rgba.push(chunk[2]);
-                rgba.push(255);
+impl Pose {
+    /// Poses all nodes at rest, or at `animation`'s sample time if given.
+    ///
+    /// # Errors
+    /// Returns [`LoadError::InvalidData`] if a named animation does not exist.
+    fn new(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        animation: Option<&AnimationPose>,
+    ) -> Result<Self, LoadError> {
+        let mut trs: Vec<(GlamVec3, Quat, GlamVec3)> = document
+            .nodes()
+            .map(|node| {
+                let (t, r, s) = node.transform().decomposed();
+                (
+                    GlamVec3::from_array(t),
+                    Quat::from_array(r),
+                    GlamVec3::from_array(s),
+                )
+            })
+            .collect();
+        let mut weights = vec![None; trs.len()];
+        let mut animated = vec![false; trs.len()];
+
+        if let Some(selection) = animation {
+            let found = match &selection.name {
+                Some(name) => document.animations().find(|a| a.name() == Some(name)),
+                None => document.animations().next(),
+            };
+            match (found, &selection.name) {
+                (Some(found), _) => apply_animation(
+                    &found,
+                    buffers,
+                    selection.time,
+                    &mut trs,
+                    &mut animated,
+                    &mut weights,
+                ),
+                (None, Some(name)) => {
+                    return Err(LoadError::InvalidData(format!(
+                        "No animation named {:?}",
+                        name
+                    )))
+                }
+                // Files without animations simply render at rest
+                (None, None) => {}
             }
-            rgba
         }
-        Format::R8 => {
-            let mut rgba = Vec::with_capacity(pixels.len() * 4);
-            for &gray in pixels {
-                rgba.push(gray);
-                rgba.push(gray);
-                rgba.push(gray);
-                rgba.push(255);
+
+        // Only animated nodes are rebuilt from TRS; decomposing a static matrix
+        // would lose shear and mirroring, and produce NaN for zero scale
+        let locals: Vec<Mat4> = document
+            .nodes()
+            .zip(&trs)
+            .zip(&animated)
+            .map(|((node, &(t, r, s)), &animated)| {
+                if animated {
+                    GlamMat4::from_scale_rotation_translation(s, r, t).to_cols_array_2d()
+                } else {
+                    node.transform().matrix()
+                }
+            })
+            .collect();
+
+        let mut parents = vec![None; locals.len()];
+        for node in document.nodes() {
+            for child in node.children() {
+                parents[child.index()] = Some(node.index());
             }
-            rgba
         }
-        Format::R8G8 => {
-            let mut rgba = Vec::with_capacity(pixels.len() * 2);
-            for chunk in pixels.chunks_exact(2) {
-                rgba.push(chunk[0]);
-                rgba.push(chunk[1]);
-                rgba.push(0);
-                rgba.push(255);
+
+        // Walk each node up to its root; the step limit guards against cyclic files
+        let worlds = (0..locals.len())
+            .map(|index| {
+                let mut world = locals[index];
+                let mut current = parents[index];
+                let mut steps = 0;
+                while let Some(parent) = current.filter(|_| steps < locals.len()) {
+                    world = mat4_mul(locals[parent], world);
+                    current = parents[parent];
+                    steps += 1;
+                }
+                world
+            })
+            .collect();
+
+        Ok(Self {
+            locals,
+            worlds,
+            weights,
+        })
+    }
+
+    /// Returns each joint's world transform times its inverse bind matrix.
+    fn joint_matrices(&self, skin: &gltf::Skin, buffers: &[gltf::buffer::Data]) -> Vec<GlamMat4> {
+        let inverse_binds: Vec<Mat4> = skin
+            .reader(|buffer| buffers.get(buffer.index()).map(|d| &*d.0))
+            .read_inverse_bind_matrices()
+            .map(|iter| iter.collect())
+            .unwrap_or_default();
+
+        skin.joints()
+            .enumerate()
+            .map(|(i, joint)| {
+                let world = GlamMat4::from_cols_array_2d(&self.worlds[joint.index()]);
+                let inverse_bind = inverse_binds
+                    .get(i)
+                    .map(GlamMat4::from_cols_array_2d)
+                    .unwrap_or(GlamMat4::IDENTITY);
+                world * inverse_bind
+            })
+            .collect()
+    }
+}
+
+/// Overrides node TRS values and morph weights with the animation's channels
+/// sampled at `time`, flagging every node whose TRS was overridden in `animated`.
+fn apply_animation(
+    animation: &gltf::Animation,
+    buffers: &[gltf::buffer::Data],
+    time: f32,
+    trs: &mut [(GlamVec3, Quat, GlamVec3)],
+    animated: &mut [bool],
+    weights: &mut [Option<Vec<f32>>],
+) {
+    for channel in animation.channels() {
+        let reader = channel.reader(|buffer| buffers.get(buffer.index()).map(|d| &*d.0));
+        let Some(inputs) = reader.read_inputs() else {
+            continue;
+        };
+        let inputs: Vec<f32> = inputs.collect();
+        let (outputs, is_rotation): (Vec<[f32; 4]>, bool) = match reader.read_outputs() {
+            Some(ReadOutputs::Translations(iter)) | Some(ReadOutputs::Scales(iter)) => {
+                (iter.map(|v| [v[0], v[1], v[2], 0.0]).collect(), false)
+            }
+            Some(ReadOutputs::Rotations(iter)) => (iter.into_f32().collect(), true),
+            Some(ReadOutputs::MorphTargetWeights(iter)) => {
+                let flat: Vec<f32> = iter.into_f32().collect();
+                if let Some(slot) = weights.get_mut(channel.target().node().index()) {
+                    let interpolation = channel.sampler().interpolation();
+                    *slot = Some(sample_weights(&inputs, &flat, interpolation, time));
+                }
+                continue;
             }
-            rgba
+            None => continue,
+        };
+
+        let interpolation = channel.sampler().interpolation();
+        let Some(value) = sample_keyframes(&inputs, &outputs, interpolation, time, is_rotation)
+        else {
+            continue;
+        };
+        let node = channel.target().node().index();
+        let Some(target) = trs.get_mut(node) else {
+            continue;
+        };
+        animated[node] = true;
+        match channel.target().property() {
+            Property::Translation => target.0 = GlamVec4::from_array(value).truncate(),
+            Property::Rotation => target.1 = Quat::from_array(value),
+            Property::Scale => target.2 = GlamVec4::from_array(value).truncate(),
+            Property::MorphTargetWeights => {}
         }
-        Format::R16 | Format::R16G16 | Format::R16G16B16 | Format::R16G16B16A16 => {
-            vec![255u8; (pixels.len() / 2) * 4]
+    }
+}
+
+/// Samples keyframe values at `time`, holding the first and last values outside the keyframes.
+///
+/// Rotations are slerped (linear) or renormalized (cubic spline). Cubic spline
+/// outputs hold an in-tangent, value and out-tangent per keyframe.
+fn sample_keyframes(
+    inputs: &[f32],
+    outputs: &[[f32; 4]],
+    interpolation: Interpolation,
+    time: f32,
+    is_rotation: bool,
+) -> Option<[f32; 4]> {
+    let cubic = interpolation == Interpolation::CubicSpline;
+    let stride = if cubic { 3 } else { 1 };
+    let value = |k: usize| outputs.get(k * stride + usize::from(cubic)).copied();
+
+    let last = inputs.len().checked_sub(1)?;
+    if time <= inputs[0] {
+        return value(0);
+    }
+    if time >= inputs[last] {
+        return value(last);
+    }
+
+    // Keyframe k is the last one at or before `time`
+    let k = inputs.partition_point(|&t| t <= time) - 1;
+    let dt = inputs[k + 1] - inputs[k];
+    let s = if dt > 0.0 {
+        (time - inputs[k]) / dt
+    } else {
+        0.0
+    };
+    let (v0, v1) = (
+        GlamVec4::from_array(value(k)?),
+        GlamVec4::from_array(value(k + 1)?),
+    );
+
+    let result = match interpolation {
+        Interpolation::Step => v0,
+        Interpolation::Linear if is_rotation => {
+            let q = Quat::from_vec4(v0).slerp(Quat::from_vec4(v1), s);
+            GlamVec4::from(q)
         }
-        Format::R32G32B32FLOAT | Format::R32G32B32A32FLOAT => {
-            vec![
-                255u8;
-                (pixels.len()
-                    / if format == Format::R32G32B32FLOAT {
-                        12
-                    } else {
-                        16
-                    })
-                    * 4
-            ]
+        Interpolation::Linear => v0.lerp(v1, s),
+        Interpolation::CubicSpline => {
+            let out0 = GlamVec4::from_array(*outputs.get(k * 3 + 2)?);
+            let in1 = GlamVec4::from_array(*outputs.get((k + 1) * 3)?);
+            let (s2, s3) = (s * s, s * s * s);
+            let p = v0 * (2.0 * s3 - 3.0 * s2 + 1.0)
+                + out0 * dt * (s3 - 2.0 * s2 + s)
+                + v1 * (-2.0 * s3 + 3.0 * s2)
+                + in1 * dt * (s3 - s2);
+            if is_rotation {
+                p.normalize_or_zero()
+            } else {
+                p
+            }
+        }
+    };
+    Some(result.to_array())
+}
+
+/// Samples a morph weights channel, whose outputs hold one weight per target per keyframe element.
+fn sample_weights(
+    inputs: &[f32],
+    flat: &[f32],
+    interpolation: Interpolation,
+    time: f32,
+) -> Vec<f32> {
+    let stride = if interpolation == Interpolation::CubicSpline {
+        3
+    } else {
+        1
+    };
+    let elements = inputs.len() * stride;
+    if elements == 0 {
+        return Vec::new();
+    }
+    // Sample each target's weights as its own scalar channel
+    let targets = flat.len() / elements;
+    (0..targets)
+        .map(|target| {
+            let outputs: Vec<[f32; 4]> = (0..elements)
+                .map(|e| [flat[e * targets + target], 0.0, 0.0, 0.0])
+                .collect();
+            sample_keyframes(inputs, &outputs, interpolation, time, false).map_or(0.0, |v| v[0])
+        })
+        .collect()
+}
+
+/// Blends a point by up to four joint influences.
+///
+/// Returns `None` if no influence has weight, so the caller can fall back to the node transform.
+fn skin_point(joints: &[GlamMat4], ids: [u16; 4], weights: [f32; 4], p: Vec3) -> Option<Vec3> {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let point = GlamVec3::from_array(p);
+    let mut skinned = GlamVec3::ZERO;
+    for (&id, &weight) in ids.iter().zip(&weights) {
+        if let Some(joint) = joints.get(usize::from(id)).filter(|_| weight > 0.0) {
+            skinned += joint.transform_point3(point) * (weight / total);
+        }
+    }
+    Some(skinned.to_array())
+}
+
+/// Blends a skinned vertex normal through the inverse-transpose of each joint matrix.
+fn skin_normal(
+    joint_normals: &[GlamMat3],
+    ids: [u16; 4],
+    weights: [f32; 4],
+    n: Vec3,
+) -> Option<Vec3> {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let normal = GlamVec3::from_array(n);
+    let mut skinned = GlamVec3::ZERO;
+    for (&id, &weight) in ids.iter().zip(&weights) {
+        if let Some(joint) = joint_normals.get(usize::from(id)).filter(|_| weight > 0.0) {
+            skinned += (*joint * normal) * (weight / total);
         }
     }
+    Some(skinned.to_array())
+}
+
+/// Adds weighted morph target deltas to per-vertex values.
+fn add_morph_deltas(values: &mut [Vec3], deltas: &[Vec3], weight: f32) {
+    for (v, d) in values.iter_mut().zip(deltas) {
+        *v = [0, 1, 2].map(|a| v[a] + d[a] * weight);
+    }
 }
 
 // Linear algebra helpers
 
+/// Returns the matrix that transforms normals under `m`: the inverse-transpose
+/// of its upper 3x3, which keeps them perpendicular under non-uniform scale.
+fn normal_matrix(m: &GlamMat4) -> GlamMat3 {
+    GlamMat3::from_mat4(*m).inverse().transpose()
+}
+
+fn transform_normal(m: GlamMat3, n: Vec3) -> Vec3 {
+    (m * GlamVec3::from_array(n)).to_array()
+}
+
 fn transform_point(m: Mat4, p: Vec3) -> Vec3 {
     let x = m[0][0] * p[0] + m[1][0] * p[1] + m[2][0] * p[2] + m[3][0];
     let y = m[0][1] * p[0] + m[1][1] * p[1] + m[2][1] * p[2] + m[3][1];
@@ -395,6 +1597,12 @@ fn transform_point(m: Mat4, p: Vec3) -> Vec3 {
     }
 }
 
+fn mat3_determinant(m: Mat4) -> f32 {
+    m[0][0] * (m[1][1] * m[2][2] - m[2][1] * m[1][2])
+        - m[1][0] * (m[0][1] * m[2][2] - m[2][1] * m[0][2])
+        + m[2][0] * (m[0][1] * m[1][2] - m[1][1] * m[0][2])
+}
+
 fn mat4_mul(a: Mat4, b: Mat4) -> Mat4 {
     let mut r = [[0.0_f32; 4]; 4];
     for col in 0..4 {
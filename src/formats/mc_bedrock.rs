@@ -10,11 +10,13 @@ use std::path::Path;
 use serde::Deserialize;
 
 use super::shared::cube::{
-    apply_uv_rotation, compute_cube_vertices, quad_to_triangles, scale_vec3, BLOCK_SCALE,
-    DEFAULT_UVS,
+    apply_uv_rotation, compute_cube_vertices, scale_vec3, BLOCK_SCALE, DEFAULT_UVS,
 };
-use super::shared::rotation::{rotate_vertices, RotationOrder, RotationTransform};
-use super::{FormatLoader, LoadError, LoadResult, ModelData, Triangle};
+use super::shared::mesh::ModelBuilder;
+use super::shared::rotation::{
+    pivot_node_transform, rotate_model_y_180, rotate_vertices, RotationOrder, RotationTransform,
+};
+use super::{FormatLoader, LoadError, LoadOptions, LoadResult, Material};
 
 pub struct McBedrockLoader;
 
@@ -43,6 +45,16 @@ impl FormatLoader for McBedrockLoader {
     }
 
     fn load_from_bytes(&self, data: &[u8]) -> LoadResult {
+        self.load_from_bytes_with_options(data, &LoadOptions::default())
+    }
+
+    fn load_from_path(&self, path: &Path) -> LoadResult {
+        let data = std::fs::read(path)?;
+        self.load_from_bytes(&data)
+    }
+
+    fn load_from_bytes_with_options(&self, data: &[u8], options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
         let text = std::str::from_utf8(data).map_err(|_| {
             LoadError::InvalidData("Invalid UTF-8 in Bedrock geometry file".to_string())
         })?;
@@ -50,13 +62,9 @@ impl FormatLoader for McBedrockLoader {
         let file: BedrockFile = serde_json::from_str(text).map_err(|e| {
             LoadError::InvalidData(format!("Failed to parse Bedrock geometry: {}", e))
         })?;
+        options.mark_parsed();
 
-        convert_bedrock_to_triangles(file)
-    }
-
-    fn load_from_path(&self, path: &Path) -> LoadResult {
-        let data = std::fs::read(path)?;
-        self.load_from_bytes(&data)
+        convert_bedrock_to_meshes(file)
     }
 }
 
@@ -118,6 +126,9 @@ struct BedrockFaceUv {
     uv_size: [f32; 2],
     #[serde(default)]
     uv_rotation: Option<f32>,
+    /// Named material slot, resolved by the entity or block definition.
+    #[serde(default)]
+    material_instance: Option<String>,
 }
 
 #[derive(Deserialize, Default)]
@@ -150,7 +161,7 @@ enum FaceName {
     Down,
 }
 
-fn convert_bedrock_to_triangles(file: BedrockFile) -> LoadResult {
+fn convert_bedrock_to_meshes(file: BedrockFile) -> LoadResult {
     let geometry = file
         .geometry
         .into_iter()
@@ -162,24 +173,75 @@ fn convert_bedrock_to_triangles(file: BedrockFile) -> LoadResult {
 
     let bone_chains = build_bone_rotation_chains(&geometry.bones);
 
-    let mut triangles = Vec::new();
+    let mut builder = ModelBuilder::new();
+    let bone_nodes = build_bone_nodes(&geometry.bones, &mut builder);
 
     for (bone_idx, bone) in geometry.bones.iter().enumerate() {
         let bone_chain = &bone_chains[bone_idx];
+        builder.set_node(Some(bone_nodes[bone_idx]));
 
         for cube in &bone.cubes {
-            let cube_tris = convert_bedrock_cube(cube, bone_chain, tex_width, tex_height);
-            triangles.extend(cube_tris);
+            convert_bedrock_cube(cube, bone_chain, tex_width, tex_height, &mut builder);
         }
     }
 
-    if triangles.is_empty() {
+    if builder.is_empty() {
         return Err(LoadError::NoGeometry);
     }
 
-    rotate_triangles_y_180(&mut triangles);
+    let mut model = builder.build();
+    rotate_model_y_180(&mut model);
 
-    Ok(ModelData { triangles })
+    Ok(model)
+}
+
+/// Adds a scene node for every bone and returns the node index of each bone.
+///
+/// Parents are created before their children regardless of file order; bones
+/// with missing or circular parents become roots.
+fn build_bone_nodes(bones: &[BedrockBone], builder: &mut ModelBuilder) -> Vec<usize> {
+    let name_to_idx: HashMap<&str, usize> = bones
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.name.as_str(), i))
+        .collect();
+    let parent_of = |idx: usize| {
+        bones[idx]
+            .parent
+            .as_deref()
+            .and_then(|name| name_to_idx.get(name))
+            .copied()
+    };
+
+    let mut nodes: Vec<Option<usize>> = vec![None; bones.len()];
+    for start in 0..bones.len() {
+        // Collect the not-yet-created ancestors of this bone, nearest first
+        let mut pending = Vec::new();
+        let mut current = Some(start);
+        while let Some(idx) = current {
+            if nodes[idx].is_some() || pending.contains(&idx) {
+                break;
+            }
+            pending.push(idx);
+            current = parent_of(idx);
+        }
+
+        for &idx in pending.iter().rev() {
+            let bone = &bones[idx];
+            let rotation = bone.rotation.and_then(|rotation| {
+                RotationTransform::new_if_non_zero_with_order(
+                    bone.pivot,
+                    rotation,
+                    RotationOrder::ZYX,
+                )
+            });
+            let parent = parent_of(idx).and_then(|p| nodes[p]);
+            let transform = pivot_node_transform(rotation, BLOCK_SCALE);
+            nodes[idx] = Some(builder.add_node(Some(bone.name.clone()), transform, parent));
+        }
+    }
+
+    nodes.into_iter().flatten().collect()
 }
 
 /// Builds rotation transform chains for each bone (bone → parent → ... → root).
@@ -241,8 +303,8 @@ fn convert_bedrock_cube(
     bone_chain: &[RotationTransform],
     tex_width: f32,
     tex_height: f32,
-) -> Vec<Triangle> {
-    let mut triangles = Vec::with_capacity(12);
+    builder: &mut ModelBuilder,
+) {
     let scale = BLOCK_SCALE;
 
     // Bedrock cubes: origin is min corner, size is dimensions
@@ -303,6 +365,10 @@ fn convert_bedrock_cube(
     let per_face = parse_per_face_uv(&cube.uv);
 
     let default_color = [0.85, 0.85, 0.85];
+    let default_material = builder.material(Material {
+        color: default_color,
+        ..Default::default()
+    });
 
     for (indices, face_name) in BEDROCK_FACE_DEFS {
         let face_uv = match face_name {
@@ -330,11 +396,18 @@ fn convert_bedrock_cube(
             DEFAULT_UVS
         };
 
-        let tris = quad_to_triangles(&vertices, indices, uvs, default_color, None);
-        triangles.extend(tris);
-    }
+        // Textures live in the entity definition; keep the material slot name only
+        let material = match face_uv.and_then(|f| f.material_instance.as_ref()) {
+            Some(name) => builder.material(Material {
+                name: Some(name.clone()),
+                color: default_color,
+                ..Default::default()
+            }),
+            None => default_material,
+        };
 
-    triangles
+        builder.push_quad(material, indices.map(|i| vertices[i]), uvs);
+    }
 }
 
 fn parse_per_face_uv(uv_value: &serde_json::Value) -> Option<BedrockPerFaceUv> {
@@ -344,31 +417,3 @@ fn parse_per_face_uv(uv_value: &serde_json::Value) -> Option<BedrockPerFaceUv> {
         None
     }
 }
-
-/// Rotates all triangles 180 degrees around the Y axis through their collective center.
-fn rotate_triangles_y_180(triangles: &mut [Triangle]) {
-    if triangles.is_empty() {
-        return;
-    }
-
-    let (min, max) = {
-        let mut min = glam::Vec3::splat(f32::INFINITY);
-        let mut max = glam::Vec3::splat(f32::NEG_INFINITY);
-        for tri in triangles.iter() {
-            for v in &tri.verts {
-                let p = glam::Vec3::from_array(*v);
-                min = min.min(p);
-                max = max.max(p);
-            }
-        }
-        (min, max)
-    };
-    let center = (min + max) * 0.5;
-
-    for tri in triangles.iter_mut() {
-        for v in &mut tri.verts {
-            v[0] = 2.0 * center.x - v[0];
-            v[2] = 2.0 * center.z - v[2];
-        }
-    }
-}
@@ -9,11 +9,13 @@ use std::path::Path;
 use serde::Deserialize;
 
 use super::shared::cube::{
-    apply_uv_rotation, compute_cube_vertices, quad_to_triangles, scale_vec3, BLOCK_SCALE,
-    DEFAULT_UVS,
+    apply_uv_rotation, compute_cube_vertices, scale_vec3, BLOCK_SCALE, DEFAULT_UVS,
 };
-use super::shared::rotation::{rotate_vertices, RotationOrder, RotationTransform};
-use super::{FormatLoader, LoadError, LoadResult, ModelData, Triangle};
+use super::shared::mesh::ModelBuilder;
+use super::shared::rotation::{
+    rotate_model_y_180, rotate_vertices, RotationOrder, RotationTransform,
+};
+use super::{FormatLoader, LoadError, LoadOptions, LoadResult, Material};
 
 pub struct McJavaLoader;
 
@@ -48,19 +50,25 @@ impl FormatLoader for McJavaLoader {
     }
 
     fn load_from_bytes(&self, data: &[u8]) -> LoadResult {
+        self.load_from_bytes_with_options(data, &LoadOptions::default())
+    }
+
+    fn load_from_path(&self, path: &Path) -> LoadResult {
+        let data = std::fs::read(path)?;
+        self.load_from_bytes(&data)
+    }
+
+    fn load_from_bytes_with_options(&self, data: &[u8], options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
         let text = std::str::from_utf8(data).map_err(|_| {
             LoadError::InvalidData("Invalid UTF-8 in MC Java model file".to_string())
         })?;
 
         let model: JavaModel = serde_json::from_str(text)
             .map_err(|e| LoadError::InvalidData(format!("Failed to parse MC Java model: {}", e)))?;
+        options.mark_parsed();
 
-        convert_java_model_to_triangles(model)
-    }
-
-    fn load_from_path(&self, path: &Path) -> LoadResult {
-        let data = std::fs::read(path)?;
-        self.load_from_bytes(&data)
+        convert_java_model_to_meshes(model)
     }
 }
 
@@ -136,8 +144,8 @@ enum FaceSlot {
     Down,
 }
 
-fn convert_java_model_to_triangles(model: JavaModel) -> LoadResult {
-    let mut triangles = Vec::new();
+fn convert_java_model_to_meshes(model: JavaModel) -> LoadResult {
+    let mut builder = ModelBuilder::new();
 
     // UV space size from texture_size or default 16x16
     let (tex_width, tex_height) = model
@@ -146,21 +154,25 @@ fn convert_java_model_to_triangles(model: JavaModel) -> LoadResult {
         .unwrap_or((16.0, 16.0));
 
     for element in &model.elements {
-        let cube_tris = convert_java_cube(element, tex_width, tex_height);
-        triangles.extend(cube_tris);
+        convert_java_cube(element, tex_width, tex_height, &mut builder);
     }
 
-    if triangles.is_empty() {
+    if builder.is_empty() {
         return Err(LoadError::NoGeometry);
     }
 
-    rotate_triangles_y_180(&mut triangles);
+    let mut model = builder.build();
+    rotate_model_y_180(&mut model);
 
-    Ok(ModelData { triangles })
+    Ok(model)
 }
 
-fn convert_java_cube(element: &JavaElement, tex_width: f32, tex_height: f32) -> Vec<Triangle> {
-    let mut triangles = Vec::with_capacity(12);
+fn convert_java_cube(
+    element: &JavaElement,
+    tex_width: f32,
+    tex_height: f32,
+    builder: &mut ModelBuilder,
+) {
     let scale = BLOCK_SCALE;
 
     let from = scale_vec3(element.from, scale);
@@ -206,9 +218,16 @@ fn convert_java_cube(element: &JavaElement, tex_width: f32, tex_height: f32) ->
         };
 
         // Skip faces without a texture reference
-        if face.texture.is_none() {
+        let Some(texture_ref) = &face.texture else {
             continue;
-        }
+        };
+
+        // Textures are external, so each texture variable gets a solid-color material
+        let material = builder.material(Material {
+            name: Some(texture_ref.clone()),
+            color: default_color,
+            ..Default::default()
+        });
 
         let uvs = if let Some(uv) = &face.uv {
             let u1 = uv[0] / tex_width;
@@ -222,37 +241,6 @@ fn convert_java_cube(element: &JavaElement, tex_width: f32, tex_height: f32) ->
             DEFAULT_UVS
         };
 
-        let tris = quad_to_triangles(&vertices, indices, uvs, default_color, None);
-        triangles.extend(tris);
-    }
-
-    triangles
-}
-
-/// Rotates all triangles 180 degrees around the Y axis through their collective center.
-fn rotate_triangles_y_180(triangles: &mut [Triangle]) {
-    if triangles.is_empty() {
-        return;
-    }
-
-    let (min, max) = {
-        let mut min = glam::Vec3::splat(f32::INFINITY);
-        let mut max = glam::Vec3::splat(f32::NEG_INFINITY);
-        for tri in triangles.iter() {
-            for v in &tri.verts {
-                let p = glam::Vec3::from_array(*v);
-                min = min.min(p);
-                max = max.max(p);
-            }
-        }
-        (min, max)
-    };
-    let center = (min + max) * 0.5;
-
-    for tri in triangles.iter_mut() {
-        for v in &mut tri.verts {
-            v[0] = 2.0 * center.x - v[0];
-            v[2] = 2.0 * center.z - v[2];
-        }
+        builder.push_quad(material, indices.map(|i| vertices[i]), uvs);
     }
 }
@@ -0,0 +1,690 @@
+//! Provides a decoder for `EXT_meshopt_compression` buffer views.
+//!
+//! glTF files processed by gltfpack and similar tools store vertex and index
+//! data compressed with the meshoptimizer codecs. Each compressed buffer view
+//! names a [`Mode`] (attributes, triangle indices or an index sequence) and an
+//! optional [`Filter`] applied to attribute data after decoding. [`decode`]
+//! expands one view into its raw bytes so the regular accessor readers can use it.
+//!
+//! # Examples
+//! ```
+//! use glimpse::formats::meshopt::{decode, Filter, Mode};
+//!
+//! // A single triangle 0, 1, 2 in the index sequence codec
+//! let encoded = [0xd1, 0x00, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00];
+//! let bytes = decode(Mode::Indices, Filter::None, 3, 2, &encoded).unwrap();
+//! assert_eq!(bytes, [0, 0, 1, 0, 2, 0]);
+//! ```
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use super::LoadError;
+
+/// Header nibble of the vertex attribute codec.
+const VERTEX_HEADER: u8 = 0xa0;
+/// Header nibble of the triangle index codec.
+const INDEX_HEADER: u8 = 0xe0;
+/// Header nibble of the index sequence codec.
+const SEQUENCE_HEADER: u8 = 0xd0;
+
+/// Scratch size that bounds how many vertices one attribute block holds.
+const VERTEX_BLOCK_SIZE_BYTES: usize = 8192;
+/// Maximum number of vertices in one attribute block.
+const VERTEX_BLOCK_MAX_SIZE: usize = 256;
+/// Number of bytes encoded together with one bit width.
+const BYTE_GROUP_SIZE: usize = 16;
+/// Largest number of bytes one byte group can read.
+const BYTE_GROUP_DECODE_LIMIT: usize = 24;
+/// Minimum size of the padding that ends an attribute stream.
+const TAIL_MAX_SIZE: usize = 32;
+
+/// Layout of a compressed buffer view.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::meshopt::Mode;
+///
+/// assert_ne!(Mode::Attributes, Mode::Triangles);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Interleaved vertex attributes, `stride` bytes per element.
+    Attributes,
+    /// Triangle list indices, 2 or 4 bytes each.
+    Triangles,
+    /// Any other index data, 2 or 4 bytes each.
+    Indices,
+}
+
+/// Post-processing applied to decoded attribute data.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::meshopt::Filter;
+///
+/// assert_ne!(Filter::None, Filter::Octahedral);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    /// Data is stored as is.
+    None,
+    /// Unit vectors stored as octahedral-encoded 8 or 16-bit components.
+    Octahedral,
+    /// Unit quaternions stored as three 16-bit components and the index of the largest one.
+    Quaternion,
+    /// Floats stored as a 24-bit mantissa and an 8-bit exponent.
+    Exponential,
+}
+
+/// Decodes `count` elements of `stride` bytes from a compressed buffer view.
+///
+/// # Errors
+/// Returns [`LoadError::InvalidData`] if the data is truncated or corrupt, or
+/// if `stride` does not suit the mode and filter.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::meshopt::{decode, Filter, Mode};
+///
+/// // A corrupt header is rejected instead of producing garbage
+/// assert!(decode(Mode::Triangles, Filter::None, 3, 2, &[0u8; 32]).is_err());
+/// ```
+pub fn decode(
+    mode: Mode,
+    filter: Filter,
+    count: usize,
+    stride: usize,
+    data: &[u8],
+) -> Result<Vec<u8>, LoadError> {
+    let mut bytes = match mode {
+        Mode::Attributes => decode_vertex_buffer(count, stride, data)?,
+        Mode::Triangles => encode_indices(&decode_index_buffer(count, data)?, stride)?,
+        Mode::Indices => encode_indices(&decode_index_sequence(count, data)?, stride)?,
+    };
+
+    match (mode, filter) {
+        (_, Filter::None) => {}
+        (Mode::Attributes, Filter::Octahedral) => filter_octahedral(&mut bytes, stride)?,
+        (Mode::Attributes, Filter::Quaternion) => filter_quaternion(&mut bytes, stride)?,
+        (Mode::Attributes, Filter::Exponential) => filter_exponential(&mut bytes, stride)?,
+        _ => return Err(invalid("filters only apply to attribute data")),
+    }
+    Ok(bytes)
+}
+
+fn invalid(reason: &str) -> LoadError {
+    LoadError::InvalidData(format!("Invalid meshopt data: {}", reason))
+}
+
+// ===========================================================================
+// Attribute codec
+// ===========================================================================
+
+/// Decodes delta-encoded vertex bytes, one byte lane at a time per block.
+fn decode_vertex_buffer(count: usize, stride: usize, data: &[u8]) -> Result<Vec<u8>, LoadError> {
+    if stride == 0 || stride > 256 || !stride.is_multiple_of(4) {
+        return Err(invalid(
+            "attribute stride must be a multiple of 4 up to 256",
+        ));
+    }
+    if data.len() < 1 + stride || data[0] & 0xf0 != VERTEX_HEADER {
+        return Err(invalid("bad attribute header"));
+    }
+    if data[0] & 0x0f != 0 {
+        return Err(invalid("unsupported attribute codec version"));
+    }
+
+    // The tail stores the first vertex, which seeds the deltas
+    let mut last = data[data.len() - stride..].to_vec();
+    let block_size =
+        ((VERTEX_BLOCK_SIZE_BYTES / stride) & !(BYTE_GROUP_SIZE - 1)).min(VERTEX_BLOCK_MAX_SIZE);
+
+    // Grow the output per block so truncated data cannot claim huge allocations
+    let mut vertices = Vec::new();
+    let mut deltas = [0u8; VERTEX_BLOCK_MAX_SIZE];
+    let mut pos = 1;
+    let mut first = 0;
+    while first < count {
+        let block = block_size.min(count - first);
+        vertices.resize((first + block) * stride, 0);
+        let aligned = block.next_multiple_of(BYTE_GROUP_SIZE);
+
+        for (k, lane) in last.iter_mut().enumerate() {
+            pos = decode_bytes(data, pos, &mut deltas[..aligned])?;
+            for (i, &delta) in deltas[..block].iter().enumerate() {
+                *lane = lane.wrapping_add(unzigzag8(delta));
+                vertices[(first + i) * stride + k] = *lane;
+            }
+        }
+        first += block;
+    }
+
+    if data.len() - pos != stride.max(TAIL_MAX_SIZE) {
+        return Err(invalid("attribute data has trailing bytes"));
+    }
+    Ok(vertices)
+}
+
+/// Decodes one lane of byte groups into `buffer`, returning the new read position.
+fn decode_bytes(data: &[u8], pos: usize, buffer: &mut [u8]) -> Result<usize, LoadError> {
+    // Two header bits per group give its bit width
+    let groups = buffer.len() / BYTE_GROUP_SIZE;
+    let header_size = groups.div_ceil(4);
+    let header = data
+        .get(pos..pos + header_size)
+        .ok_or_else(|| invalid("truncated attribute data"))?;
+    let mut pos = pos + header_size;
+
+    for (g, group) in buffer.chunks_exact_mut(BYTE_GROUP_SIZE).enumerate() {
+        // Guarantees the group's reads below stay in bounds
+        if data.len() - pos < BYTE_GROUP_DECODE_LIMIT {
+            return Err(invalid("truncated attribute data"));
+        }
+        let bitslog2 = (header[g / 4] >> ((g % 4) * 2)) & 3;
+        pos = decode_bytes_group(data, pos, group, bitslog2);
+    }
+    Ok(pos)
+}
+
+/// Decodes 16 bytes packed at 0, 2, 4 or 8 bits each.
+///
+/// Packed values with all bits set are escapes whose byte follows the packed data.
+fn decode_bytes_group(data: &[u8], pos: usize, group: &mut [u8], bitslog2: u8) -> usize {
+    match bitslog2 {
+        0 => {
+            group.fill(0);
+            pos
+        }
+        3 => {
+            group.copy_from_slice(&data[pos..pos + BYTE_GROUP_SIZE]);
+            pos + BYTE_GROUP_SIZE
+        }
+        _ => {
+            let bits = 1usize << bitslog2;
+            let per_byte = 8 / bits;
+            let escape = (1u8 << bits) - 1;
+            let mut extra = pos + BYTE_GROUP_SIZE / per_byte;
+            for (i, value) in group.iter_mut().enumerate() {
+                let shift = 8 - bits * (i % per_byte + 1);
+                let packed = (data[pos + i / per_byte] >> shift) & escape;
+                *value = if packed == escape {
+                    extra += 1;
+                    data[extra - 1]
+                } else {
+                    packed
+                };
+            }
+            extra
+        }
+    }
+}
+
+fn unzigzag8(v: u8) -> u8 {
+    (v & 1).wrapping_neg() ^ (v >> 1)
+}
+
+// ===========================================================================
+// Index codecs
+// ===========================================================================
+
+/// The auxiliary code table the encoder appends to triangle index data.
+const CODEAUX_TABLE_SIZE: usize = 16;
+
+/// Ring buffers of recently used edges and vertices, mirroring the encoder's state.
+struct Fifo {
+    edges: [[u32; 2]; 16],
+    edge_offset: usize,
+    vertices: [u32; 16],
+    vertex_offset: usize,
+}
+
+impl Default for Fifo {
+    fn default() -> Self {
+        Self {
+            edges: [[u32::MAX; 2]; 16],
+            edge_offset: 0,
+            vertices: [u32::MAX; 16],
+            vertex_offset: 0,
+        }
+    }
+}
+
+impl Fifo {
+    /// Returns the edge pushed `back` pushes ago (1 is the most recent).
+    fn edge(&self, back: usize) -> [u32; 2] {
+        self.edges[self.edge_offset.wrapping_sub(back) & 15]
+    }
+
+    /// Returns the vertex slot `back` places before the write position.
+    fn vertex(&self, back: usize) -> u32 {
+        self.vertices[self.vertex_offset.wrapping_sub(back) & 15]
+    }
+
+    fn push_edge(&mut self, a: u32, b: u32) {
+        self.edges[self.edge_offset] = [a, b];
+        self.edge_offset = (self.edge_offset + 1) & 15;
+    }
+
+    /// Writes a vertex, only advancing when it is new to the cache.
+    fn push_vertex(&mut self, v: u32, advance: bool) {
+        self.vertices[self.vertex_offset] = v;
+        self.vertex_offset = (self.vertex_offset + usize::from(advance)) & 15;
+    }
+}
+
+/// Decodes triangle indices encoded against a cache of recent edges and vertices.
+fn decode_index_buffer(count: usize, data: &[u8]) -> Result<Vec<u32>, LoadError> {
+    if !count.is_multiple_of(3) {
+        return Err(invalid("triangle index count must be a multiple of 3"));
+    }
+    if data.len() < 1 + count / 3 + CODEAUX_TABLE_SIZE || data[0] & 0xf0 != INDEX_HEADER {
+        return Err(invalid("bad triangle index header"));
+    }
+    let version = data[0] & 0x0f;
+    if version > 1 {
+        return Err(invalid("unsupported triangle index codec version"));
+    }
+
+    let mut fifo = Fifo::default();
+    let mut next = 0u32;
+    let mut last = 0u32;
+    // Version 1 reserves codes 13 and 14 for indices next to the last free index
+    let fec_max = if version >= 1 { 13 } else { 15 };
+
+    let codes = &data[1..1 + count / 3];
+    let safe_end = data.len() - CODEAUX_TABLE_SIZE;
+    let codeaux_table = &data[safe_end..];
+    let mut pos = 1 + count / 3;
+    let mut indices = Vec::with_capacity(count);
+
+    for &code in codes {
+        // Each triangle reads at most 16 bytes, which the code table guarantees
+        if pos > safe_end {
+            return Err(invalid("truncated triangle index data"));
+        }
+
+        let triangle = if code < 0xf0 {
+            // Reuse a recent edge plus a cached, new or free third vertex
+            let fe = usize::from(code >> 4);
+            let [a, b] = fifo.edge(1 + fe);
+            let fec = usize::from(code & 15);
+            let c = if fec < fec_max {
+                let c = if fec == 0 {
+                    next += 1;
+                    next - 1
+                } else {
+                    fifo.vertex(1 + fec)
+                };
+                fifo.push_vertex(c, fec == 0);
+                c
+            } else {
+                last = match fec {
+                    13 => last.wrapping_sub(1),
+                    14 => last.wrapping_add(1),
+                    _ => decode_index(data, &mut pos, last),
+                };
+                fifo.push_vertex(last, true);
+                last
+            };
+            fifo.push_edge(c, b);
+            fifo.push_edge(a, c);
+            [a, b, c]
+        } else {
+            // A triangle with no cached edge; its vertex codes come from the table or the stream
+            let (a, b, c, feb, fec) = if code < 0xfe {
+                let codeaux = codeaux_table[usize::from(code & 15)];
+                let (feb, fec) = (usize::from(codeaux >> 4), usize::from(codeaux & 15));
+                let a = next;
+                next += 1;
+                let b = if feb == 0 {
+                    next += 1;
+                    next - 1
+                } else {
+                    fifo.vertex(feb)
+                };
+                let c = if fec == 0 {
+                    next += 1;
+                    next - 1
+                } else {
+                    fifo.vertex(fec)
+                };
+                (a, b, c, feb, fec)
+            } else {
+                let codeaux = data[pos];
+                pos += 1;
+                if codeaux == 0 {
+                    next = 0;
+                }
+                let fea = if code == 0xfe { 0 } else { 15 };
+                let (feb, fec) = (usize::from(codeaux >> 4), usize::from(codeaux & 15));
+                let read = |fe: usize, next: &mut u32| match fe {
+                    0 => {
+                        *next += 1;
+                        *next - 1
+                    }
+                    15 => 0,
+                    _ => fifo.vertex(fe),
+                };
+                let mut a = read(fea, &mut next);
+                let mut b = read(feb, &mut next);
+                let mut c = read(fec, &mut next);
+                // Free indices are delta-encoded against the previous free index
+                for (fe, v) in [(fea, &mut a), (feb, &mut b), (fec, &mut c)] {
+                    if fe == 15 {
+                        last = decode_index(data, &mut pos, last);
+                        *v = last;
+                    }
+                }
+                (a, b, c, feb, fec)
+            };
+            fifo.push_vertex(a, true);
+            fifo.push_vertex(b, feb == 0 || feb == 15);
+            fifo.push_vertex(c, fec == 0 || fec == 15);
+            fifo.push_edge(b, a);
+            fifo.push_edge(c, b);
+            fifo.push_edge(a, c);
+            [a, b, c]
+        };
+        indices.extend(triangle);
+    }
+
+    if pos != safe_end {
+        return Err(invalid("triangle index data has trailing bytes"));
+    }
+    Ok(indices)
+}
+
+/// Decodes indices stored as deltas against one of two running baselines.
+fn decode_index_sequence(count: usize, data: &[u8]) -> Result<Vec<u32>, LoadError> {
+    const TAIL_SIZE: usize = 4;
+    if data.len() < 1 + count + TAIL_SIZE || data[0] & 0xf0 != SEQUENCE_HEADER {
+        return Err(invalid("bad index sequence header"));
+    }
+    if data[0] & 0x0f > 1 {
+        return Err(invalid("unsupported index sequence codec version"));
+    }
+
+    let safe_end = data.len() - TAIL_SIZE;
+    let mut last = [0u32; 2];
+    let mut pos = 1;
+    let mut indices = Vec::with_capacity(count);
+    for _ in 0..count {
+        // Each index reads at most 5 bytes, which the tail guarantees
+        if pos >= safe_end {
+            return Err(invalid("truncated index sequence data"));
+        }
+        let v = decode_vbyte(data, &mut pos);
+        let baseline = &mut last[(v & 1) as usize];
+        *baseline = baseline.wrapping_add(unzigzag32(v >> 1));
+        indices.push(*baseline);
+    }
+
+    if pos != safe_end {
+        return Err(invalid("index sequence data has trailing bytes"));
+    }
+    Ok(indices)
+}
+
+/// Reads a little-endian base-128 integer of up to 5 bytes.
+fn decode_vbyte(data: &[u8], pos: &mut usize) -> u32 {
+    let lead = data[*pos];
+    *pos += 1;
+    if lead < 128 {
+        return u32::from(lead);
+    }
+
+    let mut result = u32::from(lead & 127);
+    let mut shift = 7;
+    for _ in 0..4 {
+        let group = data[*pos];
+        *pos += 1;
+        result |= u32::from(group & 127) << shift;
+        shift += 7;
+        if group < 128 {
+            break;
+        }
+    }
+    result
+}
+
+fn decode_index(data: &[u8], pos: &mut usize, last: u32) -> u32 {
+    last.wrapping_add(unzigzag32(decode_vbyte(data, pos)))
+}
+
+fn unzigzag32(v: u32) -> u32 {
+    (v >> 1) ^ (v & 1).wrapping_neg()
+}
+
+/// Writes indices as little-endian 16 or 32-bit integers.
+fn encode_indices(indices: &[u32], index_size: usize) -> Result<Vec<u8>, LoadError> {
+    match index_size {
+        2 => Ok(indices
+            .iter()
+            .flat_map(|&i| (i as u16).to_le_bytes())
+            .collect()),
+        4 => Ok(indices.iter().flat_map(|&i| i.to_le_bytes()).collect()),
+        _ => Err(invalid("index stride must be 2 or 4")),
+    }
+}
+
+// ===========================================================================
+// Filters
+// ===========================================================================
+
+/// Reconstructs unit vectors from octahedral X/Y and a Z holding the encoded one.
+fn filter_octahedral(data: &mut [u8], stride: usize) -> Result<(), LoadError> {
+    let (size, max) = match stride {
+        4 => (1, 127.0),
+        8 => (2, 32767.0),
+        _ => return Err(invalid("octahedral filter needs a stride of 4 or 8")),
+    };
+
+    for element in data.chunks_exact_mut(stride) {
+        let mut x = read_signed(element, 0, size);
+        let mut y = read_signed(element, 1, size);
+        let z = read_signed(element, 2, size) - x.abs() - y.abs();
+
+        // Unfold the lower hemisphere
+        let t = z.min(0.0);
+        x += if x >= 0.0 { t } else { -t };
+        y += if y >= 0.0 { t } else { -t };
+
+        let scale = max / (x * x + y * y + z * z).sqrt();
+        for (i, v) in [x, y, z].into_iter().enumerate() {
+            write_signed(element, i, size, round_signed(v * scale));
+        }
+    }
+    Ok(())
+}
+
+/// Reconstructs unit quaternions from their three smallest components.
+fn filter_quaternion(data: &mut [u8], stride: usize) -> Result<(), LoadError> {
+    if stride != 8 {
+        return Err(invalid("quaternion filter needs a stride of 8"));
+    }
+
+    for element in data.chunks_exact_mut(stride) {
+        let packed = read_signed(element, 3, 2) as i16;
+        // The low bits of the last component hold the index of the omitted one
+        let scale = FRAC_1_SQRT_2 / f32::from(packed | 3);
+        let [x, y, z] = [0, 1, 2].map(|i| read_signed(element, i, 2) * scale);
+        let w = (1.0 - x * x - y * y - z * z).max(0.0).sqrt();
+
+        let largest = (packed & 3) as usize;
+        for (offset, v) in [x, y, z].into_iter().enumerate() {
+            write_signed(
+                element,
+                (largest + 1 + offset) & 3,
+                2,
+                round_signed(v * 32767.0),
+            );
+        }
+        write_signed(element, largest, 2, (w * 32767.0 + 0.5) as i32);
+    }
+    Ok(())
+}
+
+/// Expands 32-bit words holding a signed 24-bit mantissa and signed 8-bit exponent to floats.
+fn filter_exponential(data: &mut [u8], stride: usize) -> Result<(), LoadError> {
+    if !stride.is_multiple_of(4) {
+        return Err(invalid("exponential filter needs a stride divisible by 4"));
+    }
+
+    for word in data.chunks_exact_mut(4) {
+        let v = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+        let mantissa = ((v << 8) as i32) >> 8;
+        let exponent = (v as i32) >> 24;
+        let value = f32::from_bits(((exponent + 127) as u32) << 23) * mantissa as f32;
+        word.copy_from_slice(&value.to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Reads component `index` of `size` bytes (1 or 2) as a signed integer.
+fn read_signed(element: &[u8], index: usize, size: usize) -> f32 {
+    match size {
+        1 => f32::from(element[index] as i8),
+        _ => f32::from(i16::from_le_bytes([
+            element[index * 2],
+            element[index * 2 + 1],
+        ])),
+    }
+}
+
+/// Writes component `index` of `size` bytes (1 or 2), truncating to its width.
+fn write_signed(element: &mut [u8], index: usize, size: usize, value: i32) {
+    match size {
+        1 => element[index] = value as i8 as u8,
+        _ => element[index * 2..index * 2 + 2].copy_from_slice(&(value as i16).to_le_bytes()),
+    }
+}
+
+/// Rounds half away from zero, matching the reference decoder.
+fn round_signed(v: f32) -> i32 {
+    (v + if v >= 0.0 { 0.5 } else { -0.5 }) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes vertices with every byte group stored raw, the simplest valid stream.
+    fn encode_vertices(data: &[u8], stride: usize) -> Vec<u8> {
+        let count = data.len() / stride;
+        let block_size = ((VERTEX_BLOCK_SIZE_BYTES / stride) & !(BYTE_GROUP_SIZE - 1))
+            .min(VERTEX_BLOCK_MAX_SIZE);
+        let mut out = vec![VERTEX_HEADER];
+        let mut last = data[..stride].to_vec();
+        for first in (0..count).step_by(block_size) {
+            let block = block_size.min(count - first);
+            let aligned = block.next_multiple_of(BYTE_GROUP_SIZE);
+            for (k, lane) in last.iter_mut().enumerate() {
+                let mut deltas = vec![0u8; aligned];
+                for (i, delta) in deltas[..block].iter_mut().enumerate() {
+                    let v = data[(first + i) * stride + k];
+                    let d = v.wrapping_sub(*lane);
+                    *delta = (d << 1) ^ ((d as i8) >> 7) as u8;
+                    *lane = v;
+                }
+                out.extend(vec![0xff; (aligned / BYTE_GROUP_SIZE).div_ceil(4)]);
+                out.extend(deltas);
+            }
+        }
+        out.extend(vec![0; stride.max(TAIL_MAX_SIZE) - stride]);
+        out.extend(&data[..stride]);
+        out
+    }
+
+    #[test]
+    fn test_attributes_round_trip_across_blocks() {
+        // 300 vertices span two blocks of 256
+        let data: Vec<u8> = (0..300u32 * 8).map(|i| (i * 7 % 251) as u8).collect();
+        let encoded = encode_vertices(&data, 8);
+        let decoded = decode(Mode::Attributes, Filter::None, 300, 8, &encoded).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_packed_byte_groups_and_escapes() {
+        // Lane 0 packs 2-bit deltas 0, -1, +1 and an escaped +64; lanes 1-3 are all zero
+        let mut encoded = vec![VERTEX_HEADER, 0x01, 0x1b, 0x00, 0x00, 0x00, 0x80];
+        encoded.extend([0x00; 3]);
+        encoded.extend([0; 28]);
+        encoded.extend([10, 0, 0, 0]);
+
+        let decoded = decode(Mode::Attributes, Filter::None, 16, 4, &encoded).unwrap();
+        let lane0: Vec<u8> = decoded.chunks(4).map(|v| v[0]).collect();
+        assert_eq!(&lane0[..5], &[10, 9, 10, 74, 74]);
+        assert!(decoded.chunks(4).all(|v| v[1..] == [0, 0, 0]));
+    }
+
+    #[test]
+    fn test_truncated_attributes_are_rejected() {
+        let data: Vec<u8> = (0..64).collect();
+        let encoded = encode_vertices(&data, 4);
+        let truncated = &encoded[..encoded.len() - 8];
+        assert!(decode(Mode::Attributes, Filter::None, 16, 4, truncated).is_err());
+    }
+
+    #[test]
+    fn test_triangle_indices_reuse_edges() {
+        // Triangle 0 1 2 from the code table, then 2 1 3 reusing edge 2-1 with a new vertex
+        let mut encoded = vec![INDEX_HEADER | 1, 0xf0, 0x10];
+        encoded.extend([
+            0x00, 0x76, 0x87, 0x56, 0x67, 0x78, 0xa9, 0x86, 0x65, 0x89, 0x68, 0x98, 0x01, 0x69,
+            0x00, 0x00,
+        ]);
+        let decoded = decode(Mode::Triangles, Filter::None, 6, 4, &encoded).unwrap();
+        let indices: Vec<u32> = decoded
+            .chunks(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        assert_eq!(indices, [0, 1, 2, 2, 1, 3]);
+    }
+
+    #[test]
+    fn test_index_sequence_uses_two_baselines() {
+        // 5 on baseline 0, then 100 on baseline 1, then 6 back on baseline 0
+        let encoded = [SEQUENCE_HEADER | 1, 20, 0x91, 0x03, 4, 0, 0, 0, 0];
+        let decoded = decode(Mode::Indices, Filter::None, 3, 2, &encoded).unwrap();
+        assert_eq!(decoded, [5, 0, 100, 0, 6, 0]);
+    }
+
+    #[test]
+    fn test_octahedral_filter() {
+        // +Z and +X encoded as 8-bit octahedral coordinates
+        let mut data = vec![0, 0, 127, 0, 127, 0, 127, 0];
+        filter_octahedral(&mut data, 4).unwrap();
+        assert_eq!(data, [0, 0, 127, 0, 127, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_quaternion_filter() {
+        // Identity with W (index 3) omitted and X/Y/Z all zero
+        let mut data = vec![0, 0, 0, 0, 0, 0, 3, 0];
+        filter_quaternion(&mut data, 8).unwrap();
+        let components: Vec<i16> = data
+            .chunks(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(components, [0, 0, 0, 32767]);
+    }
+
+    #[test]
+    fn test_exponential_filter() {
+        // 6 * 2^-2
+        let word = (((-2i32) as u32) << 24) | 6;
+        let mut data = word.to_le_bytes().to_vec();
+        filter_exponential(&mut data, 4).unwrap();
+        assert_eq!(
+            f32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+            1.5
+        );
+    }
+
+    #[test]
+    fn test_filters_only_apply_to_attributes() {
+        let encoded = [SEQUENCE_HEADER, 0, 4, 4, 0, 0, 0, 0];
+        assert!(decode(Mode::Indices, Filter::Octahedral, 3, 2, &encoded).is_err());
+    }
+}
@@ -13,15 +13,25 @@
 //! ```
 
 pub mod bbmodel;
+#[cfg(feature = "draco")]
+pub mod draco;
 pub mod gltf;
 pub mod mc_bedrock;
 pub mod mc_java;
+pub mod meshopt;
 pub mod obj;
+pub mod resolver;
 pub mod shared;
+pub mod simplify;
 pub mod vintagestory;
 
+use std::ops::Range;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::budget::{Budget, Interrupted};
+use resolver::{DirectoryResolver, ResourceResolver};
 
 // ---- Math type aliases ----
 /// A 2D vector type used by format loaders.
@@ -99,11 +109,31 @@ impl TextureData {
     /// assert_eq!(sample, [1.0, 1.0, 1.0, 1.0]);
     /// ```
     pub fn sample(&self, u: f32, v: f32) -> [f32; 4] {
-        // Wrap UVs to [0, 1)
-        let u = u.fract();
-        let v = v.fract();
-        let u = if u < 0.0 { u + 1.0 } else { u };
-        let v = if v < 0.0 { v + 1.0 } else { v };
+        self.sample_with(u, v, WrapMode::Repeat)
+    }
+
+    /// Samples the texture at UV coordinates, handling coordinates outside
+    /// `0.0..1.0` as `wrap` dictates.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{TextureData, WrapMode};
+    ///
+    /// // Black on the left, white on the right
+    /// let tex = TextureData {
+    ///     width: 2,
+    ///     height: 1,
+    ///     data: vec![0, 0, 0, 255, 255, 255, 255, 255],
+    /// };
+    /// assert_eq!(tex.sample_with(1.25, 0.5, WrapMode::Repeat)[0], 0.0);
+    /// assert_eq!(tex.sample_with(1.25, 0.5, WrapMode::Clamp)[0], 1.0);
+    /// ```
+    pub fn sample_with(&self, u: f32, v: f32, wrap: WrapMode) -> [f32; 4] {
+        let (u, v) = match wrap {
+            // Wrap UVs to [0, 1)
+            WrapMode::Repeat => (u.rem_euclid(1.0), v.rem_euclid(1.0)),
+            WrapMode::Clamp => (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0)),
+        };
 
         let x = ((u * self.width as f32) as u32).min(self.width.saturating_sub(1));
         let y = ((v * self.height as f32) as u32).min(self.height.saturating_sub(1));
@@ -122,7 +152,24 @@ impl TextureData {
     }
 }
 
-/// Represents a triangle with position, UV, color, and optional texture.
+/// Controls how textures are sampled outside the `0.0..1.0` UV range.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::WrapMode;
+///
+/// assert_eq!(WrapMode::default(), WrapMode::Repeat);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WrapMode {
+    /// The texture tiles across the surface.
+    #[default]
+    Repeat,
+    /// Coordinates are clamped, repeating the texture's edge pixels.
+    Clamp,
+}
+
+/// Represents a standalone triangle with position, UV, color, and optional texture.
 ///
 /// # Examples
 /// ```
@@ -143,6 +190,7 @@ impl TextureData {
 /// };
 /// let _ = tri;
 /// ```
+#[derive(Clone)]
 pub struct Triangle {
     /// Triangle vertex positions.
     pub verts: [Vec3; 3],
@@ -154,8 +202,439 @@ pub struct Triangle {
     pub texture: Option<Arc<TextureData>>,
 }
 
+/// Controls how a material's alpha is interpreted.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::AlphaMode;
+///
+/// let mode = AlphaMode::Mask { cutoff: 0.5 };
+/// assert_ne!(mode, AlphaMode::Opaque);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AlphaMode {
+    /// Alpha is ignored; the surface is fully opaque.
+    Opaque,
+    /// Fragments with alpha below `cutoff` are discarded, others are opaque.
+    Mask {
+        /// Alpha threshold below which fragments are discarded.
+        cutoff: f32,
+    },
+    /// Fragments are alpha-blended over what is behind them.
+    Blend,
+}
+
+/// Controls how a material's fragments combine with the image.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::RenderMode;
+///
+/// assert_eq!(RenderMode::default(), RenderMode::Normal);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Fragments replace (or blend over) the image as dictated by the alpha mode.
+    #[default]
+    Normal,
+    /// Fragments are added onto the image, e.g. for glow effects.
+    Additive,
+}
+
+/// Describes how a mesh's surface looks.
+///
+/// The default material is a white, double-sided surface with a 0.5 alpha
+/// cutoff, which matches how block-model formats are usually displayed.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::{AlphaMode, Material};
+///
+/// let material = Material::default();
+/// assert_eq!(material.color, [1.0, 1.0, 1.0]);
+/// assert_eq!(material.alpha_mode, AlphaMode::Mask { cutoff: 0.5 });
+/// assert!(material.double_sided);
+/// ```
+#[derive(Clone)]
+pub struct Material {
+    /// Optional material name from the source file.
+    pub name: Option<String>,
+    /// Base RGB color, multiplied with the texture and vertex colors.
+    pub color: [f32; 3],
+    /// Base opacity, multiplied with the texture's alpha.
+    pub alpha: f32,
+    /// Optional base color texture.
+    pub texture: Option<Arc<TextureData>>,
+    /// How alpha is interpreted.
+    pub alpha_mode: AlphaMode,
+    /// Whether back faces are drawn. Single-sided back faces are culled.
+    pub double_sided: bool,
+    /// Emitted RGB light, added after shading. May exceed 1.0 for strong emitters.
+    pub emissive: [f32; 3],
+    /// Optional texture multiplied with `emissive`.
+    pub emissive_texture: Option<Arc<TextureData>>,
+    /// Perceptual roughness in `0.0..=1.0`; rougher surfaces have dimmer, wider highlights.
+    pub roughness: f32,
+    /// Metalness in `0.0..=1.0`; metals have darker diffuse and tinted highlights.
+    pub metallic: f32,
+    /// How fragments combine with the image.
+    pub render_mode: RenderMode,
+    /// Shows the base color as-is, without lighting.
+    pub unlit: bool,
+    /// RGB tint and strength of specular highlights.
+    pub specular: [f32; 3],
+    /// How the material's textures are sampled outside the `0.0..1.0` UV range.
+    pub wrap: WrapMode,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        DEFAULT_MATERIAL.clone()
+    }
+}
+
+impl PartialEq for Material {
+    /// Compares all properties; textures are compared by identity.
+    fn eq(&self, other: &Self) -> bool {
+        fn same_texture(a: &Option<Arc<TextureData>>, b: &Option<Arc<TextureData>>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+        }
+
+        self.name == other.name
+            && self.color == other.color
+            && self.alpha == other.alpha
+            && same_texture(&self.texture, &other.texture)
+            && self.alpha_mode == other.alpha_mode
+            && self.double_sided == other.double_sided
+            && self.emissive == other.emissive
+            && same_texture(&self.emissive_texture, &other.emissive_texture)
+            && self.roughness == other.roughness
+            && self.metallic == other.metallic
+            && self.render_mode == other.render_mode
+            && self.unlit == other.unlit
+            && self.specular == other.specular
+            && self.wrap == other.wrap
+    }
+}
+
+/// Material used by default and when a mesh refers to a missing material table entry.
+static DEFAULT_MATERIAL: Material = Material {
+    name: None,
+    color: [1.0, 1.0, 1.0],
+    alpha: 1.0,
+    texture: None,
+    alpha_mode: AlphaMode::Mask { cutoff: 0.5 },
+    double_sided: true,
+    emissive: [0.0, 0.0, 0.0],
+    emissive_texture: None,
+    roughness: 0.5,
+    metallic: 0.0,
+    render_mode: RenderMode::Normal,
+    unlit: false,
+    specular: [1.0, 1.0, 1.0],
+    wrap: WrapMode::Repeat,
+};
+
+/// Represents an indexed mesh with shared vertex buffers.
+///
+/// `positions`, `uvs` and (if present) `normals` and `colors` are parallel arrays; every
+/// three entries of `indices` form one triangle, every two entries of `lines`
+/// one line segment, and every entry of `points` one point. Lines and points
+/// are drawn unlit in screen space.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::Mesh;
+///
+/// let mesh = Mesh {
+///     positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]],
+///     uvs: vec![[0.0, 0.0]; 4],
+///     normals: None,
+///     colors: None,
+///     indices: vec![0, 1, 2, 2, 1, 3],
+///     lines: vec![0, 3],
+///     points: Vec::new(),
+///     material: 0,
+/// };
+/// assert_eq!(mesh.triangle_count(), 2);
+/// ```
+#[derive(Clone, Default)]
+pub struct Mesh {
+    /// Vertex positions.
+    pub positions: Vec<Vec3>,
+    /// Vertex UV coordinates, one per position.
+    pub uvs: Vec<Vec2>,
+    /// Optional vertex normals, one per position, for smooth shading.
+    ///
+    /// Meshes without normals are flat-shaded, as are vertices whose normal is zero.
+    pub normals: Option<Vec<Vec3>>,
+    /// Optional RGB vertex colors, one per position.
+    pub colors: Option<Vec<[f32; 3]>>,
+    /// Triangle list indices into the vertex buffers.
+    pub indices: Vec<u32>,
+    /// Line list indices into the vertex buffers.
+    pub lines: Vec<u32>,
+    /// Point list indices into the vertex buffers.
+    pub points: Vec<u32>,
+    /// Index into [`ModelData::materials`].
+    pub material: usize,
+}
+
+impl Mesh {
+    /// Returns the number of triangles in this mesh.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::Mesh;
+    ///
+    /// assert_eq!(Mesh::default().triangle_count(), 0);
+    /// ```
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Returns the vertex indices of triangle `index`.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::Mesh;
+    ///
+    /// let mesh = Mesh {
+    ///     indices: vec![0, 1, 2],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(mesh.triangle_indices(0), [0, 1, 2]);
+    /// ```
+    pub fn triangle_indices(&self, index: usize) -> [usize; 3] {
+        let i = index * 3;
+        [
+            self.indices[i] as usize,
+            self.indices[i + 1] as usize,
+            self.indices[i + 2] as usize,
+        ]
+    }
+}
+
+/// Identity transform used by nodes without their own placement.
+const IDENTITY: Mat4 = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// A named part of a model's scene tree, such as a glTF node, a Blockbench
+/// group or element, a Bedrock bone or a Vintage Story element.
+///
+/// Mesh positions are already in model space, so transforms are informational:
+/// they describe where a node sits relative to its parent for tools that want
+/// to re-pose or highlight parts.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::Node;
+///
+/// let node = Node {
+///     name: Some("head".to_string()),
+///     meshes: 0..2,
+///     ..Default::default()
+/// };
+/// assert!(node.parent.is_none());
+/// assert_eq!(node.meshes.len(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    /// Name from the source file, if any.
+    pub name: Option<String>,
+    /// Column-major transform relative to the parent node.
+    pub transform: Mat4,
+    /// Index of the parent in [`ModelData::nodes`], or `None` for a root.
+    pub parent: Option<usize>,
+    /// Indices of child nodes in [`ModelData::nodes`].
+    pub children: Vec<usize>,
+    /// Range of [`ModelData::meshes`] owned directly by this node.
+    pub meshes: Range<usize>,
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Self {
+            name: None,
+            transform: IDENTITY,
+            parent: None,
+            children: Vec::new(),
+            meshes: 0..0,
+        }
+    }
+}
+
+/// A camera authored in the source file, such as a glTF camera node.
+///
+/// The camera looks down the local -Z axis of `transform` with +Y up.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::{Camera, Projection};
+///
+/// let camera = Camera {
+///     name: Some("Front".to_string()),
+///     projection: Projection::Perspective {
+///         yfov: 0.8,
+///         znear: 0.1,
+///         zfar: None,
+///     },
+///     ..Default::default()
+/// };
+/// assert_eq!(camera.transform[3], [0.0, 0.0, 0.0, 1.0]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Camera {
+    /// Name of the camera or its node, if any.
+    pub name: Option<String>,
+    /// Column-major model-space transform of the camera.
+    pub transform: Mat4,
+    /// How the camera projects the scene.
+    pub projection: Projection,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            name: None,
+            transform: IDENTITY,
+            projection: Projection::Perspective {
+                yfov: 45.0_f32.to_radians(),
+                znear: 0.01,
+                zfar: None,
+            },
+        }
+    }
+}
+
+/// The projection of a [`Camera`].
+///
+/// The horizontal extent always follows the thumbnail's aspect ratio, so only
+/// the vertical field of view or magnification is kept.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::Projection;
+///
+/// let ortho = Projection::Orthographic {
+///     ymag: 2.0,
+///     znear: 0.0,
+///     zfar: 100.0,
+/// };
+/// assert!(matches!(ortho, Projection::Orthographic { .. }));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// Perspective projection; `zfar` is `None` for an infinite projection.
+    Perspective {
+        /// Vertical field of view in radians.
+        yfov: f32,
+        /// Distance to the near clipping plane.
+        znear: f32,
+        /// Distance to the far clipping plane.
+        zfar: Option<f32>,
+    },
+    /// Orthographic projection.
+    Orthographic {
+        /// Half the height of the view volume.
+        ymag: f32,
+        /// Distance to the near clipping plane.
+        znear: f32,
+        /// Distance to the far clipping plane.
+        zfar: f32,
+    },
+}
+
+/// A punctual light authored in the source file, such as a
+/// `KHR_lights_punctual` light.
+///
+/// Directional and spot lights shine down the local -Z axis of `transform`.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::{Light, LightKind};
+///
+/// let sun = Light {
+///     kind: LightKind::Directional,
+///     intensity: 3.0,
+///     ..Default::default()
+/// };
+/// assert_eq!(sun.color, [1.0, 1.0, 1.0]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Light {
+    /// Name of the light or its node, if any.
+    pub name: Option<String>,
+    /// Column-major model-space transform of the light.
+    pub transform: Mat4,
+    /// The type of light.
+    pub kind: LightKind,
+    /// Linear RGB color.
+    pub color: [f32; 3],
+    /// Brightness in lux for directional lights and candela otherwise.
+    pub intensity: f32,
+    /// Distance beyond which point and spot lights have no effect.
+    pub range: Option<f32>,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self {
+            name: None,
+            transform: IDENTITY,
+            kind: LightKind::Point,
+            color: [1.0, 1.0, 1.0],
+            intensity: 1.0,
+            range: None,
+        }
+    }
+}
+
+/// The type of a [`Light`].
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::LightKind;
+///
+/// let spot = LightKind::Spot {
+///     inner_cone_angle: 0.0,
+///     outer_cone_angle: std::f32::consts::FRAC_PI_4,
+/// };
+/// assert_ne!(spot, LightKind::Point);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LightKind {
+    /// Parallel light coming from infinitely far away.
+    Directional,
+    /// Light emitted equally in all directions from a point.
+    Point,
+    /// Light emitted in a cone from a point.
+    Spot {
+        /// Angle in radians at which the falloff starts.
+        inner_cone_angle: f32,
+        /// Angle in radians at which the light reaches zero.
+        outer_cone_angle: f32,
+    },
+}
+
 /// Represents loaded model data ready for rendering.
 ///
+/// Geometry is stored as indexed [`Mesh`]es that reference a shared
+/// [`Material`] table, so vertices and textures are not duplicated per
+/// triangle. Use [`ModelData::triangles`] to iterate over standalone
+/// [`Triangle`]s instead.
+///
+/// Loaders that know the source hierarchy also fill [`ModelData::nodes`],
+/// so parts can be listed, hidden or highlighted by name.
+///
 /// # Examples
 /// ```
 /// use glimpse::formats::{ModelData, Triangle};
@@ -166,12 +645,238 @@ pub struct Triangle {
 ///     color: [1.0, 1.0, 1.0],
 ///     texture: None,
 /// };
-/// let model = ModelData { triangles: vec![tri] };
-/// assert_eq!(model.triangles.len(), 1);
+/// let model = ModelData::from_triangles(vec![tri]);
+/// assert_eq!(model.triangle_count(), 1);
 /// ```
+#[derive(Clone, Default)]
 pub struct ModelData {
-    /// Triangles ready for rasterization.
-    pub triangles: Vec<Triangle>,
+    /// Meshes ready for rasterization.
+    pub meshes: Vec<Mesh>,
+    /// Materials referenced by [`Mesh::material`].
+    pub materials: Vec<Material>,
+    /// Optional scene tree; empty if the source has no hierarchy.
+    ///
+    /// Parents always precede their children.
+    pub nodes: Vec<Node>,
+    /// Cameras authored in the source file, in document order.
+    pub cameras: Vec<Camera>,
+    /// Punctual lights authored in the source file.
+    pub lights: Vec<Light>,
+}
+
+impl ModelData {
+    /// Builds model data from standalone triangles.
+    ///
+    /// Triangles sharing a color and texture are grouped into one mesh with a
+    /// default [`Material`], and identical vertices within a mesh are shared.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{ModelData, Triangle};
+    ///
+    /// let tri = |x: f32| Triangle {
+    ///     verts: [[x, 0.0, 0.0], [x + 1.0, 0.0, 0.0], [x, 1.0, 0.0]],
+    ///     uvs: [[0.0, 0.0]; 3],
+    ///     color: [1.0, 0.0, 0.0],
+    ///     texture: None,
+    /// };
+    /// let model = ModelData::from_triangles(vec![tri(0.0), tri(1.0)]);
+    /// assert_eq!(model.meshes.len(), 1);
+    /// assert_eq!(model.meshes[0].positions.len(), 5);
+    /// ```
+    pub fn from_triangles(triangles: impl IntoIterator<Item = Triangle>) -> Self {
+        let mut builder = shared::mesh::ModelBuilder::new();
+        for tri in triangles {
+            let material = builder.material(Material {
+                color: tri.color,
+                texture: tri.texture,
+                ..Default::default()
+            });
+            builder.push_triangle(material, tri.verts, tri.uvs);
+        }
+        builder.build()
+    }
+
+    /// Appends a material and returns its index.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{Material, ModelData};
+    ///
+    /// let mut model = ModelData::default();
+    /// assert_eq!(model.add_material(Material::default()), 0);
+    /// assert_eq!(model.add_material(Material::default()), 1);
+    /// ```
+    pub fn add_material(&mut self, material: Material) -> usize {
+        self.materials.push(material);
+        self.materials.len() - 1
+    }
+
+    /// Returns the material at `index`, or a plain white material if it is missing.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::ModelData;
+    ///
+    /// let model = ModelData::default();
+    /// assert_eq!(model.material(3).color, [1.0, 1.0, 1.0]);
+    /// ```
+    pub fn material(&self, index: usize) -> &Material {
+        self.materials.get(index).unwrap_or(&DEFAULT_MATERIAL)
+    }
+
+    /// Returns the total number of triangles across all meshes.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::ModelData;
+    ///
+    /// assert_eq!(ModelData::default().triangle_count(), 0);
+    /// ```
+    pub fn triangle_count(&self) -> usize {
+        self.meshes.iter().map(Mesh::triangle_count).sum()
+    }
+
+    /// Returns the total number of triangles, line segments and points across
+    /// all meshes.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{Mesh, ModelData};
+    ///
+    /// let model = ModelData {
+    ///     meshes: vec![Mesh {
+    ///         indices: vec![0, 1, 2],
+    ///         lines: vec![0, 1],
+    ///         points: vec![0, 1, 2],
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(model.primitive_count(), 5);
+    /// ```
+    pub fn primitive_count(&self) -> usize {
+        self.meshes
+            .iter()
+            .map(|mesh| mesh.triangle_count() + mesh.lines.len() / 2 + mesh.points.len())
+            .sum()
+    }
+
+    /// Returns whether the model contains no triangles, lines or points.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::ModelData;
+    ///
+    /// assert!(ModelData::default().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.meshes
+            .iter()
+            .all(|mesh| mesh.indices.is_empty() && mesh.lines.is_empty() && mesh.points.is_empty())
+    }
+
+    /// Returns the index of the first node called `name`.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{ModelData, Node};
+    ///
+    /// let model = ModelData {
+    ///     nodes: vec![Node {
+    ///         name: Some("body".to_string()),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(model.find_node("body"), Some(0));
+    /// assert_eq!(model.find_node("head"), None);
+    /// ```
+    pub fn find_node(&self, name: &str) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| node.name.as_deref() == Some(name))
+    }
+
+    /// Returns the indices of the meshes owned by `node` and all of its descendants.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{ModelData, Node};
+    ///
+    /// let model = ModelData {
+    ///     nodes: vec![
+    ///         Node {
+    ///             children: vec![1],
+    ///             meshes: 0..1,
+    ///             ..Default::default()
+    ///         },
+    ///         Node {
+    ///             parent: Some(0),
+    ///             meshes: 1..3,
+    ///             ..Default::default()
+    ///         },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(model.node_meshes(0), vec![0, 1, 2]);
+    /// assert_eq!(model.node_meshes(1), vec![1, 2]);
+    /// ```
+    pub fn node_meshes(&self, node: usize) -> Vec<usize> {
+        let mut meshes = Vec::new();
+        let mut stack = vec![node];
+        while let Some(index) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else {
+                continue;
+            };
+            meshes.extend(node.meshes.clone());
+            stack.extend(node.children.iter().rev());
+        }
+        meshes.sort_unstable();
+        meshes
+    }
+
+    /// Iterates over every triangle as a standalone [`Triangle`].
+    ///
+    /// Vertex colors are averaged per triangle and multiplied with the
+    /// material color.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{ModelData, Triangle};
+    ///
+    /// let tri = Triangle {
+    ///     verts: [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+    ///     uvs: [[0.0, 0.0]; 3],
+    ///     color: [0.5, 0.5, 0.5],
+    ///     texture: None,
+    /// };
+    /// let model = ModelData::from_triangles(vec![tri]);
+    /// let tris: Vec<Triangle> = model.triangles().collect();
+    /// assert_eq!(tris[0].color, [0.5, 0.5, 0.5]);
+    /// ```
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
+        self.meshes.iter().flat_map(move |mesh| {
+            let material = self.material(mesh.material);
+            (0..mesh.triangle_count()).map(move |t| {
+                let idx = mesh.triangle_indices(t);
+                let color = match &mesh.colors {
+                    Some(colors) => {
+                        let c = idx.map(|i| colors[i]);
+                        [0, 1, 2]
+                            .map(|ch| (c[0][ch] + c[1][ch] + c[2][ch]) / 3.0 * material.color[ch])
+                    }
+                    None => material.color,
+                };
+                Triangle {
+                    verts: idx.map(|i| mesh.positions[i]),
+                    uvs: idx.map(|i| mesh.uvs[i]),
+                    color,
+                    texture: material.texture.clone(),
+                }
+            })
+        })
+    }
 }
 
 /// The result type for format loading.
@@ -204,6 +909,10 @@ pub enum LoadError {
     IoError(std::io::Error),
     /// Indicates no geometry was found in the model.
     NoGeometry,
+    /// Indicates loading was cancelled through a [`CancellationToken`](crate::budget::CancellationToken).
+    Cancelled,
+    /// Indicates loading exceeded its time budget.
+    TimedOut,
 }
 
 impl std::fmt::Display for LoadError {
@@ -213,6 +922,8 @@ impl std::fmt::Display for LoadError {
             LoadError::UnrecognizedFormat => write!(f, "Unrecognized format"),
             LoadError::IoError(e) => write!(f, "IO error: {}", e),
             LoadError::NoGeometry => write!(f, "No geometry found"),
+            LoadError::Cancelled => write!(f, "Loading was cancelled"),
+            LoadError::TimedOut => write!(f, "Loading exceeded its time budget"),
         }
     }
 }
@@ -225,6 +936,196 @@ impl From<std::io::Error> for LoadError {
     }
 }
 
+impl From<Interrupted> for LoadError {
+    fn from(e: Interrupted) -> Self {
+        match e {
+            Interrupted::Cancelled => LoadError::Cancelled,
+            Interrupted::TimedOut => LoadError::TimedOut,
+        }
+    }
+}
+
+/// Options that control how a model is loaded.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use glimpse::budget::Budget;
+/// use glimpse::formats::LoadOptions;
+///
+/// let options = LoadOptions {
+///     budget: Budget::new().with_timeout(Duration::from_secs(2)),
+///     ..Default::default()
+/// };
+/// assert!(options.budget.deadline.is_some());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    /// Cancellation token and deadline polled while loading.
+    pub budget: Budget,
+    /// Animation to pose the model with, for formats that support animation.
+    /// `None` shows the rest pose.
+    pub animation: Option<AnimationPose>,
+    /// Scene to load from files with several scenes. `None` loads the
+    /// default scene, or the first one if the file names no default.
+    pub scene: Option<SceneChoice>,
+    /// Name of the material variant to apply, for formats that support
+    /// variants. `None` keeps the default materials.
+    pub variant: Option<String>,
+    /// Reads files the model references, such as OBJ material libraries and
+    /// external glTF buffers. Loads from bytes only reach such files through
+    /// a resolver; loads from a path fall back to the model's directory.
+    pub resolver: Option<Arc<dyn ResourceResolver>>,
+    /// Records when the loader finished parsing, splitting load time into
+    /// parsing and conversion for [`LoadReport`].
+    pub parse_clock: Option<ParseClock>,
+}
+
+impl LoadOptions {
+    /// Records that the loader has finished parsing and starts building meshes.
+    ///
+    /// Loaders call this once between the two stages; it does nothing without
+    /// a [`ParseClock`].
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{LoadOptions, ParseClock};
+    ///
+    /// let clock = ParseClock::new();
+    /// let options = LoadOptions {
+    ///     parse_clock: Some(clock.clone()),
+    ///     ..Default::default()
+    /// };
+    /// options.mark_parsed();
+    /// assert!(clock.parsed_at().is_some());
+    /// ```
+    pub fn mark_parsed(&self) {
+        if let Some(clock) = &self.parse_clock {
+            clock.mark();
+        }
+    }
+}
+
+/// A cloneable record of when a loader finished parsing its input.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::ParseClock;
+///
+/// let clock = ParseClock::new();
+/// assert!(clock.parsed_at().is_none());
+/// clock.mark();
+/// assert!(clock.parsed_at().is_some());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ParseClock(Arc<OnceLock<Instant>>);
+
+impl ParseClock {
+    /// Creates a clock with no recorded time.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::ParseClock;
+    ///
+    /// assert!(ParseClock::new().parsed_at().is_none());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the current time. Only the first call has an effect.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::ParseClock;
+    ///
+    /// let clock = ParseClock::new();
+    /// clock.mark();
+    /// let first = clock.parsed_at();
+    /// clock.mark();
+    /// assert_eq!(clock.parsed_at(), first);
+    /// ```
+    pub fn mark(&self) {
+        let _ = self.0.set(Instant::now());
+    }
+
+    /// Returns when parsing finished, if it was recorded.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::ParseClock;
+    ///
+    /// assert!(ParseClock::default().parsed_at().is_none());
+    /// ```
+    pub fn parsed_at(&self) -> Option<Instant> {
+        self.0.get().copied()
+    }
+}
+
+/// Describes how a model was loaded.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::LoadReport;
+///
+/// let report = LoadReport::default();
+/// assert_eq!(report.loader, "");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LoadReport {
+    /// Name of the loader that parsed the model.
+    pub loader: &'static str,
+    /// Time spent reading and parsing the file, including referenced resources.
+    pub parse: Duration,
+    /// Time spent building meshes from the parsed file.
+    pub convert: Duration,
+}
+
+/// Selects one scene of a file with several scenes.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::{LoadOptions, SceneChoice};
+///
+/// let options = LoadOptions {
+///     scene: Some(SceneChoice::Named("Showroom".to_string())),
+///     ..Default::default()
+/// };
+/// assert_ne!(options.scene, Some(SceneChoice::Index(0)));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum SceneChoice {
+    /// The scene at this index.
+    Index(usize),
+    /// The first scene with this name.
+    Named(String),
+}
+
+/// Selects an animation and the time at which it is sampled.
+///
+/// Times before the first or after the last keyframe hold the first or last
+/// pose, as the glTF specification prescribes.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::AnimationPose;
+///
+/// // Half a second into the first animation
+/// let pose = AnimationPose {
+///     name: None,
+///     time: 0.5,
+/// };
+/// assert!(pose.name.is_none());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnimationPose {
+    /// Name of the animation to sample, or `None` for the first one.
+    pub name: Option<String>,
+    /// Sample time in seconds.
+    pub time: f32,
+}
+
 /// A trait for format-specific model loaders.
 ///
 /// # Examples
@@ -308,6 +1209,52 @@ pub trait FormatLoader: Send + Sync {
         let data = std::fs::read(path)?;
         self.load_from_bytes(&data)
     }
+
+    /// Loads a model from raw bytes, honouring the given load options.
+    ///
+    /// Default implementation checks the budget and calls `load_from_bytes`.
+    /// Loaders with long-running stages override this to poll the budget
+    /// while they work.
+    ///
+    /// # Errors
+    /// Returns an error if the data cannot be parsed, contains no geometry,
+    /// or the budget runs out.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::{self, FormatLoader, LoadOptions};
+    ///
+    /// let loader = formats::gltf::GltfLoader;
+    /// let result = loader.load_from_bytes_with_options(b"invalid", &LoadOptions::default());
+    /// assert!(result.is_err());
+    /// ```
+    fn load_from_bytes_with_options(&self, data: &[u8], options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
+        self.load_from_bytes(data)
+    }
+
+    /// Loads a model from a file path, honouring the given load options.
+    ///
+    /// Default implementation checks the budget and calls `load_from_path`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed, or the budget runs out.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use glimpse::formats::{self, FormatLoader, LoadOptions};
+    ///
+    /// let loader = formats::gltf::GltfLoader;
+    /// let result = loader
+    ///     .load_from_path_with_options(Path::new("does_not_exist.gltf"), &LoadOptions::default());
+    /// assert!(result.is_err());
+    /// ```
+    fn load_from_path_with_options(&self, path: &Path, options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
+        self.load_from_path(path)
+    }
 }
 
 /// Returns all registered format loaders.
@@ -372,9 +1319,79 @@ pub fn find_loader(data: &[u8], extension: Option<&str>) -> Option<Box<dyn Forma
 /// assert!(matches!(result, Err(LoadError::UnrecognizedFormat)));
 /// ```
 pub fn load_model(data: &[u8], extension: Option<&str>) -> LoadResult {
-    find_loader(data, extension)
-        .ok_or(LoadError::UnrecognizedFormat)?
-        .load_from_bytes(data)
+    load_model_with_options(data, extension, &LoadOptions::default())
+}
+
+/// Loads a model from bytes with the given options, auto-detecting the format.
+///
+/// # Errors
+/// Returns an error if no loader recognizes the data, parsing fails, or the
+/// budget runs out.
+///
+/// # Examples
+/// ```
+/// use glimpse::budget::{Budget, CancellationToken};
+/// use glimpse::formats::{self, LoadError, LoadOptions};
+///
+/// let token = CancellationToken::new();
+/// token.cancel();
+/// let options = LoadOptions {
+///     budget: Budget::new().with_token(token),
+///     ..Default::default()
+/// };
+/// let result = formats::load_model_with_options(b"glTF", Some("glb"), &options);
+/// assert!(matches!(result, Err(LoadError::Cancelled)));
+/// ```
+pub fn load_model_with_options(
+    data: &[u8],
+    extension: Option<&str>,
+    options: &LoadOptions,
+) -> LoadResult {
+    load_model_with_report(data, extension, options).map(|(model, _)| model)
+}
+
+/// Loads a model from bytes with the given options and reports which loader
+/// was used and how long parsing and conversion took.
+///
+/// Loaders that do not record the end of parsing count entirely as parsing.
+///
+/// # Errors
+/// Returns an error if no loader recognizes the data, parsing fails, or the
+/// budget runs out.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::{self, LoadOptions};
+///
+/// let obj = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+/// let (model, report) =
+///     formats::load_model_with_report(obj, Some("obj"), &LoadOptions::default()).unwrap();
+/// assert_eq!(model.triangle_count(), 1);
+/// assert_eq!(report.loader, "Wavefront OBJ");
+/// ```
+pub fn load_model_with_report(
+    data: &[u8],
+    extension: Option<&str>,
+    options: &LoadOptions,
+) -> Result<(ModelData, LoadReport), LoadError> {
+    let start = Instant::now();
+    let loader = find_loader(data, extension).ok_or(LoadError::UnrecognizedFormat)?;
+
+    let clock = ParseClock::new();
+    let options = LoadOptions {
+        parse_clock: Some(clock.clone()),
+        ..options.clone()
+    };
+    let model = loader.load_from_bytes_with_options(data, &options)?;
+
+    let end = Instant::now();
+    let parsed_at = clock.parsed_at().unwrap_or(end);
+    let report = LoadReport {
+        loader: loader.name(),
+        parse: parsed_at - start,
+        convert: end - parsed_at,
+    };
+    Ok((model, report))
 }
 
 /// Loads a model from a file path, auto-detecting the format.
@@ -392,15 +1409,72 @@ pub fn load_model(data: &[u8], extension: Option<&str>) -> LoadResult {
 /// assert!(result.is_err());
 /// ```
 pub fn load_model_from_path(path: &Path) -> LoadResult {
+    load_model_from_path_with_options(path, &LoadOptions::default())
+}
+
+/// Loads a model from a file path with the given options, auto-detecting the format.
+///
+/// # Errors
+/// Returns an error if the file cannot be read, the format is unrecognized,
+/// or the budget runs out.
+///
+/// # Examples
+/// ```
+/// use std::path::Path;
+///
+/// use glimpse::formats::{self, LoadOptions};
+///
+/// let result =
+///     formats::load_model_from_path_with_options(Path::new("missing.obj"), &LoadOptions::default());
+/// assert!(result.is_err());
+/// ```
+pub fn load_model_from_path_with_options(path: &Path, options: &LoadOptions) -> LoadResult {
+    load_model_from_path_with_report(path, options).map(|(model, _)| model)
+}
+
+/// Loads a model from a file path with the given options and reports which
+/// loader was used and how long parsing and conversion took.
+///
+/// The file is read once; files it references are read through
+/// [`LoadOptions::resolver`], or from the model's directory if none is set.
+///
+/// # Errors
+/// Returns an error if the file cannot be read, the format is unrecognized,
+/// or the budget runs out.
+///
+/// # Examples
+/// ```
+/// use std::path::Path;
+///
+/// use glimpse::formats::{self, LoadOptions};
+///
+/// let result =
+///     formats::load_model_from_path_with_report(Path::new("missing.obj"), &LoadOptions::default());
+/// assert!(result.is_err());
+/// ```
+pub fn load_model_from_path_with_report(
+    path: &Path,
+    options: &LoadOptions,
+) -> Result<(ModelData, LoadReport), LoadError> {
+    let start = Instant::now();
     let extension = path
         .extension()
         .and_then(|e| e.to_str())
         .map(|s| s.to_lowercase());
 
     let data = std::fs::read(path)?;
+    let read = start.elapsed();
 
-    let loader = find_loader(&data, extension.as_deref()).ok_or(LoadError::UnrecognizedFormat)?;
-
-    // Use path-based loading for formats that need external resource resolution
-    loader.load_from_path(path)
+    // External resources such as textures resolve next to the model by default
+    let resolver = options
+        .resolver
+        .clone()
+        .unwrap_or_else(|| Arc::new(DirectoryResolver::for_model(path)));
+    let options = LoadOptions {
+        resolver: Some(resolver),
+        ..options.clone()
+    };
+    let (model, mut report) = load_model_with_report(&data, extension.as_deref(), &options)?;
+    report.parse += read;
+    Ok((model, report))
 }
@@ -1,21 +1,35 @@
 //! Provides a Wavefront OBJ format loader.
 //!
 //! OBJ is a widely supported 3D model format. This loader handles geometry
-//! (vertices and faces) with automatic polygon triangulation. When loaded
-//! from a file path, companion .mtl materials are resolved for diffuse
-//! colors and textures.
-
+//! (vertices and faces), ear-clipping concave polygons into triangles. Faces
+//! are smooth-shaded with their `vn` normals, or with normals averaged per
+//! `s` smoothing group when the file has none, and tinted by `v x y z r g b`
+//! vertex colors where present. When loaded from a file path or with a
+//! resource resolver, companion .mtl materials are resolved for diffuse
+//! colors, textures, opacity, specular highlights and emission, including the
+//! placement options of diffuse maps.
+
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::{BufReader, Cursor};
 use std::path::Path;
 use std::sync::Arc;
 
-use obj::raw::material::{parse_mtl, MtlColor};
-use obj::raw::object::Polygon;
+use obj::raw::object::{Polygon, RawObj};
 use obj::raw::parse_obj;
 
-use super::shared::texture::load_texture_from_file;
-use super::{FormatLoader, LoadError, LoadResult, ModelData, TextureData, Triangle};
+use super::resolver::{self, DirectoryResolver, ResourceResolver};
+use super::shared::mesh::MeshBuilder;
+use super::shared::texture::decode_texture;
+use super::shared::triangulate::triangulate;
+use super::{
+    AlphaMode, FormatLoader, LoadError, LoadOptions, LoadResult, Material, ModelData, TextureData,
+    Vec2, Vec3, WrapMode,
+};
+use crate::budget::Budget;
+
+/// Number of polygons converted between budget checks.
+const BUDGET_CHECK_INTERVAL: usize = 16 * 1024;
 
 pub struct ObjLoader;
 
@@ -58,77 +72,434 @@ impl FormatLoader for ObjLoader {
     }
 
     fn load_from_bytes(&self, data: &[u8]) -> LoadResult {
-        let reader = BufReader::new(Cursor::new(data));
-        let raw = parse_obj(reader)
-            .map_err(|e| LoadError::InvalidData(format!("Failed to parse OBJ: {}", e)))?;
-
-        convert_raw_obj_to_triangles(&raw, &HashMap::new())
+        self.load_from_bytes_with_options(data, &LoadOptions::default())
     }
 
     fn load_from_path(&self, path: &Path) -> LoadResult {
+        self.load_from_path_with_options(path, &LoadOptions::default())
+    }
+
+    fn load_from_bytes_with_options(&self, data: &[u8], options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
+        let (raw, colors) = parse(data)?;
+
+        // Without a resolver there is nowhere to find the .mtl files
+        let materials = match options.resolver.as_deref() {
+            Some(resolver) => load_mtl_materials(&raw.material_libraries, resolver),
+            None => HashMap::new(),
+        };
+        options.budget.check()?;
+        options.mark_parsed();
+
+        convert_raw_obj_to_meshes(&raw, colors.as_deref(), &materials, &options.budget)
+    }
+
+    fn load_from_path_with_options(&self, path: &Path, options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
         let data = std::fs::read(path)?;
-        let reader = BufReader::new(Cursor::new(&data[..]));
-        let raw = parse_obj(reader)
-            .map_err(|e| LoadError::InvalidData(format!("Failed to parse OBJ: {}", e)))?;
+        let (raw, colors) = parse(&data)?;
 
         // Load companion .mtl files
-        let obj_dir = path.parent().unwrap_or(Path::new("."));
-        let materials = load_mtl_materials(&raw.material_libraries, obj_dir);
+        let directory = DirectoryResolver::for_model(path);
+        let resolver = options.resolver.as_deref().unwrap_or(&directory);
+        let materials = load_mtl_materials(&raw.material_libraries, resolver);
+        options.budget.check()?;
+        options.mark_parsed();
+
+        convert_raw_obj_to_meshes(&raw, colors.as_deref(), &materials, &options.budget)
+    }
+}
+
+/// Optional RGB color of every OBJ position, in file order.
+type VertexColors = Vec<Option<[f32; 3]>>;
+
+/// Parses OBJ text, returning per-position vertex colors if any `v` line has them.
+fn parse(data: &[u8]) -> Result<(RawObj, Option<VertexColors>), LoadError> {
+    let (text, colors) = match split_vertex_colors(data) {
+        Some((text, colors)) => (Cow::Owned(text.into_bytes()), Some(colors)),
+        None => (Cow::Borrowed(data), None),
+    };
+    let raw = parse_obj(BufReader::new(Cursor::new(&text[..])))
+        .map_err(|e| LoadError::InvalidData(format!("Failed to parse OBJ: {}", e)))?;
+    Ok((raw, colors))
+}
 
-        convert_raw_obj_to_triangles(&raw, &materials)
+/// Separates the `v x y z r g b` vertex color extension from OBJ text.
+///
+/// obj-rs only accepts three or four position components, so colored `v`
+/// lines are rewritten to plain positions. Seven components are read as
+/// `x y z r g b` with a trailing alpha, as MeshLab and tinyobjloader do.
+/// Returns `None` if no vertex has a color.
+fn split_vertex_colors(data: &[u8]) -> Option<(String, VertexColors)> {
+    let text = std::str::from_utf8(data).ok()?;
+    let colored = |line: &str| {
+        let mut args = strip_comment(line).split_whitespace();
+        args.next() == Some("v") && matches!(args.count(), 6 | 7)
+    };
+    // Most files have no colors; skip copying them
+    if !text.lines().any(colored) {
+        return None;
     }
+
+    let mut rewritten = String::with_capacity(text.len());
+    let mut colors = Vec::new();
+    let mut lines = text.lines();
+
+    while let Some(first) = lines.next() {
+        // Join backslash continuations after stripping comments, like obj-rs
+        let mut physical = vec![first];
+        let mut statement = Cow::Borrowed(strip_comment(first));
+        while let Some(joined) = statement.strip_suffix('\\') {
+            let Some(next) = lines.next() else {
+                break;
+            };
+            statement = Cow::Owned(format!("{} {}", joined, strip_comment(next)));
+            physical.push(next);
+        }
+
+        let mut args = statement.split_whitespace();
+        if args.next() == Some("v") {
+            let values: Vec<&str> = args.collect();
+            let color = match values.len() {
+                6 | 7 => values[3..6]
+                    .iter()
+                    .map(|v| v.parse::<f32>().ok())
+                    .collect::<Option<Vec<_>>>(),
+                _ => None,
+            };
+            if let Some(color) = color {
+                let [x, y, z] = [values[0], values[1], values[2]];
+                rewritten.push_str(&format!("v {x} {y} {z}"));
+                // Keep line numbers in parse errors intact
+                rewritten.push_str(&"\n".repeat(physical.len()));
+                colors.push(Some(
+                    [color[0], color[1], color[2]].map(|c| c.clamp(0.0, 1.0)),
+                ));
+                continue;
+            }
+            colors.push(None);
+        }
+        for line in physical {
+            rewritten.push_str(line);
+            rewritten.push('\n');
+        }
+    }
+
+    colors
+        .iter()
+        .any(Option::is_some)
+        .then_some((rewritten, colors))
+}
+
+/// Drops an OBJ `#` comment from a line.
+fn strip_comment(line: &str) -> &str {
+    line.split_once('#').map_or(line, |(code, _)| code)
 }
 
-/// Loaded material data from .mtl file.
+/// A material from a .mtl file, with where its diffuse map sits on the surface.
 struct ObjMaterial {
-    color: [f32; 3],
-    texture: Option<Arc<TextureData>>,
+    material: Material,
+    /// `-s` scale of the diffuse map, applied to texture coordinates.
+    uv_scale: Vec2,
+    /// `-o` offset of the diffuse map, applied after scaling.
+    uv_offset: Vec2,
+}
+
+impl ObjMaterial {
+    /// Places a texture coordinate on the diffuse map.
+    fn map_uv(&self, uv: Vec2) -> Vec2 {
+        [0, 1].map(|a| uv[a] * self.uv_scale[a] + self.uv_offset[a])
+    }
+}
+
+/// The statements of one `newmtl` block that affect rendering.
+#[derive(Default)]
+struct MtlEntry {
+    diffuse: Option<[f32; 3]>,
+    specular: Option<[f32; 3]>,
+    emissive: Option<[f32; 3]>,
+    shininess: Option<f32>,
+    dissolve: Option<f32>,
+    transparency: Option<f32>,
+    diffuse_map: Option<TextureMap>,
+    emissive_map: Option<TextureMap>,
+    dissolve_map: Option<TextureMap>,
+}
+
+/// A texture statement's file name and placement options.
+struct TextureMap {
+    file: String,
+    scale: Vec2,
+    offset: Vec2,
+    clamp: bool,
 }
 
 /// Loads materials from .mtl files referenced by the OBJ.
-fn load_mtl_materials(mtl_libs: &[String], obj_dir: &Path) -> HashMap<String, ObjMaterial> {
+fn load_mtl_materials(
+    mtl_libs: &[String],
+    resolver: &dyn ResourceResolver,
+) -> HashMap<String, ObjMaterial> {
     let mut materials = HashMap::new();
-
-    for mtl_name in mtl_libs {
-        let mtl_path = obj_dir.join(mtl_name);
-        let mtl_data = match std::fs::read(&mtl_path) {
-            Ok(d) => d,
-            Err(_) => continue,
+    let mut textures = HashMap::new();
+
+    // obj-rs splits `mtllib` on whitespace, which breaks names with spaces
+    let joined = (mtl_libs.len() > 1)
+        .then(|| read_reference(resolver, "", &mtl_libs.join(" ")))
+        .flatten();
+    let libraries: Vec<(String, Vec<u8>)> = match joined {
+        Some(library) => vec![library],
+        None => mtl_libs
+            .iter()
+            .filter_map(|name| read_reference(resolver, "", name))
+            .collect(),
+    };
+
+    for (mtl_path, mtl_data) in libraries {
+        let mtl_dir = resolver::parent(&mtl_path);
+        let mut load_map = |map: &TextureMap| {
+            textures
+                .entry(resolver::join(mtl_dir, &map.file))
+                .or_insert_with(|| {
+                    let (path, bytes) = read_reference(resolver, mtl_dir, &map.file)?;
+                    let extension = Path::new(&path).extension().and_then(|ext| ext.to_str());
+                    decode_texture(&bytes, extension)
+                })
+                .clone()
         };
 
-        let reader = BufReader::new(Cursor::new(&mtl_data[..]));
-        let raw_mtl = match parse_mtl(reader) {
-            Ok(m) => m,
-            Err(_) => continue,
-        };
+        for (name, entry) in parse_mtl(&String::from_utf8_lossy(&mtl_data)) {
+            let color = entry.diffuse.unwrap_or([0.85, 0.85, 0.85]);
+            let mut texture = entry.diffuse_map.as_ref().and_then(&mut load_map);
+
+            // `d` is opacity and `Tr` its inverse; `d` wins if both are given
+            let alpha = entry
+                .dissolve
+                .or(entry.transparency.map(|tr| 1.0 - tr))
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0);
+            let mut soft_mask = false;
+            if let Some(mask) = entry.dissolve_map.as_ref().and_then(&mut load_map) {
+                let (masked, soft) = apply_alpha_map(texture.as_deref(), &mask);
+                texture = Some(Arc::new(masked));
+                soft_mask = soft;
+            }
 
-        let mtl_dir = mtl_path.parent().unwrap_or(obj_dir);
+            // Partially dissolved materials are blended; otherwise keep the
+            // default cutout behaviour so textures with holes still work
+            let alpha_mode = if alpha < 1.0 || soft_mask {
+                AlphaMode::Blend
+            } else {
+                Material::default().alpha_mode
+            };
 
-        for (name, mat) in &raw_mtl.materials {
-            let color = mat
-                .diffuse
-                .as_ref()
-                .map(mtl_color_to_rgb)
-                .unwrap_or([0.85, 0.85, 0.85]);
+            // Map the Phong exponent onto perceptual roughness
+            let roughness = entry
+                .shininess
+                .map(|ns| (2.0 / (ns.max(0.0) + 2.0)).sqrt())
+                .unwrap_or(Material::default().roughness);
 
-            let texture = mat.diffuse_map.as_ref().and_then(|map| {
-                let tex_path = mtl_dir.join(&map.file);
-                load_texture_from_file(&tex_path)
-            });
+            let diffuse_map = entry.diffuse_map.as_ref();
+            let material = Material {
+                name: Some(name.clone()),
+                color,
+                alpha,
+                texture,
+                alpha_mode,
+                emissive: entry.emissive.unwrap_or([0.0; 3]),
+                emissive_texture: entry.emissive_map.as_ref().and_then(&mut load_map),
+                roughness,
+                specular: entry.specular.unwrap_or(Material::default().specular),
+                wrap: if diffuse_map.is_some_and(|map| map.clamp) {
+                    WrapMode::Clamp
+                } else {
+                    WrapMode::Repeat
+                },
+                ..Default::default()
+            };
+            materials.insert(
+                name,
+                ObjMaterial {
+                    material,
+                    uv_scale: diffuse_map.map_or([1.0, 1.0], |map| map.scale),
+                    uv_offset: diffuse_map.map_or([0.0, 0.0], |map| map.offset),
+                },
+            );
+        }
+    }
 
-            materials.insert(name.clone(), ObjMaterial { color, texture });
+    materials
+}
+
+/// Parses the materials of a .mtl file.
+///
+/// Keywords are matched case-insensitively and unknown statements are
+/// skipped, since exporters add their own (`Pr`, `Pm`, `map_Bump`, ...).
+fn parse_mtl(text: &str) -> Vec<(String, MtlEntry)> {
+    let mut materials: Vec<(String, MtlEntry)> = Vec::new();
+
+    for line in text.lines() {
+        let (keyword, args) = split_token(strip_comment(line));
+        let keyword = keyword.to_ascii_lowercase();
+        if keyword == "newmtl" {
+            let (name, _) = split_token(args);
+            materials.push((name.to_string(), MtlEntry::default()));
+            continue;
+        }
+        let Some((_, entry)) = materials.last_mut() else {
+            continue;
+        };
+
+        let number = || split_token(args).0.parse::<f32>().ok();
+        match keyword.as_str() {
+            "kd" => entry.diffuse = parse_mtl_color(args),
+            "ks" => entry.specular = parse_mtl_color(args),
+            "ke" => entry.emissive = parse_mtl_color(args),
+            "ns" => entry.shininess = number(),
+            "d" => entry.dissolve = number(),
+            "tr" => entry.transparency = number(),
+            "map_kd" => entry.diffuse_map = parse_texture_map(args),
+            "map_ke" => entry.emissive_map = parse_texture_map(args),
+            "map_d" => entry.dissolve_map = parse_texture_map(args),
+            _ => {}
         }
     }
 
     materials
 }
 
-fn mtl_color_to_rgb(color: &MtlColor) -> [f32; 3] {
-    match color {
-        MtlColor::Rgb(r, g, b) => [*r, *g, *b],
-        MtlColor::Xyz(x, y, z) => [*x, *y, *z],
-        MtlColor::Spectral(_, _) => [0.85, 0.85, 0.85],
+/// Parses an RGB color statement's arguments; a single value is gray.
+///
+/// CIE XYZ values are used as RGB, and spectral curves fall back to gray.
+fn parse_mtl_color(args: &str) -> Option<[f32; 3]> {
+    let mut values = args.split_whitespace().peekable();
+    match values.peek().copied() {
+        Some("spectral") => return Some([0.85, 0.85, 0.85]),
+        Some("xyz") => {
+            values.next();
+        }
+        _ => {}
+    }
+    let values: Vec<f32> = values.map(|v| v.parse().ok()).collect::<Option<_>>()?;
+    match values[..] {
+        [gray] => Some([gray; 3]),
+        [r, g, b] => Some([r, g, b]),
+        _ => None,
+    }
+}
+
+/// Parses a texture statement: options such as `-s`, `-o` and `-clamp`
+/// followed by a file name, which may contain spaces.
+fn parse_texture_map(args: &str) -> Option<TextureMap> {
+    let mut map = TextureMap {
+        file: String::new(),
+        scale: [1.0, 1.0],
+        offset: [0.0, 0.0],
+        clamp: false,
+    };
+
+    let mut rest = args;
+    while let Some(option) = rest.trim_start().strip_prefix('-') {
+        let (option, after) = split_token(option);
+        rest = after;
+        match option {
+            // Up to three numbers: u, optional v and w
+            "s" | "o" | "t" => {
+                let mut values = Vec::new();
+                while values.len() < 3 {
+                    let (token, after) = split_token(rest);
+                    let Ok(value) = token.parse::<f32>() else {
+                        break;
+                    };
+                    values.push(value);
+                    rest = after;
+                }
+                let default = if option == "s" { 1.0 } else { 0.0 };
+                let uv = [0, 1].map(|a| values.get(a).copied().unwrap_or(default));
+                match option {
+                    "s" => map.scale = uv,
+                    "o" => map.offset = uv,
+                    _ => {}
+                }
+            }
+            "clamp" => {
+                let (value, after) = split_token(rest);
+                map.clamp = value.eq_ignore_ascii_case("on");
+                rest = after;
+            }
+            // -mm takes two values; -blendu, -blendv, -bm, -boost, -cc,
+            // -imfchan, -texres and -type take one
+            _ => {
+                for _ in 0..if option == "mm" { 2 } else { 1 } {
+                    rest = split_token(rest).1;
+                }
+            }
+        }
+    }
+
+    let file = rest.trim().trim_matches('"');
+    if file.is_empty() {
+        return None;
+    }
+    map.file = file.to_string();
+    Some(map)
+}
+
+/// Splits off the first whitespace-separated token, returning it and the rest.
+fn split_token(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    (&text[..end], &text[end..])
+}
+
+/// Reads a file referenced from an OBJ or MTL file in the resource directory
+/// `dir`, returning its resolved path and contents.
+///
+/// Absolute paths from the exporting machine usually no longer exist, so the
+/// bare file name is tried next to the referencing file as well.
+fn read_reference(
+    resolver: &dyn ResourceResolver,
+    dir: &str,
+    reference: &str,
+) -> Option<(String, Vec<u8>)> {
+    let path = resolver::join(dir, reference);
+    let name = path.rsplit('/').next().unwrap_or_default();
+    let fallback = resolver::join(dir, name);
+    [path.clone(), fallback]
+        .into_iter()
+        .find_map(|path| Some((path.clone(), resolver.read(&path).ok()?)))
+}
+
+/// Multiplies a texture's alpha with a `map_d` dissolve mask.
+///
+/// The mask's alpha channel is used if it has one, otherwise its luminance.
+/// Without a diffuse texture the mask becomes a white texture of its own.
+/// Returns the masked texture and whether the mask has partial opacity.
+fn apply_alpha_map(texture: Option<&TextureData>, mask: &TextureData) -> (TextureData, bool) {
+    let use_alpha = mask.data.chunks_exact(4).any(|p| p[3] < 255);
+    let mut soft = false;
+    let mut opacity = |u: f32, v: f32| {
+        let [r, g, b, a] = mask.sample(u, v);
+        let value = if use_alpha {
+            a
+        } else {
+            0.2126 * r + 0.7152 * g + 0.0722 * b
+        };
+        soft |= value > 0.01 && value < 0.99;
+        value
+    };
+
+    let mut masked = texture.cloned().unwrap_or_else(|| TextureData {
+        width: mask.width,
+        height: mask.height,
+        data: vec![255; mask.data.len()],
+    });
+    let (width, height) = (masked.width as usize, masked.height.max(1) as usize);
+    for (i, pixel) in masked.data.chunks_exact_mut(4).enumerate() {
+        let u = ((i % width) as f32 + 0.5) / width as f32;
+        let v = ((i / width) as f32 + 0.5) / height as f32;
+        pixel[3] = (pixel[3] as f32 * opacity(u, v)).round() as u8;
     }
+    (masked, soft)
 }
 
 /// Extracts position index at a given slot from any polygon variant.
@@ -160,16 +531,126 @@ fn polygon_tex_at(polygon: &Polygon, i: usize) -> Option<usize> {
     }
 }
 
-fn convert_raw_obj_to_triangles(
-    raw: &obj::raw::object::RawObj,
+/// Extracts normal index at a given slot (if available).
+fn polygon_normal_at(polygon: &Polygon, i: usize) -> Option<usize> {
+    match polygon {
+        Polygon::P(_) | Polygon::PT(_) => None,
+        Polygon::PN(pairs) => pairs.get(i).map(|&(_, n)| n),
+        Polygon::PTN(triples) => triples.get(i).map(|&(_, _, n)| n),
+    }
+}
+
+/// Normals for the corners of OBJ polygons.
+///
+/// Corners use their `vn` normal if they have one. Otherwise polygons in a
+/// smoothing group share area-weighted normals per position, and polygons
+/// outside any group keep their flat face normal.
+struct CornerNormals<'a> {
+    normals: &'a [(f32, f32, f32)],
+    /// Smoothing group of every polygon; 0 means smoothing is off.
+    groups: Vec<usize>,
+    /// Unnormalized face normal of every polygon.
+    faces: Vec<Vec3>,
+    /// Summed face normals per position index and smoothing group.
+    smooth: HashMap<(usize, usize), Vec3>,
+}
+
+impl<'a> CornerNormals<'a> {
+    /// Prepares corner normals, or returns `None` if the file has neither
+    /// normals nor smoothing groups and should stay flat-shaded.
+    fn new(raw: &'a RawObj, budget: &Budget) -> Result<Option<Self>, LoadError> {
+        let mut groups = vec![0; raw.polygons.len()];
+        for (&group, ranges) in &raw.smoothing_groups {
+            for range in &ranges.polygons {
+                for slot in groups.iter_mut().take(range.end).skip(range.start) {
+                    *slot = group;
+                }
+            }
+        }
+        if raw.normals.is_empty() && groups.iter().all(|&group| group == 0) {
+            return Ok(None);
+        }
+
+        let mut faces = Vec::with_capacity(raw.polygons.len());
+        let mut smooth: HashMap<(usize, usize), Vec3> = HashMap::new();
+        for (poly_idx, polygon) in raw.polygons.iter().enumerate() {
+            if poly_idx % BUDGET_CHECK_INTERVAL == BUDGET_CHECK_INTERVAL - 1 {
+                budget.check()?;
+            }
+            let corners: Vec<usize> = (0..polygon_len(polygon))
+                .filter_map(|i| polygon_pos_at(polygon, i))
+                .filter(|&p| p < raw.positions.len())
+                .collect();
+            let face = newell_normal(&corners, &raw.positions);
+            faces.push(face);
+
+            let group = groups[poly_idx];
+            if group == 0 {
+                continue;
+            }
+            for p in corners {
+                let sum = smooth.entry((p, group)).or_insert([0.0; 3]);
+                *sum = [0, 1, 2].map(|a| sum[a] + face[a]);
+            }
+        }
+
+        Ok(Some(Self {
+            normals: &raw.normals,
+            groups,
+            faces,
+            smooth,
+        }))
+    }
+
+    /// Returns the normal of corner `i` of polygon `poly_idx`; zero if degenerate.
+    fn at(&self, poly_idx: usize, polygon: &Polygon, i: usize) -> Vec3 {
+        if let Some(&(x, y, z)) = polygon_normal_at(polygon, i).and_then(|n| self.normals.get(n)) {
+            return normalize([x, y, z]);
+        }
+        let group = self.groups[poly_idx];
+        let smooth = polygon_pos_at(polygon, i)
+            .filter(|_| group != 0)
+            .and_then(|p| self.smooth.get(&(p, group)));
+        normalize(*smooth.unwrap_or(&self.faces[poly_idx]))
+    }
+}
+
+/// Computes a polygon's area-weighted normal with Newell's method, which also
+/// handles non-planar N-gons.
+fn newell_normal(corners: &[usize], positions: &[(f32, f32, f32, f32)]) -> Vec3 {
+    let mut normal = [0.0; 3];
+    for (k, &i) in corners.iter().enumerate() {
+        let a = positions[i];
+        let b = positions[corners[(k + 1) % corners.len()]];
+        normal[0] += (a.1 - b.1) * (a.2 + b.2);
+        normal[1] += (a.2 - b.2) * (a.0 + b.0);
+        normal[2] += (a.0 - b.0) * (a.1 + b.1);
+    }
+    normal
+}
+
+/// Scales a vector to unit length, leaving zero vectors at zero.
+fn normalize(v: Vec3) -> Vec3 {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if length > 0.0 && length.is_finite() {
+        v.map(|c| c / length)
+    } else {
+        [0.0; 3]
+    }
+}
+
+fn convert_raw_obj_to_meshes(
+    raw: &RawObj,
+    colors: Option<&[Option<[f32; 3]>]>,
     materials: &HashMap<String, ObjMaterial>,
+    budget: &Budget,
 ) -> LoadResult {
-    let mut triangles = Vec::new();
     let default_color = [0.85, 0.85, 0.85];
     let default_uv = [0.0, 0.0];
 
     let positions = &raw.positions;
     let tex_coords = &raw.tex_coords;
+    let normals = CornerNormals::new(raw, budget)?;
 
     // Build polygon index → material name mapping from meshes
     let mut polygon_material: Vec<Option<&str>> = vec![None; raw.polygons.len()];
@@ -183,65 +664,92 @@ fn convert_raw_obj_to_triangles(
         }
     }
 
+    // One mesh per material actually used; `None` is the default material
+    let mut model = ModelData::default();
+    let mut builders: HashMap<Option<&str>, MeshBuilder> = HashMap::new();
+    let mut order: Vec<Option<&str>> = Vec::new();
+
     for (poly_idx, polygon) in raw.polygons.iter().enumerate() {
+        if poly_idx % BUDGET_CHECK_INTERVAL == BUDGET_CHECK_INTERVAL - 1 {
+            budget.check()?;
+        }
+
         let n = polygon_len(polygon);
         if n < 3 {
             continue;
         }
 
         // Look up material for this polygon
-        let mat = polygon_material
+        let mat_name = polygon_material
             .get(poly_idx)
-            .and_then(|name| name.and_then(|n| materials.get(n)));
-
-        let color = mat.map(|m| m.color).unwrap_or(default_color);
-        let texture = mat.and_then(|m| m.texture.clone());
-
-        // Fan triangulation
-        let p0 = match polygon_pos_at(polygon, 0) {
-            Some(idx) if idx < positions.len() => idx,
-            _ => continue,
-        };
-        let v0 = [positions[p0].0, positions[p0].1, positions[p0].2];
-        let uv0 = polygon_tex_at(polygon, 0)
-            .filter(|&idx| idx < tex_coords.len())
-            .map(|idx| [tex_coords[idx].0, tex_coords[idx].1])
-            .unwrap_or(default_uv);
-
-        for i in 1..n - 1 {
-            let p1 = match polygon_pos_at(polygon, i) {
-                Some(idx) if idx < positions.len() => idx,
-                _ => continue,
-            };
-            let p2 = match polygon_pos_at(polygon, i + 1) {
-                Some(idx) if idx < positions.len() => idx,
-                _ => continue,
+            .copied()
+            .flatten()
+            .filter(|name| materials.contains_key(*name));
+        let obj_material = mat_name.and_then(|name| materials.get(name));
+
+        let builder = builders.entry(mat_name).or_insert_with(|| {
+            let material = model.add_material(
+                obj_material
+                    .map(|obj_material| obj_material.material.clone())
+                    .unwrap_or_else(|| Material {
+                        // Vertex colors carry the default gray themselves
+                        color: if colors.is_some() {
+                            [1.0; 3]
+                        } else {
+                            default_color
+                        },
+                        ..Default::default()
+                    }),
+            );
+            order.push(mat_name);
+            MeshBuilder::new(material)
+        });
+
+        // Corners referencing missing positions are dropped
+        let mut corners = Vec::with_capacity(n);
+        let mut outline = Vec::with_capacity(n);
+        for i in 0..n {
+            let Some(p) = polygon_pos_at(polygon, i).filter(|&idx| idx < positions.len()) else {
+                continue;
             };
-
-            let v1 = [positions[p1].0, positions[p1].1, positions[p1].2];
-            let v2 = [positions[p2].0, positions[p2].1, positions[p2].2];
-
-            let uv1 = polygon_tex_at(polygon, i)
-                .filter(|&idx| idx < tex_coords.len())
-                .map(|idx| [tex_coords[idx].0, tex_coords[idx].1])
-                .unwrap_or(default_uv);
-            let uv2 = polygon_tex_at(polygon, i + 1)
+            let uv = polygon_tex_at(polygon, i)
                 .filter(|&idx| idx < tex_coords.len())
                 .map(|idx| [tex_coords[idx].0, tex_coords[idx].1])
                 .unwrap_or(default_uv);
-
-            triangles.push(Triangle {
-                verts: [v0, v1, v2],
-                uvs: [uv0, uv1, uv2],
-                color,
-                texture: texture.clone(),
+            let uv = obj_material.map_or(uv, |obj_material| obj_material.map_uv(uv));
+            let pos = [positions[p].0, positions[p].1, positions[p].2];
+            let normal = normals.as_ref().map(|n| n.at(poly_idx, polygon, i));
+            // Uncolored vertices show the plain material color
+            let color = colors.map(|colors| {
+                colors
+                    .get(p)
+                    .copied()
+                    .flatten()
+                    .unwrap_or(if mat_name.is_some() {
+                        [1.0; 3]
+                    } else {
+                        default_color
+                    })
             });
+            corners.push(builder.vertex_with_attributes(pos, uv, normal, color));
+            outline.push(pos);
+        }
+
+        for [a, b, c] in triangulate(&outline) {
+            builder.triangle(corners[a], corners[b], corners[c]);
         }
     }
 
-    if triangles.is_empty() {
+    model.meshes = order
+        .into_iter()
+        .filter_map(|name| builders.remove(&name))
+        .filter(|builder| !builder.is_empty())
+        .map(MeshBuilder::build)
+        .collect();
+
+    if model.is_empty() {
         return Err(LoadError::NoGeometry);
     }
 
-    Ok(ModelData { triangles })
+    Ok(model)
 }
@@ -0,0 +1,708 @@
+//! Provides access to the files a model references, such as OBJ material
+//! libraries, external glTF buffers and images, and Vintage Story textures.
+//!
+//! Loaders never touch the file system for these lookups themselves. Hosts
+//! pass a [`ResourceResolver`] in
+//! [`LoadOptions::resolver`](super::LoadOptions::resolver): a
+//! [`DirectoryResolver`] reads files from disk, a [`MemoryResolver`] serves
+//! files held in memory, and a `ZipResolver` (with the `zip` feature) reads
+//! them from an archive such as a game mod. Path loads fall back to a
+//! [`DirectoryResolver`] for the model's directory.
+//!
+//! Resource paths are relative to the model's directory and use `/` as the
+//! separator; [`join`] builds them from the references found in model files.
+//!
+//! # Examples
+//! ```
+//! use std::sync::Arc;
+//!
+//! use glimpse::formats::resolver::MemoryResolver;
+//! use glimpse::formats::{self, LoadOptions};
+//!
+//! let resolver = MemoryResolver::new().with_file("shapes.mtl", b"newmtl Red\nKd 1 0 0\n".to_vec());
+//! let options = LoadOptions {
+//!     resolver: Some(Arc::new(resolver)),
+//!     ..Default::default()
+//! };
+//! let obj = b"mtllib shapes.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl Red\nf 1 2 3\n";
+//! let model = formats::load_model_with_options(obj, Some("obj"), &options).unwrap();
+//! assert_eq!(model.materials[0].color, [1.0, 0.0, 0.0]);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "zip")]
+use std::sync::Arc;
+
+/// Reads files referenced by a model.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+///
+/// let resolver = MemoryResolver::new().with_file("model.bin", vec![1, 2, 3]);
+/// assert_eq!(resolver.read("model.bin").unwrap(), [1, 2, 3]);
+/// assert!(resolver.read("missing.bin").is_err());
+/// ```
+pub trait ResourceResolver: Send + Sync + Debug {
+    /// Reads the file at `path`, relative to the model's directory.
+    ///
+    /// # Errors
+    /// Returns an error if the file does not exist or cannot be read.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+    ///
+    /// let resolver = MemoryResolver::new().with_file("textures/wood.png", vec![0]);
+    /// assert!(resolver.read("textures/wood.png").is_ok());
+    /// ```
+    fn read(&self, path: &str) -> io::Result<Vec<u8>>;
+
+    /// Returns whether a file or directory exists at `path`, relative to the
+    /// model's directory.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+    ///
+    /// let resolver = MemoryResolver::new().with_file("textures/wood.png", vec![0]);
+    /// assert!(resolver.exists("textures"));
+    /// assert!(resolver.exists("textures/wood.png"));
+    /// assert!(!resolver.exists("wood.png"));
+    /// ```
+    fn exists(&self, path: &str) -> bool;
+
+    /// Lists the names of the files and directories directly inside `dir`,
+    /// relative to the model's directory.
+    ///
+    /// # Errors
+    /// Returns an error if the directory does not exist or cannot be read.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+    ///
+    /// let resolver = MemoryResolver::new()
+    ///     .with_file("textures/wood.png", vec![0])
+    ///     .with_file("textures/block/stone.png", vec![0]);
+    /// let mut names = resolver.list("textures").unwrap();
+    /// names.sort();
+    /// assert_eq!(names, ["block", "wood.png"]);
+    /// ```
+    fn list(&self, dir: &str) -> io::Result<Vec<String>>;
+
+    /// Returns the `/`-separated path of the model's directory within the
+    /// resolver's tree, if known.
+    ///
+    /// Loaders use it to find enclosing folders, such as the `assets`
+    /// directory of a Vintage Story mod.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+    ///
+    /// let resolver = MemoryResolver::for_model("assets/game/shapes/chair.json");
+    /// assert_eq!(resolver.model_dir().as_deref(), Some("assets/game/shapes"));
+    /// ```
+    fn model_dir(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Resolves resources from a directory on disk.
+///
+/// # Examples
+/// ```
+/// use std::path::Path;
+///
+/// use glimpse::formats::resolver::{DirectoryResolver, ResourceResolver};
+///
+/// let resolver = DirectoryResolver::for_model(Path::new("models/chair.obj"));
+/// assert_eq!(resolver.root(), Path::new("models"));
+/// assert!(resolver.read("missing.mtl").is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct DirectoryResolver {
+    root: PathBuf,
+}
+
+impl DirectoryResolver {
+    /// Creates a resolver for models stored in `root`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use glimpse::formats::resolver::DirectoryResolver;
+    ///
+    /// assert_eq!(DirectoryResolver::new("assets").root(), Path::new("assets"));
+    /// ```
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Creates a resolver for the files next to the model at `path`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use glimpse::formats::resolver::DirectoryResolver;
+    ///
+    /// let resolver = DirectoryResolver::for_model(Path::new("scene.gltf"));
+    /// assert_eq!(resolver.root(), Path::new(""));
+    /// ```
+    pub fn for_model(path: &Path) -> Self {
+        Self::new(path.parent().unwrap_or(Path::new("")))
+    }
+
+    /// Returns the directory resources are resolved against.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use glimpse::formats::resolver::DirectoryResolver;
+    ///
+    /// assert_eq!(DirectoryResolver::new("a/b").root(), Path::new("a/b"));
+    /// ```
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl ResourceResolver for DirectoryResolver {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        std::fs::read(self.root.join(path))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.root.join(path).exists()
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        std::fs::read_dir(self.root.join(dir))?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect()
+    }
+
+    fn model_dir(&self) -> Option<String> {
+        Some(self.root.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+/// Resolves resources from files held in memory.
+///
+/// Files are stored by their path in the resolver's tree; reads are relative
+/// to the model's directory within that tree, which is the root unless the
+/// resolver was created with [`MemoryResolver::for_model`].
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+///
+/// let mut resolver = MemoryResolver::new();
+/// resolver.insert("a/../scene.bin", vec![7]);
+/// assert_eq!(resolver.read("scene.bin").unwrap(), [7]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryResolver {
+    files: HashMap<String, Vec<u8>>,
+    dir: String,
+}
+
+impl MemoryResolver {
+    /// Creates a resolver without any files.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+    ///
+    /// assert!(MemoryResolver::new().read("anything").is_err());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a resolver for the model stored at `model_path` in the tree.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+    ///
+    /// let resolver = MemoryResolver::for_model("models/chair.obj")
+    ///     .with_file("textures/oak.png", vec![1]);
+    /// assert!(resolver.read("../textures/oak.png").is_ok());
+    /// ```
+    pub fn for_model(model_path: &str) -> Self {
+        Self {
+            files: HashMap::new(),
+            dir: parent(&join("", model_path)).to_string(),
+        }
+    }
+
+    /// Adds or replaces the file at `path`, relative to the tree's root.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+    ///
+    /// let mut resolver = MemoryResolver::new();
+    /// resolver.insert("textures\\wood.png", vec![1]);
+    /// assert!(resolver.read("textures/wood.png").is_ok());
+    /// ```
+    pub fn insert(&mut self, path: &str, data: Vec<u8>) {
+        self.files.insert(join("", path), data);
+    }
+
+    /// Returns the resolver with the file at `path` added.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::{MemoryResolver, ResourceResolver};
+    ///
+    /// let resolver = MemoryResolver::new().with_file("a.mtl", Vec::new());
+    /// assert!(resolver.read("a.mtl").is_ok());
+    /// ```
+    pub fn with_file(mut self, path: &str, data: Vec<u8>) -> Self {
+        self.insert(path, data);
+        self
+    }
+}
+
+impl ResourceResolver for MemoryResolver {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        self.files
+            .get(&join(&self.dir, path))
+            .cloned()
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        tree_contains(self.files.keys(), &join(&self.dir, path))
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        list_tree(self.files.keys(), &join(&self.dir, dir)).ok_or_else(|| not_found(dir))
+    }
+
+    fn model_dir(&self) -> Option<String> {
+        Some(self.dir.clone())
+    }
+}
+
+/// Resolves resources from a zip archive, such as a game mod.
+///
+/// Stored and deflated entries are supported; encrypted entries and zip64
+/// archives are not. Reads are relative to the model's directory within the
+/// archive, which is the archive's root unless the resolver was created
+/// with [`ZipResolver::for_model`].
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::resolver::ZipResolver;
+///
+/// assert!(ZipResolver::new(b"not a zip".to_vec()).is_err());
+/// ```
+#[cfg(feature = "zip")]
+#[derive(Clone, Debug)]
+pub struct ZipResolver {
+    archive: Arc<[u8]>,
+    entries: HashMap<String, ZipEntry>,
+    dir: String,
+}
+
+/// Location of a file in a zip archive, from its central directory record.
+#[cfg(feature = "zip")]
+#[derive(Clone, Copy, Debug)]
+struct ZipEntry {
+    header: usize,
+    flags: u16,
+    method: u16,
+    compressed: usize,
+    size: usize,
+}
+
+#[cfg(feature = "zip")]
+impl ZipResolver {
+    /// Opens a zip archive held in memory.
+    ///
+    /// # Errors
+    /// Returns an error if `archive` is not a zip archive or its central
+    /// directory is corrupt.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::ZipResolver;
+    ///
+    /// // An empty archive is just its end of central directory record
+    /// let mut empty = b"PK\x05\x06".to_vec();
+    /// empty.resize(22, 0);
+    /// assert_eq!(ZipResolver::new(empty).unwrap().files().count(), 0);
+    /// ```
+    pub fn new(archive: Vec<u8>) -> io::Result<Self> {
+        const END_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x05, 0x06];
+        const ENTRY_SIGNATURE: u32 = 0x0201_4b50;
+
+        // The end record is last, followed by a comment of up to 64 KiB
+        let data = &archive[..];
+        let last = data
+            .len()
+            .checked_sub(22)
+            .ok_or_else(|| invalid("not a zip archive"))?;
+        let end = (last.saturating_sub(0xffff)..=last)
+            .rev()
+            .find(|&i| data[i..].starts_with(&END_SIGNATURE))
+            .ok_or_else(|| invalid("not a zip archive"))?;
+        let count = read_u16(data, end + 10)?;
+        let offset = read_u32(data, end + 16)?;
+        if count == u16::MAX || offset == u32::MAX {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "zip64 archives are not supported",
+            ));
+        }
+
+        let mut entries = HashMap::new();
+        let mut cursor = offset as usize;
+        for _ in 0..count {
+            if read_u32(data, cursor)? != ENTRY_SIGNATURE {
+                return Err(invalid("corrupt zip central directory"));
+            }
+            let name_len = read_u16(data, cursor + 28)? as usize;
+            let extra_len = read_u16(data, cursor + 30)? as usize;
+            let comment_len = read_u16(data, cursor + 32)? as usize;
+            let name = data
+                .get(cursor + 46..cursor + 46 + name_len)
+                .ok_or_else(|| invalid("corrupt zip central directory"))?;
+            let name = String::from_utf8_lossy(name);
+            // Directories are implied by the files inside them
+            if !name.ends_with('/') {
+                let entry = ZipEntry {
+                    header: read_u32(data, cursor + 42)? as usize,
+                    flags: read_u16(data, cursor + 8)?,
+                    method: read_u16(data, cursor + 10)?,
+                    compressed: read_u32(data, cursor + 20)? as usize,
+                    size: read_u32(data, cursor + 24)? as usize,
+                };
+                entries.insert(join("", &name), entry);
+            }
+            cursor += 46 + name_len + extra_len + comment_len;
+        }
+
+        Ok(Self {
+            archive: archive.into(),
+            entries,
+            dir: String::new(),
+        })
+    }
+
+    /// Opens a zip archive for the model stored at `model_path` inside it.
+    ///
+    /// # Errors
+    /// Returns an error if `archive` is not a zip archive or its central
+    /// directory is corrupt.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::ZipResolver;
+    ///
+    /// assert!(ZipResolver::for_model(Vec::new(), "assets/game/shapes/chair.json").is_err());
+    /// ```
+    pub fn for_model(archive: Vec<u8>, model_path: &str) -> io::Result<Self> {
+        let mut resolver = Self::new(archive)?;
+        resolver.dir = parent(&join("", model_path)).to_string();
+        Ok(resolver)
+    }
+
+    /// Returns the paths of all files in the archive, relative to its root.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::resolver::ZipResolver;
+    ///
+    /// let mut empty = b"PK\x05\x06".to_vec();
+    /// empty.resize(22, 0);
+    /// assert!(ZipResolver::new(empty).unwrap().files().next().is_none());
+    /// ```
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+}
+
+#[cfg(feature = "zip")]
+impl ResourceResolver for ZipResolver {
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        const HEADER_SIGNATURE: u32 = 0x0403_4b50;
+
+        let entry = self
+            .entries
+            .get(&join(&self.dir, path))
+            .ok_or_else(|| not_found(path))?;
+        if entry.flags & 1 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{path} is encrypted"),
+            ));
+        }
+
+        let data = &self.archive[..];
+        if read_u32(data, entry.header)? != HEADER_SIGNATURE {
+            return Err(invalid("corrupt zip entry header"));
+        }
+        let name_len = read_u16(data, entry.header + 26)? as usize;
+        let extra_len = read_u16(data, entry.header + 28)? as usize;
+        let start = entry.header + 30 + name_len + extra_len;
+        let compressed = data
+            .get(start..start + entry.compressed)
+            .ok_or_else(|| invalid("truncated zip entry"))?;
+
+        match entry.method {
+            0 => Ok(compressed.to_vec()),
+            8 => miniz_oxide::inflate::decompress_to_vec_with_limit(compressed, entry.size)
+                .map_err(|_| invalid("corrupt deflate data")),
+            method => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("zip compression method {method} is not supported"),
+            )),
+        }
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        tree_contains(self.entries.keys(), &join(&self.dir, path))
+    }
+
+    fn list(&self, dir: &str) -> io::Result<Vec<String>> {
+        list_tree(self.entries.keys(), &join(&self.dir, dir)).ok_or_else(|| not_found(dir))
+    }
+
+    fn model_dir(&self) -> Option<String> {
+        Some(self.dir.clone())
+    }
+}
+
+/// Returns whether `path` is one of `files` or a directory containing one.
+fn tree_contains<'a>(mut files: impl Iterator<Item = &'a String>, path: &str) -> bool {
+    files.any(|file| {
+        path.is_empty()
+            || file == path
+            || file
+                .strip_prefix(path)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Lists the names directly inside `dir` among `files`, or `None` if no
+/// file lies inside it.
+fn list_tree<'a>(files: impl Iterator<Item = &'a String>, dir: &str) -> Option<Vec<String>> {
+    let mut names: Vec<String> = files
+        .filter_map(|file| {
+            let rest = if dir.is_empty() {
+                file.as_str()
+            } else {
+                file.strip_prefix(dir)?.strip_prefix('/')?
+            };
+            rest.split('/').next().map(str::to_string)
+        })
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+    names.sort();
+    names.dedup();
+    Some(names)
+}
+
+fn not_found(path: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, path.to_string())
+}
+
+#[cfg(feature = "zip")]
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(feature = "zip")]
+fn read_u16(data: &[u8], offset: usize) -> io::Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| invalid("truncated zip archive"))
+}
+
+#[cfg(feature = "zip")]
+fn read_u32(data: &[u8], offset: usize) -> io::Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| invalid("truncated zip archive"))
+}
+
+/// Resolves `reference` against the resource directory `dir`.
+///
+/// Backslashes are treated as separators and `.` and `..` segments are
+/// collapsed. Absolute references are returned as they are.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::resolver::join;
+///
+/// assert_eq!(join("materials", "..\\textures\\oak.png"), "textures/oak.png");
+/// assert_eq!(join("", "./scene.bin"), "scene.bin");
+/// assert_eq!(join("models", "/srv/oak.png"), "/srv/oak.png");
+/// ```
+pub fn join(dir: &str, reference: &str) -> String {
+    let reference = reference.replace('\\', "/");
+    let absolute = reference.starts_with('/') || Path::new(&reference).has_root() || {
+        // Windows drive letters such as `C:/`
+        let bytes = reference.as_bytes();
+        bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+    };
+    if absolute {
+        return reference;
+    }
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in dir.split('/').chain(reference.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Returns the directory part of a resource path.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::resolver::parent;
+///
+/// assert_eq!(parent("materials/wood.mtl"), "materials");
+/// assert_eq!(parent("wood.mtl"), "");
+/// ```
+pub fn parent(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_collapses_relative_segments() {
+        assert_eq!(join("a/b", "../c/./d.png"), "a/c/d.png");
+        assert_eq!(join("", "../up.png"), "../up.png");
+        assert_eq!(join("a", "../../up.png"), "../up.png");
+        assert_eq!(join("dir", "C:\\Models\\oak.png"), "C:/Models/oak.png");
+    }
+
+    #[test]
+    fn test_directory_resolver_reads_relative_to_root() {
+        let dir = std::env::temp_dir().join(format!("glimpse_resolver_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("textures")).unwrap();
+        std::fs::write(dir.join("textures").join("oak.png"), [1, 2]).unwrap();
+
+        let resolver = DirectoryResolver::for_model(&dir.join("model.obj"));
+        let read = resolver.read(&join("", "textures\\oak.png"));
+        let listed = resolver.list("textures");
+        let exists = resolver.exists("textures/oak.png");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(read.unwrap(), [1, 2]);
+        assert_eq!(listed.unwrap(), ["oak.png"]);
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_memory_resolver_walks_tree_from_model() {
+        let resolver = MemoryResolver::for_model("assets/game/shapes/chair.json")
+            .with_file("assets/game/textures/oak.png", vec![1])
+            .with_file("assets/other/textures/pine.png", vec![2]);
+        assert_eq!(resolver.read("../textures/oak.png").unwrap(), [1]);
+        assert_eq!(resolver.list("../..").unwrap(), ["game", "other"]);
+        assert!(resolver.exists("../../other/textures"));
+        assert!(!resolver.exists("../../other/tex"));
+        assert!(resolver.list("../../missing").is_err());
+    }
+
+    /// Builds a zip archive, deflating the entries marked as compressed.
+    #[cfg(feature = "zip")]
+    fn zip_archive(files: &[(&str, &[u8], bool)]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        let mut directory = Vec::new();
+        for &(name, data, deflate) in files {
+            let (method, stored) = if deflate {
+                (8u16, miniz_oxide::deflate::compress_to_vec(data, 6))
+            } else {
+                (0u16, data.to_vec())
+            };
+            let header = archive.len() as u32;
+            let fields = |out: &mut Vec<u8>| {
+                out.extend_from_slice(&[20, 0, 0, 0]); // version, flags
+                out.extend_from_slice(&method.to_le_bytes());
+                out.extend_from_slice(&[0; 8]); // time, date, crc
+                out.extend_from_slice(&(stored.len() as u32).to_le_bytes());
+                out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+                out.extend_from_slice(&[0, 0]); // extra length
+            };
+            archive.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+            fields(&mut archive);
+            archive.extend_from_slice(name.as_bytes());
+            archive.extend_from_slice(&stored);
+
+            directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            directory.extend_from_slice(&[20, 0]); // version made by
+            fields(&mut directory);
+            directory.extend_from_slice(&[0; 10]); // comment, disk, attributes
+            directory.extend_from_slice(&header.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+        }
+
+        let offset = archive.len() as u32;
+        archive.extend_from_slice(&directory);
+        archive.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        archive.extend_from_slice(&[0; 4]); // disk numbers
+        archive.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        archive.extend_from_slice(&offset.to_le_bytes());
+        archive.extend_from_slice(&[0, 0]); // comment length
+        archive
+    }
+
+    #[test]
+    #[cfg(feature = "zip")]
+    fn test_zip_resolver_reads_stored_and_deflated_entries() {
+        let text = b"newmtl Oak\nKd 0.6 0.4 0.2\n".repeat(20);
+        let archive = zip_archive(&[
+            ("mod/models/chair.obj", b"v 0 0 0\n", false),
+            ("mod/models/chair.mtl", &text, true),
+            ("mod/textures/oak.png", &[1, 2, 3], false),
+        ]);
+        let resolver = ZipResolver::for_model(archive, "mod/models/chair.obj").unwrap();
+        assert_eq!(resolver.files().count(), 3);
+        assert_eq!(resolver.read("chair.obj").unwrap(), b"v 0 0 0\n");
+        assert_eq!(resolver.read("chair.mtl").unwrap(), text);
+        assert_eq!(resolver.read("../textures/oak.png").unwrap(), [1, 2, 3]);
+        assert_eq!(resolver.list("..").unwrap(), ["models", "textures"]);
+        assert!(resolver.exists("../textures"));
+        assert!(resolver.read("missing.png").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "zip")]
+    fn test_zip_resolver_rejects_truncated_archives() {
+        let archive = zip_archive(&[("a.bin", &[1, 2, 3], false)]);
+        assert!(ZipResolver::new(archive[..archive.len() - 1].to_vec()).is_err());
+        // A central directory pointing past the data
+        let mut broken = archive.clone();
+        let offset = broken.len() - 6;
+        broken[offset..offset + 4].copy_from_slice(&1000u32.to_le_bytes());
+        assert!(ZipResolver::new(broken).is_err());
+    }
+}
@@ -0,0 +1,524 @@
+//! Provides incremental construction of indexed meshes.
+//!
+//! Loaders that produce geometry one triangle at a time can push triangles
+//! into a [`MeshBuilder`], which shares identical vertices so the resulting
+//! [`Mesh`] stores each position/UV pair only once. A [`ModelBuilder`] does
+//! the same for a whole model, keeping one mesh per distinct [`Material`] and
+//! scene [`Node`].
+//!
+//! # Examples
+//! ```
+//! use glimpse::formats::shared::mesh::MeshBuilder;
+//!
+//! let mut builder = MeshBuilder::new(0);
+//! builder.push_triangle(
+//!     [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+//!     [[0.0, 0.0]; 3],
+//! );
+//! let mesh = builder.build();
+//! assert_eq!(mesh.triangle_count(), 1);
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::formats::{Mat4, Material, Mesh, ModelData, Node, Vec2, Vec3};
+
+/// Builds a [`Mesh`] while sharing identical vertices.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::shared::mesh::MeshBuilder;
+///
+/// let mut builder = MeshBuilder::new(2);
+/// let a = builder.vertex([0.0, 0.0, 0.0], [0.0, 0.0]);
+/// let b = builder.vertex([0.0, 0.0, 0.0], [0.0, 0.0]);
+/// assert_eq!(a, b);
+/// assert_eq!(builder.build().material, 2);
+/// ```
+pub struct MeshBuilder {
+    mesh: Mesh,
+    lookup: HashMap<[u32; 11], u32>,
+}
+
+impl MeshBuilder {
+    /// Creates an empty builder for a mesh using `material`.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::MeshBuilder;
+    ///
+    /// let builder = MeshBuilder::new(0);
+    /// assert!(builder.is_empty());
+    /// ```
+    pub fn new(material: usize) -> Self {
+        Self {
+            mesh: Mesh {
+                material,
+                ..Default::default()
+            },
+            lookup: HashMap::new(),
+        }
+    }
+
+    /// Returns the index of a vertex with the given position and UV, adding it if new.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::MeshBuilder;
+    ///
+    /// let mut builder = MeshBuilder::new(0);
+    /// assert_eq!(builder.vertex([0.0, 0.0, 0.0], [0.0, 0.0]), 0);
+    /// assert_eq!(builder.vertex([1.0, 0.0, 0.0], [0.0, 0.0]), 1);
+    /// ```
+    pub fn vertex(&mut self, position: Vec3, uv: Vec2) -> u32 {
+        self.add_vertex(position, uv, [0.0; 3], [1.0; 3])
+    }
+
+    /// Returns the index of a vertex with the given position, UV and normal,
+    /// adding it if new.
+    ///
+    /// Vertices added without a normal get a zero normal, which the renderer
+    /// shades flat.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::MeshBuilder;
+    ///
+    /// let mut builder = MeshBuilder::new(0);
+    /// let up = builder.vertex_with_normal([0.0; 3], [0.0, 0.0], [0.0, 1.0, 0.0]);
+    /// let side = builder.vertex_with_normal([0.0; 3], [0.0, 0.0], [1.0, 0.0, 0.0]);
+    /// assert_ne!(up, side);
+    /// assert_eq!(builder.build().normals.unwrap()[1], [1.0, 0.0, 0.0]);
+    /// ```
+    pub fn vertex_with_normal(&mut self, position: Vec3, uv: Vec2, normal: Vec3) -> u32 {
+        self.vertex_with_attributes(position, uv, Some(normal), None)
+    }
+
+    /// Returns the index of a vertex with the given position, UV and optional
+    /// normal and RGB color, adding it if new.
+    ///
+    /// Normals and colors are stored once any vertex has them; vertices added
+    /// without one get a zero normal or a white color.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::MeshBuilder;
+    ///
+    /// let mut builder = MeshBuilder::new(0);
+    /// builder.vertex([0.0; 3], [0.0, 0.0]);
+    /// let red = builder.vertex_with_attributes([1.0; 3], [0.0, 0.0], None, Some([1.0, 0.0, 0.0]));
+    /// let colors = builder.build().colors.unwrap();
+    /// assert_eq!(colors, vec![[1.0; 3], [1.0, 0.0, 0.0]]);
+    /// assert_eq!(red, 1);
+    /// ```
+    pub fn vertex_with_attributes(
+        &mut self,
+        position: Vec3,
+        uv: Vec2,
+        normal: Option<Vec3>,
+        color: Option<[f32; 3]>,
+    ) -> u32 {
+        let count = self.mesh.positions.len();
+        if normal.is_some() && self.mesh.normals.is_none() {
+            self.mesh.normals = Some(vec![[0.0; 3]; count]);
+        }
+        if color.is_some() && self.mesh.colors.is_none() {
+            self.mesh.colors = Some(vec![[1.0; 3]; count]);
+        }
+        self.add_vertex(
+            position,
+            uv,
+            normal.unwrap_or([0.0; 3]),
+            color.unwrap_or([1.0; 3]),
+        )
+    }
+
+    /// Looks up or appends a vertex; `normal` and `color` are only stored once
+    /// the mesh has normals or colors.
+    fn add_vertex(&mut self, position: Vec3, uv: Vec2, normal: Vec3, color: [f32; 3]) -> u32 {
+        let key = [
+            position[0].to_bits(),
+            position[1].to_bits(),
+            position[2].to_bits(),
+            uv[0].to_bits(),
+            uv[1].to_bits(),
+            normal[0].to_bits(),
+            normal[1].to_bits(),
+            normal[2].to_bits(),
+            color[0].to_bits(),
+            color[1].to_bits(),
+            color[2].to_bits(),
+        ];
+        *self.lookup.entry(key).or_insert_with(|| {
+            self.mesh.positions.push(position);
+            self.mesh.uvs.push(uv);
+            if let Some(normals) = self.mesh.normals.as_mut() {
+                normals.push(normal);
+            }
+            if let Some(colors) = self.mesh.colors.as_mut() {
+                colors.push(color);
+            }
+            (self.mesh.positions.len() - 1) as u32
+        })
+    }
+
+    /// Appends a triangle made of previously added vertices.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::MeshBuilder;
+    ///
+    /// let mut builder = MeshBuilder::new(0);
+    /// let a = builder.vertex([0.0, 0.0, 0.0], [0.0, 0.0]);
+    /// let b = builder.vertex([1.0, 0.0, 0.0], [1.0, 0.0]);
+    /// let c = builder.vertex([0.0, 1.0, 0.0], [0.0, 1.0]);
+    /// builder.triangle(a, b, c);
+    /// assert_eq!(builder.build().indices, vec![0, 1, 2]);
+    /// ```
+    pub fn triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.mesh.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    /// Appends a triangle given by its vertex positions and UVs.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::MeshBuilder;
+    ///
+    /// let mut builder = MeshBuilder::new(0);
+    /// let verts = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+    /// builder.push_triangle(verts, [[0.0, 0.0]; 3]);
+    /// builder.push_triangle(verts, [[0.0, 0.0]; 3]);
+    /// let mesh = builder.build();
+    /// assert_eq!(mesh.positions.len(), 3);
+    /// assert_eq!(mesh.triangle_count(), 2);
+    /// ```
+    pub fn push_triangle(&mut self, verts: [Vec3; 3], uvs: [Vec2; 3]) {
+        let a = self.vertex(verts[0], uvs[0]);
+        let b = self.vertex(verts[1], uvs[1]);
+        let c = self.vertex(verts[2], uvs[2]);
+        self.triangle(a, b, c);
+    }
+
+    /// Returns whether no triangles have been added.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::MeshBuilder;
+    ///
+    /// assert!(MeshBuilder::new(0).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.mesh.indices.is_empty()
+    }
+
+    /// Finishes building and returns the mesh.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::MeshBuilder;
+    ///
+    /// let mesh = MeshBuilder::new(0).build();
+    /// assert!(mesh.positions.is_empty());
+    /// ```
+    pub fn build(self) -> Mesh {
+        self.mesh
+    }
+}
+
+/// Builds [`ModelData`] with one mesh per distinct material and node.
+///
+/// Geometry is assigned to the node selected with [`ModelBuilder::set_node`];
+/// the finished meshes are ordered by node so each node owns a contiguous range.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::shared::mesh::ModelBuilder;
+/// use glimpse::formats::Material;
+///
+/// let mut builder = ModelBuilder::new();
+/// let material = builder.material(Material::default());
+/// builder.push_quad(
+///     material,
+///     [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+///     [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+/// );
+/// let model = builder.build();
+/// assert_eq!(model.triangle_count(), 2);
+/// assert_eq!(model.meshes[0].positions.len(), 4);
+/// ```
+#[derive(Default)]
+pub struct ModelBuilder {
+    materials: Vec<Material>,
+    nodes: Vec<Node>,
+    node: Option<usize>,
+    meshes: BTreeMap<(Option<usize>, usize), MeshBuilder>,
+}
+
+impl ModelBuilder {
+    /// Creates an empty builder.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::ModelBuilder;
+    ///
+    /// assert!(ModelBuilder::new().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of `material`, adding it unless an equal material exists.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::ModelBuilder;
+    /// use glimpse::formats::Material;
+    ///
+    /// let mut builder = ModelBuilder::new();
+    /// let a = builder.material(Material::default());
+    /// let b = builder.material(Material::default());
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn material(&mut self, material: Material) -> usize {
+        if let Some(index) = self.materials.iter().position(|m| *m == material) {
+            return index;
+        }
+        self.materials.push(material);
+        self.materials.len() - 1
+    }
+
+    /// Adds a scene node under `parent` and returns its index.
+    ///
+    /// # Panics
+    /// Panics if `parent` was not returned by [`ModelBuilder::add_node`].
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::ModelBuilder;
+    /// use glimpse::formats::Node;
+    ///
+    /// let mut builder = ModelBuilder::new();
+    /// let identity = Node::default().transform;
+    /// let body = builder.add_node(Some("body".to_string()), identity, None);
+    /// let head = builder.add_node(Some("head".to_string()), identity, Some(body));
+    /// let model = builder.build();
+    /// assert_eq!(model.nodes[body].children, vec![head]);
+    /// assert_eq!(model.nodes[head].parent, Some(body));
+    /// ```
+    pub fn add_node(
+        &mut self,
+        name: Option<String>,
+        transform: Mat4,
+        parent: Option<usize>,
+    ) -> usize {
+        let index = self.nodes.len();
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(index);
+        }
+        self.nodes.push(Node {
+            name,
+            transform,
+            parent,
+            ..Default::default()
+        });
+        index
+    }
+
+    /// Selects the node that receives subsequently pushed geometry.
+    ///
+    /// `None` leaves geometry outside the scene tree.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::ModelBuilder;
+    /// use glimpse::formats::{Material, Node};
+    ///
+    /// let mut builder = ModelBuilder::new();
+    /// let material = builder.material(Material::default());
+    /// let node = builder.add_node(None, Node::default().transform, None);
+    /// builder.set_node(Some(node));
+    /// builder.push_triangle(
+    ///     material,
+    ///     [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+    ///     [[0.0, 0.0]; 3],
+    /// );
+    /// assert_eq!(builder.build().nodes[node].meshes, 0..1);
+    /// ```
+    pub fn set_node(&mut self, node: Option<usize>) {
+        self.node = node;
+    }
+
+    /// Returns the mesh builder for `material` in the current node.
+    fn mesh(&mut self, material: usize) -> &mut MeshBuilder {
+        self.meshes
+            .entry((self.node, material))
+            .or_insert_with(|| MeshBuilder::new(material))
+    }
+
+    /// Appends a triangle using the material at index `material`.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::ModelBuilder;
+    /// use glimpse::formats::Material;
+    ///
+    /// let mut builder = ModelBuilder::new();
+    /// let material = builder.material(Material::default());
+    /// builder.push_triangle(
+    ///     material,
+    ///     [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+    ///     [[0.0, 0.0]; 3],
+    /// );
+    /// assert!(!builder.is_empty());
+    /// ```
+    pub fn push_triangle(&mut self, material: usize, verts: [Vec3; 3], uvs: [Vec2; 3]) {
+        self.mesh(material).push_triangle(verts, uvs);
+    }
+
+    /// Appends a quad as two triangles, (0, 1, 2) and (0, 2, 3).
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::ModelBuilder;
+    /// use glimpse::formats::Material;
+    ///
+    /// let mut builder = ModelBuilder::new();
+    /// let material = builder.material(Material::default());
+    /// builder.push_quad(
+    ///     material,
+    ///     [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+    ///     [[0.0, 0.0]; 4],
+    /// );
+    /// assert_eq!(builder.build().triangle_count(), 2);
+    /// ```
+    pub fn push_quad(&mut self, material: usize, verts: [Vec3; 4], uvs: [Vec2; 4]) {
+        self.push_triangle(
+            material,
+            [verts[0], verts[1], verts[2]],
+            [uvs[0], uvs[1], uvs[2]],
+        );
+        self.push_triangle(
+            material,
+            [verts[0], verts[2], verts[3]],
+            [uvs[0], uvs[2], uvs[3]],
+        );
+    }
+
+    /// Returns whether no triangles have been added.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::ModelBuilder;
+    ///
+    /// assert!(ModelBuilder::new().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.meshes.values().all(MeshBuilder::is_empty)
+    }
+
+    /// Finishes building. Materials that never received geometry keep their table entry but get no mesh.
+    ///
+    /// Meshes outside the scene tree come first, followed by each node's meshes in node order.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::formats::shared::mesh::ModelBuilder;
+    /// use glimpse::formats::Material;
+    ///
+    /// let mut builder = ModelBuilder::new();
+    /// builder.material(Material::default());
+    /// assert!(builder.build().meshes.is_empty());
+    /// ```
+    pub fn build(self) -> ModelData {
+        let mut nodes = self.nodes;
+        let mut meshes = Vec::new();
+        // Keys are ordered by node, so each node's meshes are emitted back to back
+        for ((node, _), builder) in self.meshes {
+            if builder.is_empty() {
+                continue;
+            }
+            if let Some(node) = node {
+                let range = &mut nodes[node].meshes;
+                if range.end != meshes.len() {
+                    *range = meshes.len()..meshes.len();
+                }
+                range.end += 1;
+            }
+            meshes.push(builder.build());
+        }
+
+        ModelData {
+            meshes,
+            materials: self.materials,
+            nodes,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shares_identical_vertices() {
+        let mut builder = MeshBuilder::new(0);
+        let quad = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let uvs = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        builder.push_triangle([quad[0], quad[1], quad[2]], [uvs[0], uvs[1], uvs[2]]);
+        builder.push_triangle([quad[0], quad[2], quad[3]], [uvs[0], uvs[2], uvs[3]]);
+
+        let mesh = builder.build();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn test_groups_meshes_by_node() {
+        let mut builder = ModelBuilder::new();
+        let red = builder.material(Material {
+            color: [1.0, 0.0, 0.0],
+            ..Default::default()
+        });
+        let blue = builder.material(Material {
+            color: [0.0, 0.0, 1.0],
+            ..Default::default()
+        });
+        let identity = Node::default().transform;
+        let body = builder.add_node(Some("body".to_string()), identity, None);
+        let arm = builder.add_node(Some("arm".to_string()), identity, Some(body));
+        let tri = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+        // Interleave nodes to check that their meshes still end up contiguous
+        for node in [arm, body, arm, body] {
+            builder.set_node(Some(node));
+            builder.push_triangle(red, tri, [[0.0, 0.0]; 3]);
+        }
+        builder.set_node(Some(arm));
+        builder.push_triangle(blue, tri, [[0.0, 0.0]; 3]);
+        builder.set_node(None);
+        builder.push_triangle(blue, tri, [[0.0, 0.0]; 3]);
+
+        let model = builder.build();
+        assert_eq!(model.meshes.len(), 4);
+        assert_eq!(model.nodes[body].meshes, 1..2);
+        assert_eq!(model.nodes[arm].meshes, 2..4);
+        assert_eq!(model.node_meshes(body), vec![1, 2, 3]);
+        assert_eq!(model.find_node("arm"), Some(arm));
+    }
+
+    #[test]
+    fn test_keeps_uv_seams_separate() {
+        let mut builder = MeshBuilder::new(0);
+        let a = builder.vertex([0.0, 0.0, 0.0], [0.0, 0.0]);
+        let b = builder.vertex([0.0, 0.0, 0.0], [1.0, 0.0]);
+        assert_ne!(a, b);
+        assert_eq!(builder.build().uvs.len(), 2);
+    }
+}
@@ -2,9 +2,11 @@
 //!
 //! This module provides common functionality used by multiple format loaders:
 //! - Cube geometry (vertices, faces, triangles)
+//! - Indexed mesh construction
 //! - Rotation transforms
 //! - JSON parsing helpers
 //! - Texture loading
+//! - Polygon triangulation
 //!
 //! # Examples
 //! ```
@@ -16,9 +18,12 @@
 
 pub mod cube;
 pub mod json;
+pub mod mesh;
 pub mod rotation;
 pub mod texture;
+pub mod triangulate;
 
 pub use cube::{compute_cube_vertices, scale_vec3, CubeFace, BLOCK_SCALE, CUBE_FACES};
 pub use json::{json_str_or_none, parse_vec3};
 pub use rotation::{rotate_vertices, rotate_vertices_xyz, RotationOrder, RotationTransform};
+pub use triangulate::triangulate;
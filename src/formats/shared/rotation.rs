@@ -21,7 +21,7 @@
 //! assert_eq!(rotated[0], vertices[0]);
 //! ```
 
-use crate::formats::Vec3;
+use crate::formats::{ModelData, Vec3};
 use glam::{EulerRot, Mat4, Vec3 as GlamVec3};
 
 /// The Euler rotation order for applying rotations.
@@ -321,6 +321,84 @@ pub fn apply_rotations(vertices: &[Vec3; 8], transforms: &[RotationTransform]) -
     result
 }
 
+/// Returns the scene node transform for an optional pivot rotation.
+///
+/// The pivot is given in source units and multiplied by `scale`, matching how
+/// the cube loaders scale vertex positions. No rotation yields the identity.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::shared::rotation::{pivot_node_transform, RotationTransform};
+///
+/// assert_eq!(pivot_node_transform(None, 1.0)[3], [0.0, 0.0, 0.0, 1.0]);
+///
+/// let rotation = RotationTransform::new([16.0, 0.0, 0.0], [0.0, 180.0, 0.0]);
+/// let matrix = pivot_node_transform(Some(rotation), 1.0 / 16.0);
+/// // Turning around x = 1 moves the origin to x = 2
+/// assert!((matrix[3][0] - 2.0).abs() < 1e-5);
+/// ```
+pub fn pivot_node_transform(
+    rotation: Option<RotationTransform>,
+    scale: f32,
+) -> crate::formats::Mat4 {
+    match rotation {
+        Some(rotation) => RotationTransform::with_order(
+            rotation.origin.map(|c| c * scale),
+            rotation.angles,
+            rotation.order,
+        )
+        .to_matrix()
+        .to_cols_array_2d(),
+        None => Mat4::IDENTITY.to_cols_array_2d(),
+    }
+}
+
+/// Rotates a whole model 180 degrees around the Y axis through its bounding-box center.
+///
+/// Root node transforms are turned as well.
+///
+/// Block-model formats face away from the default thumbnail camera, so their
+/// loaders turn the model around after conversion.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::shared::rotation::rotate_model_y_180;
+/// use glimpse::formats::{ModelData, Triangle};
+///
+/// let mut model = ModelData::from_triangles(vec![Triangle {
+///     verts: [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 1.0, 4.0]],
+///     uvs: [[0.0, 0.0]; 3],
+///     color: [1.0, 1.0, 1.0],
+///     texture: None,
+/// }]);
+/// rotate_model_y_180(&mut model);
+/// assert_eq!(model.meshes[0].positions[0], [2.0, 0.0, 4.0]);
+/// ```
+pub fn rotate_model_y_180(model: &mut ModelData) {
+    let mut min = GlamVec3::splat(f32::INFINITY);
+    let mut max = GlamVec3::splat(f32::NEG_INFINITY);
+    for v in model.meshes.iter().flat_map(|m| &m.positions) {
+        let p = GlamVec3::from_array(*v);
+        min = min.min(p);
+        max = max.max(p);
+    }
+    let center = (min + max) * 0.5;
+
+    // 180° Y rotation = reflect X and Z through center
+    for v in model.meshes.iter_mut().flat_map(|m| &mut m.positions) {
+        v[0] = 2.0 * center.x - v[0];
+        v[2] = 2.0 * center.z - v[2];
+    }
+
+    // Root nodes carry the turn so the scene tree still matches the meshes
+    let turn = Mat4::from_translation(center)
+        * Mat4::from_rotation_y(std::f32::consts::PI)
+        * Mat4::from_translation(-center);
+    for node in model.nodes.iter_mut().filter(|n| n.parent.is_none()) {
+        node.transform = (turn * Mat4::from_cols_array_2d(&node.transform)).to_cols_array_2d();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,17 @@
 //! Provides functions for loading textures from various sources,
 //! including base64-encoded data URLs.
 //!
+//! PNG and JPEG are always available. WebP, TGA, BMP and DDS are decoded when
+//! the cargo features of the same names are enabled (all on by default), and
+//! KTX2 containers with the `ktx2` feature. Basis Universal UASTC textures
+//! inside KTX2 need the additional `basisu` feature, which builds the C++
+//! transcoder.
+//!
+//! `KHR_texture_basisu` images are always Basis Universal encoded, as UASTC
+//! or as ETC1S with BasisLZ supercompression. Default builds decode neither,
+//! and no build decodes ETC1S, so glTF textures using that extension fall
+//! back to their core `source` image in those cases.
+//!
 //! # Examples
 //! ```
 //! use glimpse::formats::shared::texture::load_texture_from_data_url;
@@ -13,7 +24,6 @@
 //! assert!(texture.is_some());
 //! ```
 
-use std::path::Path;
 use std::sync::Arc;
 
 use crate::formats::TextureData;
@@ -46,9 +56,44 @@ pub fn load_texture_from_data_url(source: &str) -> Option<Arc<TextureData>> {
         .decode(encoded)
         .ok()?;
 
-    // Decode image
-    use image::GenericImageView;
-    let img = image::load_from_memory(&bytes).ok()?;
+    // The MIME type identifies formats without a signature, such as TGA
+    let mime_type = source[5..comma_pos].split(';').next();
+    decode_texture(&bytes, mime_type)
+}
+
+/// Signature at the start of every KTX2 file.
+const KTX2_MAGIC: [u8; 12] = [
+    0xab, 0x4b, 0x54, 0x58, 0x20, 0x32, 0x30, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a,
+];
+
+/// Decodes an encoded image into an RGBA texture.
+///
+/// The format is detected from the data's signature. `hint` is a file
+/// extension or MIME type used when the data has no signature, as with TGA.
+/// Returns None for unrecognized data, formats whose feature is disabled, and
+/// decoding errors.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::shared::texture::decode_texture;
+///
+/// // A 1x1 uncompressed 32-bit TGA, which can only be recognized by its extension
+/// let tga = [0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 32, 8, 255, 0, 0, 255];
+/// # #[cfg(feature = "tga")]
+/// assert_eq!(decode_texture(&tga, Some("tga")).unwrap().data, [0, 0, 255, 255]);
+/// assert!(decode_texture(b"not an image", None).is_none());
+/// ```
+pub fn decode_texture(bytes: &[u8], hint: Option<&str>) -> Option<Arc<TextureData>> {
+    if bytes.starts_with(&KTX2_MAGIC) {
+        return decode_ktx2(bytes).map(Arc::new);
+    }
+
+    use image::{GenericImageView, ImageFormat};
+    let format = image::guess_format(bytes).ok().or_else(|| {
+        let hint = hint?.to_ascii_lowercase();
+        ImageFormat::from_mime_type(&hint).or_else(|| ImageFormat::from_extension(&hint))
+    })?;
+    let img = image::load_from_memory_with_format(bytes, format).ok()?;
     let (width, height) = img.dimensions();
     let rgba = img.to_rgba8();
 
@@ -59,20 +104,100 @@ pub fn load_texture_from_data_url(source: &str) -> Option<Arc<TextureData>> {
     }))
 }
 
-/// Loads a texture from an image file on disk (PNG, JPEG, etc.).
+#[cfg(not(feature = "ktx2"))]
+fn decode_ktx2(_bytes: &[u8]) -> Option<TextureData> {
+    None
+}
+
+/// Decodes the first image of a KTX2 file's base mip level.
 ///
-/// Returns None if the file cannot be read or decoded.
-pub fn load_texture_from_file(path: &Path) -> Option<Arc<TextureData>> {
-    let bytes = std::fs::read(path).ok()?;
-    use image::GenericImageView;
-    let img = image::load_from_memory(&bytes).ok()?;
-    let (width, height) = img.dimensions();
-    let rgba = img.to_rgba8();
-    Some(Arc::new(TextureData {
+/// Supports uncompressed RGBA8 data, optionally Zstandard or zlib
+/// supercompressed, and UASTC with the `basisu` feature. ETC1S (BasisLZ)
+/// textures are not supported.
+#[cfg(feature = "ktx2")]
+fn decode_ktx2(bytes: &[u8]) -> Option<TextureData> {
+    use ktx2::{ColorModel, Format, SupercompressionScheme};
+
+    let reader = ktx2::Reader::new(bytes).ok()?;
+    let header = reader.header();
+    let level = reader.levels().next()?;
+    let width = header.pixel_width;
+    let height = header.pixel_height.max(1);
+
+    let data = match header.supercompression_scheme {
+        None => level.data.to_vec(),
+        Some(SupercompressionScheme::Zstandard) => {
+            use std::io::Read;
+            let mut data = Vec::new();
+            ruzstd::decoding::StreamingDecoder::new(level.data)
+                .ok()?
+                .take(level.uncompressed_byte_length)
+                .read_to_end(&mut data)
+                .ok()?;
+            data
+        }
+        Some(SupercompressionScheme::ZLIB) => {
+            miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+                level.data,
+                usize::try_from(level.uncompressed_byte_length).ok()?,
+            )
+            .ok()?
+        }
+        Some(_) => return None,
+    };
+
+    let pixels = match header.format {
+        Some(Format::R8G8B8A8_UNORM) | Some(Format::R8G8B8A8_SRGB) => {
+            let size = (width as usize)
+                .checked_mul(height as usize)?
+                .checked_mul(4)?;
+            data.get(..size)?.to_vec()
+        }
+        None if reader.color_model() == Some(ColorModel::UASTC) => {
+            transcode_uastc(&data, width, height)?
+        }
+        _ => return None,
+    };
+
+    Some(TextureData {
         width,
         height,
-        data: rgba.into_raw(),
-    }))
+        data: pixels,
+    })
+}
+
+#[cfg(all(feature = "ktx2", not(feature = "basisu")))]
+fn transcode_uastc(_data: &[u8], _width: u32, _height: u32) -> Option<Vec<u8>> {
+    None
+}
+
+/// Transcodes the first UASTC slice of a level to RGBA.
+#[cfg(feature = "basisu")]
+fn transcode_uastc(data: &[u8], width: u32, height: u32) -> Option<Vec<u8>> {
+    use basis_universal::{
+        DecodeFlags, LowLevelUastcTranscoder, SliceParametersUastc, TranscoderBlockFormat,
+    };
+
+    // UASTC stores 16 bytes per 4x4 block
+    let (blocks_x, blocks_y) = (width.div_ceil(4), height.div_ceil(4));
+    let size = (blocks_x as usize)
+        .checked_mul(blocks_y as usize)?
+        .checked_mul(16)?;
+    let parameters = SliceParametersUastc {
+        num_blocks_x: blocks_x,
+        num_blocks_y: blocks_y,
+        has_alpha: true,
+        original_width: width,
+        original_height: height,
+    };
+    LowLevelUastcTranscoder::new()
+        .transcode_slice(
+            data.get(..size)?,
+            parameters,
+            DecodeFlags::empty(),
+            TranscoderBlockFormat::RGBA32,
+        )
+        .ok()
 }
 
 #[cfg(test)]
@@ -126,4 +251,45 @@ mod tests {
         assert_eq!(tex.data[2], 255); // B
         assert_eq!(tex.data[3], 255); // A
     }
+
+    /// Builds a 1x1 uncompressed `R8G8B8A8_UNORM` KTX2 file.
+    #[cfg(feature = "ktx2")]
+    fn create_test_ktx2(pixel: [u8; 4]) -> Vec<u8> {
+        let mut bytes = KTX2_MAGIC.to_vec();
+        // vkFormat, typeSize, width, height, depth, layers, faces, levels, supercompression
+        for value in [37u32, 1, 1, 1, 0, 0, 1, 1, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        // DFD offset and length, key/value data offset and length
+        for value in [104u32, 4, 0, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        // Supercompression global data offset and length, then the level index
+        for value in [0u64, 0, 108, 4, 4] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&4u32.to_le_bytes());
+        bytes.extend_from_slice(&pixel);
+        bytes
+    }
+
+    #[test]
+    #[cfg(feature = "ktx2")]
+    fn test_decode_ktx2_rgba8() {
+        let texture = decode_texture(&create_test_ktx2([10, 20, 30, 40]), None).unwrap();
+        assert_eq!((texture.width, texture.height), (1, 1));
+        assert_eq!(texture.data, vec![10, 20, 30, 40]);
+    }
+
+    #[test]
+    #[cfg(feature = "tga")]
+    fn test_decode_tga_from_extension_hint() {
+        // TGA has no magic number, so the extension picks the decoder
+        let tga = [
+            0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 24, 0, 30, 20, 10,
+        ];
+        let texture = decode_texture(&tga, Some("TGA")).unwrap();
+        assert_eq!(texture.data, vec![10, 20, 30, 255]);
+        assert!(decode_texture(&tga, None).is_none());
+    }
 }
@@ -0,0 +1,270 @@
+//! Provides polygon triangulation for formats with N-gon faces.
+//!
+//! Polygons are projected onto the plane given by their Newell normal and
+//! split by ear clipping, so concave outlines such as L-shapes or lettering
+//! only produce triangles inside the polygon. Convex polygons take a fan
+//! fast path.
+//!
+//! # Examples
+//! ```
+//! use glimpse::formats::shared::triangulate::triangulate;
+//!
+//! // An L-shaped hexagon
+//! let polygon = [
+//!     [0.0, 0.0, 0.0],
+//!     [2.0, 0.0, 0.0],
+//!     [2.0, 1.0, 0.0],
+//!     [1.0, 1.0, 0.0],
+//!     [1.0, 2.0, 0.0],
+//!     [0.0, 2.0, 0.0],
+//! ];
+//! assert_eq!(triangulate(&polygon).len(), 4);
+//! ```
+
+use crate::formats::Vec3;
+
+/// Splits a simple polygon into triangles.
+///
+/// Returns triangles as indices into `polygon`, wound the same way as the
+/// polygon. Convex polygons are fanned from their first corner; concave ones
+/// are ear-clipped in the plane of the polygon. Degenerate polygons, and
+/// self-intersecting ones that leave no ear to clip, fall back to a fan.
+///
+/// # Examples
+/// ```
+/// use glimpse::formats::shared::triangulate::triangulate;
+///
+/// let quad = [
+///     [0.0, 0.0, 0.0],
+///     [1.0, 0.0, 0.0],
+///     [1.0, 1.0, 0.0],
+///     [0.0, 1.0, 0.0],
+/// ];
+/// assert_eq!(triangulate(&quad), vec![[0, 1, 2], [0, 2, 3]]);
+/// ```
+pub fn triangulate(polygon: &[Vec3]) -> Vec<[usize; 3]> {
+    let n = polygon.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    if n == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    let Some(points) = project(polygon) else {
+        return fan(&(0..n).collect::<Vec<_>>());
+    };
+    let convex =
+        (0..n).all(|i| cross(points[(i + n - 1) % n], points[i], points[(i + 1) % n]) >= 0.0);
+    if convex {
+        return fan(&(0..n).collect::<Vec<_>>());
+    }
+
+    clip_ears(&points)
+}
+
+/// Projects a polygon onto the plane of its normal, dropping the normal's
+/// dominant axis and mirroring so the outline winds counter-clockwise.
+///
+/// Returns `None` if the polygon has no area.
+fn project(polygon: &[Vec3]) -> Option<Vec<[f32; 2]>> {
+    let normal = newell_normal(polygon);
+    let abs = normal.map(f32::abs);
+    let axis = if abs[0] >= abs[1] && abs[0] >= abs[2] {
+        0
+    } else if abs[1] >= abs[2] {
+        1
+    } else {
+        2
+    };
+    if abs[axis] == 0.0 || !abs[axis].is_finite() {
+        return None;
+    }
+
+    // Keep a right-handed (u, v) pair so positive normals wind counter-clockwise
+    let (u, v) = [(1, 2), (2, 0), (0, 1)][axis];
+    let flip = normal[axis] < 0.0;
+    Some(
+        polygon
+            .iter()
+            .map(|p| if flip { [p[v], p[u]] } else { [p[u], p[v]] })
+            .collect(),
+    )
+}
+
+/// Computes a polygon's area-weighted normal with Newell's method.
+fn newell_normal(polygon: &[Vec3]) -> Vec3 {
+    let mut normal = [0.0; 3];
+    for (k, a) in polygon.iter().enumerate() {
+        let b = polygon[(k + 1) % polygon.len()];
+        normal[0] += (a[1] - b[1]) * (a[2] + b[2]);
+        normal[1] += (a[2] - b[2]) * (a[0] + b[0]);
+        normal[2] += (a[0] - b[0]) * (a[1] + b[1]);
+    }
+    normal
+}
+
+/// Ear-clips a counter-clockwise 2D polygon.
+///
+/// Corners form a linked ring, so clipping one is constant time, and the
+/// search for the next ear resumes at the last one clipped. Only reflex
+/// corners are tested against a candidate ear: if any corner lies inside it,
+/// a reflex one does too. This keeps large outlines near O(n * reflex).
+fn clip_ears(points: &[[f32; 2]]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    let mut next: Vec<usize> = (0..n).map(|i| (i + 1) % n).collect();
+    let mut prev: Vec<usize> = (0..n).map(|i| (i + n - 1) % n).collect();
+    let turn = |prev: &[usize], next: &[usize], i: usize| {
+        cross(points[prev[i]], points[i], points[next[i]])
+    };
+    let mut reflex: Vec<usize> = (0..n).filter(|&i| turn(&prev, &next, i) <= 0.0).collect();
+    let mut triangles = Vec::with_capacity(n - 2);
+
+    let mut remaining = n;
+    let mut corner = 0;
+    let mut misses = 0;
+    while remaining > 3 {
+        let [a, b, c] = [prev[corner], corner, next[corner]];
+        let (pa, pb, pc) = (points[a], points[b], points[c]);
+        // Reflex or flat corners cannot be ears, nor can triangles that
+        // contain another corner of the outline
+        let ear = cross(pa, pb, pc) > 0.0
+            && !reflex.iter().any(|&i| {
+                let p = points[i];
+                p != pa && p != pb && p != pc && inside(p, pa, pb, pc)
+            });
+        if !ear {
+            misses += 1;
+            if misses == remaining {
+                // Self-intersecting or numerically degenerate outline
+                let ring: Vec<usize> = std::iter::successors(Some(corner), |&i| Some(next[i]))
+                    .take(remaining)
+                    .collect();
+                triangles.extend(fan(&ring));
+                return triangles;
+            }
+            corner = c;
+            continue;
+        }
+
+        triangles.push([a, b, c]);
+        next[a] = c;
+        prev[c] = a;
+        remaining -= 1;
+        misses = 0;
+        // Only the clipped corner's neighbours change shape
+        reflex.retain(|&i| i != a && i != c);
+        reflex.extend([a, c].into_iter().filter(|&i| turn(&prev, &next, i) <= 0.0));
+        corner = c;
+    }
+
+    triangles.push([prev[corner], corner, next[corner]]);
+    triangles
+}
+
+/// Fans triangles out from the first of `corners`.
+fn fan(corners: &[usize]) -> Vec<[usize; 3]> {
+    corners
+        .windows(2)
+        .skip(1)
+        .map(|pair| [corners[0], pair[0], pair[1]])
+        .collect()
+}
+
+/// Returns twice the signed area of triangle `abc`; positive if counter-clockwise.
+fn cross(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Returns whether `p` lies inside or on the counter-clockwise triangle `abc`.
+fn inside(p: [f32; 2], a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sums the absolute areas of triangles in the XY plane.
+    fn area(polygon: &[Vec3], triangles: &[[usize; 3]]) -> f32 {
+        let xy = |i: usize| [polygon[i][0], polygon[i][1]];
+        triangles
+            .iter()
+            .map(|&[a, b, c]| cross(xy(a), xy(b), xy(c)).abs() / 2.0)
+            .sum()
+    }
+
+    /// An L-shape of area 3, optionally wound clockwise.
+    fn l_shape(clockwise: bool) -> Vec<Vec3> {
+        let mut polygon = vec![
+            [0.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [2.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [1.0, 2.0, 0.0],
+            [0.0, 2.0, 0.0],
+        ];
+        if clockwise {
+            polygon.reverse();
+        }
+        polygon
+    }
+
+    #[test]
+    fn test_concave_polygon_stays_inside() {
+        for clockwise in [false, true] {
+            let polygon = l_shape(clockwise);
+            let triangles = triangulate(&polygon);
+            assert_eq!(triangles.len(), 4);
+            // Triangles outside the outline would add area or overlap
+            assert!((area(&polygon, &triangles) - 3.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_triangles_keep_polygon_winding() {
+        let polygon = l_shape(true);
+        let xy = |i: usize| [polygon[i][0], polygon[i][1]];
+        for [a, b, c] in triangulate(&polygon) {
+            assert!(cross(xy(a), xy(b), xy(c)) < 0.0);
+        }
+    }
+
+    #[test]
+    fn test_tilted_concave_polygon() {
+        // The L-shape standing in the XZ plane
+        let polygon: Vec<Vec3> = l_shape(false)
+            .into_iter()
+            .map(|[x, y, _]| [x, 0.0, y])
+            .collect();
+        let flat = l_shape(false);
+        let triangles = triangulate(&polygon);
+        assert_eq!(triangles.len(), 4);
+        assert!((area(&flat, &triangles) - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_large_comb_is_clipped() {
+        // A comb with 2,000 teeth has about 2,000 reflex corners, which took
+        // minutes when every candidate ear was tested against every corner
+        let teeth = 2000;
+        let mut polygon = vec![[0.0, -1.0, 0.0], [teeth as f32, -1.0, 0.0]];
+        for i in (0..teeth).rev() {
+            let x = i as f32;
+            polygon.extend([[x + 1.0, 0.0, 0.0], [x + 0.5, 1.0, 0.0]]);
+        }
+        polygon.push([0.0, 0.0, 0.0]);
+
+        let triangles = triangulate(&polygon);
+        assert_eq!(triangles.len(), polygon.len() - 2);
+        let expected = teeth as f32 * 0.5 + teeth as f32;
+        assert!((area(&polygon, &triangles) - expected).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_degenerate_polygon_falls_back_to_fan() {
+        let line = [[0.0; 3], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0], [3.0, 0.0, 0.0]];
+        assert_eq!(triangulate(&line), vec![[0, 1, 2], [0, 2, 3]]);
+        assert!(triangulate(&line[..2]).is_empty());
+    }
+}
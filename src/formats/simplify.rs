@@ -0,0 +1,482 @@
+//! Provides mesh simplification for very large models.
+//!
+//! Thumbnails are at most a few hundred pixels wide, so multi-million-triangle
+//! scans carry far more detail than can be displayed. This module reduces a
+//! [`ModelData`] to a primitive budget using vertex clustering: positions are
+//! snapped to a uniform grid, triangles and lines that collapse are dropped,
+//! points sharing a cell are merged, and the grid is refined until the result
+//! fits the budget as closely as possible.
+//!
+//! Surviving vertices keep their own UVs and colors, and every mesh keeps its
+//! material, so textured and colored models still render correctly after
+//! simplification.
+//!
+//! # Examples
+//! ```
+//! use glimpse::budget::Budget;
+//! use glimpse::formats::simplify::simplify;
+//! use glimpse::formats::{ModelData, Triangle};
+//!
+//! let tri = Triangle {
+//!     verts: [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+//!     uvs: [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]],
+//!     color: [1.0, 1.0, 1.0],
+//!     texture: None,
+//! };
+//! let mut model = ModelData::from_triangles(vec![tri]);
+//! simplify(&mut model, 100, &Budget::new()).unwrap();
+//! assert_eq!(model.triangle_count(), 1);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use super::{Mesh, ModelData, Vec3};
+use crate::budget::{Budget, Interrupted};
+
+/// Finest grid resolution (cells along the longest axis) tried during clustering.
+const MAX_GRID_RESOLUTION: u32 = 4096;
+
+/// Reduces a model to at most `max_primitives` triangles, line segments and
+/// points in total.
+///
+/// Models already within budget are left unchanged. Otherwise the finest
+/// clustering grid whose output fits the budget is used. A single-cell grid
+/// can still leave up to 56 triangles between the corners of the bounding
+/// box; if that exceeds the budget, the triangles with the smallest area
+/// are dropped, then surplus points and lines.
+///
+/// # Errors
+/// Returns an error if `budget` runs out between clustering passes. The model
+/// is left unchanged in that case.
+///
+/// # Examples
+/// ```
+/// use glimpse::budget::Budget;
+/// use glimpse::formats::simplify::simplify;
+/// use glimpse::formats::{ModelData, Triangle};
+///
+/// let triangles: Vec<Triangle> = (0..100)
+///     .map(|i| {
+///         let x = i as f32 * 0.01;
+///         Triangle {
+///             verts: [[x, 0.0, 0.0], [x + 0.01, 0.0, 0.0], [x, 1.0, 0.0]],
+///             uvs: [[0.0, 0.0]; 3],
+///             color: [1.0, 1.0, 1.0],
+///             texture: None,
+///         }
+///     })
+///     .collect();
+/// let mut model = ModelData::from_triangles(triangles);
+/// simplify(&mut model, 10, &Budget::new()).unwrap();
+/// assert!(model.triangle_count() <= 10);
+/// ```
+pub fn simplify(
+    model: &mut ModelData,
+    max_primitives: usize,
+    budget: &Budget,
+) -> Result<(), Interrupted> {
+    if model.primitive_count() <= max_primitives {
+        return Ok(());
+    }
+
+    let (min, max) = bounds(&model.meshes);
+    let extent = [max[0] - min[0], max[1] - min[1], max[2] - min[2]];
+    let longest = extent[0].max(extent[1]).max(extent[2]);
+    if !longest.is_finite() || longest <= 0.0 {
+        return Ok(());
+    }
+
+    // Binary search for the finest grid that still fits the budget.
+    let mut lo = 1;
+    let mut hi = MAX_GRID_RESOLUTION;
+    budget.check()?;
+    let mut best = cluster(&model.meshes, min, longest, lo);
+    while lo < hi {
+        budget.check()?;
+        let mid = lo + (hi - lo).div_ceil(2);
+        let clustering = cluster(&model.meshes, min, longest, mid);
+        if clustering.primitive_count() <= max_primitives {
+            lo = mid;
+            best = clustering;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    drop_smallest(&mut best, &model.meshes, max_primitives);
+
+    // Fully collapsed meshes stay as empty placeholders so node mesh ranges remain valid
+    let meshes = std::mem::take(&mut model.meshes);
+    model.meshes = meshes
+        .into_iter()
+        .enumerate()
+        .map(|(m, mesh)| rebuild_mesh(mesh, m, &best))
+        .collect();
+    Ok(())
+}
+
+/// Result of clustering all meshes on one grid.
+struct Clustering {
+    /// Kept triangle indices, per mesh.
+    kept: Vec<Vec<usize>>,
+    /// Kept line segment indices, per mesh.
+    kept_lines: Vec<Vec<usize>>,
+    /// Kept indices into each mesh's point list, per mesh.
+    kept_points: Vec<Vec<usize>>,
+    /// Grid cell of every vertex, per mesh.
+    cell_keys: Vec<Vec<[u32; 3]>>,
+    /// Sum of positions and vertex count of every occupied cell.
+    cells: HashMap<[u32; 3], ([f64; 3], u32)>,
+}
+
+impl Clustering {
+    fn triangle_count(&self) -> usize {
+        self.kept.iter().map(Vec::len).sum()
+    }
+
+    fn primitive_count(&self) -> usize {
+        let lines: usize = self.kept_lines.iter().map(Vec::len).sum();
+        let points: usize = self.kept_points.iter().map(Vec::len).sum();
+        self.triangle_count() + lines + points
+    }
+}
+
+/// Drops the kept triangles with the smallest clustered area until at most
+/// `max_primitives` primitives remain, preserving the order of the survivors.
+///
+/// Lines and points are kept ahead of triangles, since at most a few of them
+/// survive a single-cell grid; if even those exceed the budget, the last
+/// points and then the last lines are dropped.
+fn drop_smallest(clustering: &mut Clustering, meshes: &[Mesh], max_primitives: usize) {
+    if clustering.primitive_count() <= max_primitives {
+        return;
+    }
+
+    let mut spare = max_primitives;
+    for kept in clustering
+        .kept_lines
+        .iter_mut()
+        .chain(&mut clustering.kept_points)
+    {
+        let keep = kept.len().min(spare);
+        spare -= keep;
+        kept.truncate(keep);
+    }
+    let max_triangles = spare;
+
+    let center = |key: &[u32; 3]| {
+        let (sum, count) = clustering.cells[key];
+        let n = count as f64;
+        [sum[0] / n, sum[1] / n, sum[2] / n]
+    };
+    let mut ranked = Vec::with_capacity(clustering.triangle_count());
+    for (m, (mesh, kept)) in meshes.iter().zip(&clustering.kept).enumerate() {
+        let keys = &clustering.cell_keys[m];
+        for (k, &t) in kept.iter().enumerate() {
+            let [a, b, c] = mesh.triangle_indices(t).map(|i| center(&keys[i]));
+            let e1 = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let e2 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let cross = [
+                e1[1] * e2[2] - e1[2] * e2[1],
+                e1[2] * e2[0] - e1[0] * e2[2],
+                e1[0] * e2[1] - e1[1] * e2[0],
+            ];
+            let area = cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2];
+            ranked.push((area, m, k));
+        }
+    }
+    // Largest first; ties keep input order so the result is deterministic
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranked.truncate(max_triangles);
+    ranked.sort_by_key(|&(_, m, k)| (m, k));
+
+    let mut kept = vec![Vec::new(); clustering.kept.len()];
+    for (_, m, k) in ranked {
+        kept[m].push(clustering.kept[m][k]);
+    }
+    clustering.kept = kept;
+}
+
+/// Clusters vertices on a grid with `resolution` cells along the longest axis.
+///
+/// Keeps every triangle that stays non-degenerate and is not a duplicate of an
+/// earlier one with the same winding in the same mesh, in input order. Lines
+/// are kept the same way, ignoring direction, and one point per cell and mesh.
+fn cluster(meshes: &[Mesh], min: Vec3, longest: f32, resolution: u32) -> Clustering {
+    let cell_size = longest / resolution as f32;
+    let cell_of = |v: Vec3| -> [u32; 3] {
+        [
+            (((v[0] - min[0]) / cell_size) as u32).min(resolution),
+            (((v[1] - min[1]) / cell_size) as u32).min(resolution),
+            (((v[2] - min[2]) / cell_size) as u32).min(resolution),
+        ]
+    };
+
+    // Accumulate the average position of every occupied cell.
+    let cell_keys: Vec<Vec<[u32; 3]>> = meshes
+        .iter()
+        .map(|mesh| mesh.positions.iter().map(|&v| cell_of(v)).collect())
+        .collect();
+    let mut cells: HashMap<[u32; 3], ([f64; 3], u32)> = HashMap::new();
+    for (mesh, keys) in meshes.iter().zip(&cell_keys) {
+        for (v, key) in mesh.positions.iter().zip(keys) {
+            let entry = cells.entry(*key).or_insert(([0.0; 3], 0));
+            entry.0[0] += v[0] as f64;
+            entry.0[1] += v[1] as f64;
+            entry.0[2] += v[2] as f64;
+            entry.1 += 1;
+        }
+    }
+
+    let mut kept = Vec::with_capacity(meshes.len());
+    let mut kept_lines = Vec::with_capacity(meshes.len());
+    let mut kept_points = Vec::with_capacity(meshes.len());
+    for (mesh, keys) in meshes.iter().zip(&cell_keys) {
+        // Overlapping meshes such as decals keep their own copy of a triangle
+        let mut seen = HashSet::new();
+        let mut mesh_kept = Vec::new();
+        for t in 0..mesh.triangle_count() {
+            let tri_keys = mesh.triangle_indices(t).map(|i| keys[i]);
+            if tri_keys[0] == tri_keys[1]
+                || tri_keys[1] == tri_keys[2]
+                || tri_keys[0] == tri_keys[2]
+            {
+                continue;
+            }
+
+            // Rotate rather than sort, so opposite windings of a thin wall stay distinct
+            let first = (0..3).min_by_key(|&i| tri_keys[i]).unwrap_or(0);
+            let mut canonical = tri_keys;
+            canonical.rotate_left(first);
+            if seen.insert(canonical) {
+                mesh_kept.push(t);
+            }
+        }
+        kept.push(mesh_kept);
+
+        let mut seen = HashSet::new();
+        let lines = mesh.lines.chunks_exact(2).enumerate();
+        kept_lines.push(
+            lines
+                .filter(|(_, line)| {
+                    let mut ends = [keys[line[0] as usize], keys[line[1] as usize]];
+                    ends.sort_unstable();
+                    ends[0] != ends[1] && seen.insert(ends)
+                })
+                .map(|(l, _)| l)
+                .collect(),
+        );
+        let mut seen = HashSet::new();
+        kept_points.push(
+            (0..mesh.points.len())
+                .filter(|&p| seen.insert(keys[mesh.points[p] as usize]))
+                .collect(),
+        );
+    }
+
+    Clustering {
+        kept,
+        kept_lines,
+        kept_points,
+        cell_keys,
+        cells,
+    }
+}
+
+/// Rebuilds mesh `m` from its kept triangles, lines and points, moving
+/// vertices to their cluster representatives and dropping vertices no longer
+/// referenced. UVs, normals and vertex colors are preserved.
+fn rebuild_mesh(mesh: Mesh, m: usize, clustering: &Clustering) -> Mesh {
+    let keys = &clustering.cell_keys[m];
+    let cells = &clustering.cells;
+    let mut remap = vec![u32::MAX; mesh.positions.len()];
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = mesh.normals.as_ref().map(|_| Vec::new());
+    let mut colors = mesh.colors.as_ref().map(|_| Vec::new());
+    let mut keep = |i: usize| {
+        if remap[i] == u32::MAX {
+            remap[i] = positions.len() as u32;
+            let (sum, count) = cells[&keys[i]];
+            let n = count as f64;
+            positions.push([
+                (sum[0] / n) as f32,
+                (sum[1] / n) as f32,
+                (sum[2] / n) as f32,
+            ]);
+            uvs.push(mesh.uvs[i]);
+            if let (Some(out), Some(src)) = (normals.as_mut(), mesh.normals.as_ref()) {
+                out.push(src[i]);
+            }
+            if let (Some(out), Some(src)) = (colors.as_mut(), mesh.colors.as_ref()) {
+                out.push(src[i]);
+            }
+        }
+        remap[i]
+    };
+
+    let indices: Vec<u32> = clustering.kept[m]
+        .iter()
+        .flat_map(|&t| mesh.triangle_indices(t))
+        .map(&mut keep)
+        .collect();
+    let lines = clustering.kept_lines[m]
+        .iter()
+        .flat_map(|&l| [mesh.lines[2 * l], mesh.lines[2 * l + 1]])
+        .map(|i| keep(i as usize))
+        .collect();
+    let points = clustering.kept_points[m]
+        .iter()
+        .map(|&p| keep(mesh.points[p] as usize))
+        .collect();
+
+    Mesh {
+        positions,
+        uvs,
+        normals,
+        colors,
+        indices,
+        lines,
+        points,
+        material: mesh.material,
+    }
+}
+
+/// Computes the axis-aligned bounds of all mesh vertices.
+fn bounds(meshes: &[Mesh]) -> (Vec3, Vec3) {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for v in meshes.iter().flat_map(|m| &m.positions) {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(v[axis]);
+            max[axis] = max[axis].max(v[axis]);
+        }
+    }
+    (min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::Triangle;
+
+    /// Builds a finely tessellated unit square grid in the XY plane.
+    fn grid_model(n: usize) -> ModelData {
+        let mut triangles = Vec::new();
+        let step = 1.0 / n as f32;
+        for y in 0..n {
+            for x in 0..n {
+                let (x0, y0) = (x as f32 * step, y as f32 * step);
+                let (x1, y1) = (x0 + step, y0 + step);
+                let uv = |px: f32, py: f32| [px, py];
+                triangles.push(Triangle {
+                    verts: [[x0, y0, 0.0], [x1, y0, 0.0], [x1, y1, 0.0]],
+                    uvs: [uv(x0, y0), uv(x1, y0), uv(x1, y1)],
+                    color: [0.2, 0.4, 0.6],
+                    texture: None,
+                });
+                triangles.push(Triangle {
+                    verts: [[x0, y0, 0.0], [x1, y1, 0.0], [x0, y1, 0.0]],
+                    uvs: [uv(x0, y0), uv(x1, y1), uv(x0, y1)],
+                    color: [0.2, 0.4, 0.6],
+                    texture: None,
+                });
+            }
+        }
+        ModelData::from_triangles(triangles)
+    }
+
+    /// Simplifies `model` with an unlimited budget.
+    fn simplified(mut model: ModelData, max_triangles: usize) -> ModelData {
+        simplify(&mut model, max_triangles, &Budget::new()).unwrap();
+        model
+    }
+
+    #[test]
+    fn test_within_budget_is_unchanged() {
+        let model = simplified(grid_model(4), 1000);
+        assert_eq!(model.triangle_count(), 32);
+    }
+
+    #[test]
+    fn test_reduces_to_budget() {
+        let model = simplified(grid_model(64), 500);
+        assert!(model.triangle_count() <= 500);
+        assert!(
+            model.triangle_count() > 100,
+            "should not over-simplify, got {}",
+            model.triangle_count()
+        );
+    }
+
+    #[test]
+    fn test_preserves_uvs_and_colors() {
+        let model = simplified(grid_model(32), 200);
+        for tri in model.triangles() {
+            assert_eq!(tri.color, [0.2, 0.4, 0.6]);
+            for uv in &tri.uvs {
+                assert!((0.0..=1.0).contains(&uv[0]) && (0.0..=1.0).contains(&uv[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_budget_below_single_cell_drops_smallest_triangles() {
+        let model = simplified(grid_model(8), 1);
+        assert_eq!(model.triangle_count(), 1);
+    }
+
+    #[test]
+    fn test_keeps_overlapping_meshes_and_opposite_windings() {
+        let mut model = grid_model(16);
+        // A decal on the base mesh, plus a back side facing the other way
+        let mut decal = model.meshes[0].clone();
+        decal.material = 1;
+        let mut back = model.meshes[0].clone();
+        for tri in back.indices.chunks_mut(3) {
+            tri.swap(1, 2);
+        }
+        model.meshes.extend([decal, back]);
+
+        let model = simplified(model, 300);
+        let counts: Vec<usize> = model.meshes.iter().map(Mesh::triangle_count).collect();
+        assert!(counts[0] > 0);
+        assert_eq!(counts, [counts[0]; 3]);
+    }
+
+    #[test]
+    fn test_reduces_point_clouds_and_lines() {
+        let n = 100;
+        let positions: Vec<Vec3> = (0..n * n)
+            .map(|i| [(i % n) as f32 / n as f32, (i / n) as f32 / n as f32, 0.0])
+            .collect();
+        let count = positions.len() as u32;
+        let model = ModelData {
+            meshes: vec![Mesh {
+                uvs: vec![[0.0; 2]; positions.len()],
+                positions,
+                lines: (0..count - 1).flat_map(|i| [i, i + 1]).collect(),
+                points: (0..count).collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(model.primitive_count(), 19_999);
+
+        let model = simplified(model, 500);
+        let mesh = &model.meshes[0];
+        assert!(model.primitive_count() <= 500);
+        assert!(!mesh.points.is_empty() && !mesh.lines.is_empty());
+        assert!(mesh.positions.len() < 500);
+    }
+
+    #[test]
+    fn test_expired_budget_leaves_model_unchanged() {
+        let mut model = grid_model(16);
+        let budget = Budget::new().with_timeout(std::time::Duration::ZERO);
+        assert_eq!(
+            simplify(&mut model, 10, &budget),
+            Err(Interrupted::TimedOut)
+        );
+        assert_eq!(model.triangle_count(), 512);
+    }
+}
@@ -1,8 +1,10 @@
 //! Provides a Vintage Story JSON model format loader.
 //!
 //! Vintage Story uses a JSON5-based model format similar to Minecraft/Blockbench
-//! but with some differences. When loaded from a file path within a VS asset
-//! tree, textures are resolved from the `assets/*/textures/` directories.
+//! but with some differences. When the model lies within a VS asset tree,
+//! textures are resolved from the `assets/*/textures/` directories through
+//! the [`ResourceResolver`](super::resolver::ResourceResolver), so they can
+//! come from disk, a mod archive or memory.
 //!
 //! # Examples
 //! ```
@@ -13,18 +15,22 @@
 //! ```
 
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::Arc;
 
 use serde::Deserialize;
 
+use super::resolver::{join, DirectoryResolver, ResourceResolver};
 use super::shared::cube::{
-    apply_uv_rotation, compute_cube_vertices, quad_to_triangles, scale_vec3, BLOCK_SCALE,
-    DEFAULT_UVS,
+    apply_uv_rotation, compute_cube_vertices, scale_vec3, BLOCK_SCALE, DEFAULT_UVS,
 };
-use super::shared::rotation::{rotate_vertices, RotationTransform};
-use super::shared::texture::load_texture_from_file;
-use super::{FormatLoader, LoadError, LoadResult, ModelData, TextureData, Triangle, Vec3};
+use super::shared::mesh::ModelBuilder;
+use super::shared::rotation::{pivot_node_transform, rotate_vertices, RotationTransform};
+use super::shared::texture::decode_texture;
+use super::{
+    AlphaMode, FormatLoader, LoadError, LoadOptions, LoadResult, Mat4, Material, TextureData, Vec3,
+};
+use crate::budget::Budget;
 
 /// The Vintage Story format loader.
 ///
@@ -103,42 +109,56 @@ impl FormatLoader for VintageStoryLoader {
     }
 
     fn load_from_bytes(&self, data: &[u8]) -> LoadResult {
-        let text = std::str::from_utf8(data)
-            .map_err(|_| LoadError::InvalidData("Invalid UTF-8 in VS model file".to_string()))?;
-
-        // Use json5 crate to handle comments in VS files
-        let model: VsModelFile = json5::from_str(text)
-            .map_err(|e| LoadError::InvalidData(format!("Failed to parse VS JSON: {}", e)))?;
+        self.load_from_bytes_with_options(data, &LoadOptions::default())
+    }
 
-        let ctx = VsTextureContext::empty(
-            model.texture_width.unwrap_or(16) as f32,
-            model.texture_height.unwrap_or(16) as f32,
-            &model.texture_sizes,
-        );
+    fn load_from_path(&self, path: &Path) -> LoadResult {
+        self.load_from_path_with_options(path, &LoadOptions::default())
+    }
 
-        convert_vs_model_to_triangles(model, &ctx)
+    fn load_from_bytes_with_options(&self, data: &[u8], options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
+        load_vs_model(data, options.resolver.as_deref(), options)
     }
 
-    fn load_from_path(&self, path: &Path) -> LoadResult {
+    fn load_from_path_with_options(&self, path: &Path, options: &LoadOptions) -> LoadResult {
+        options.budget.check()?;
         let data = std::fs::read(path)?;
-        let text = std::str::from_utf8(&data)
-            .map_err(|_| LoadError::InvalidData("Invalid UTF-8 in VS model file".to_string()))?;
-
-        let model: VsModelFile = json5::from_str(text)
-            .map_err(|e| LoadError::InvalidData(format!("Failed to parse VS JSON: {}", e)))?;
+        let directory = DirectoryResolver::for_model(path);
+        let resolver = options.resolver.as_deref().unwrap_or(&directory);
+        load_vs_model(&data, Some(resolver), options)
+    }
+}
 
-        // Try to resolve textures from the VS asset tree
-        let loaded_textures = resolve_vs_textures(path, &model.textures);
+/// Parses a VS model, resolving its textures from the asset tree around it
+/// when a resolver is given.
+fn load_vs_model(
+    data: &[u8],
+    resolver: Option<&dyn ResourceResolver>,
+    options: &LoadOptions,
+) -> LoadResult {
+    let text = std::str::from_utf8(data)
+        .map_err(|_| LoadError::InvalidData("Invalid UTF-8 in VS model file".to_string()))?;
+
+    // Use json5 crate to handle comments in VS files
+    let model: VsModelFile = json5::from_str(text)
+        .map_err(|e| LoadError::InvalidData(format!("Failed to parse VS JSON: {}", e)))?;
+
+    // Try to resolve textures from the VS asset tree
+    let loaded_textures = match resolver {
+        Some(resolver) => resolve_vs_textures(resolver, &model.textures, &options.budget)?,
+        None => HashMap::new(),
+    };
+    options.mark_parsed();
 
-        let ctx = VsTextureContext::new(
-            model.texture_width.unwrap_or(16) as f32,
-            model.texture_height.unwrap_or(16) as f32,
-            &model.texture_sizes,
-            loaded_textures,
-        );
+    let ctx = VsTextureContext::new(
+        model.texture_width.unwrap_or(16) as f32,
+        model.texture_height.unwrap_or(16) as f32,
+        &model.texture_sizes,
+        loaded_textures,
+    );
 
-        convert_vs_model_to_triangles(model, &ctx)
-    }
+    convert_vs_model_to_meshes(model, &ctx)
 }
 
 // ---- Vintage Story JSON structure ----
@@ -177,6 +197,8 @@ struct VsElement {
     rotation_y: Option<f32>,
     #[serde(default, rename = "rotationZ")]
     rotation_z: Option<f32>,
+    #[serde(default, rename = "renderPass")]
+    render_pass: Option<i32>,
     #[serde(default)]
     children: Vec<VsElement>,
 }
@@ -202,6 +224,8 @@ struct VsFace {
     rotation: Option<f32>,
     #[serde(default)]
     enabled: Option<bool>,
+    #[serde(default)]
+    glow: Option<u8>,
 }
 
 // ---- Texture context ----
@@ -217,22 +241,6 @@ struct VsTextureContext {
 }
 
 impl VsTextureContext {
-    fn empty(
-        default_width: f32,
-        default_height: f32,
-        texture_sizes: &HashMap<String, [u32; 2]>,
-    ) -> Self {
-        Self {
-            default_width,
-            default_height,
-            texture_sizes: texture_sizes
-                .iter()
-                .map(|(k, v)| (k.clone(), [v[0] as f32, v[1] as f32]))
-                .collect(),
-            loaded_textures: HashMap::new(),
-        }
-    }
-
     fn new(
         default_width: f32,
         default_height: f32,
@@ -274,62 +282,56 @@ impl VsTextureContext {
 
 // ---- Texture resolution ----
 
-/// Walks up from the model file to find the `assets/` root directory.
-fn find_assets_root(model_path: &Path) -> Option<PathBuf> {
-    let mut current = model_path.parent()?;
-    loop {
-        if current
-            .file_name()
-            .map(|n| n.eq_ignore_ascii_case("assets"))
-            .unwrap_or(false)
-        {
-            return Some(current.to_path_buf());
-        }
-        current = current.parent()?;
-    }
-}
-
-/// Determines the domain (e.g. "survival") from the model file path.
-/// The domain is the path component immediately after `assets/`.
-fn detect_domain(model_path: &Path, assets_root: &Path) -> Option<String> {
-    let relative = model_path.strip_prefix(assets_root).ok()?;
-    let first_component = relative.components().next()?;
-    Some(first_component.as_os_str().to_string_lossy().to_string())
+/// Finds the `assets/` directory enclosing the model.
+///
+/// Returns the path of the assets directory relative to the model's
+/// directory, and the domain (e.g. "survival"), which is the path component
+/// immediately after `assets/`.
+fn find_assets_root(resolver: &dyn ResourceResolver) -> Option<(String, Option<String>)> {
+    let model_dir = resolver.model_dir()?;
+    let segments: Vec<&str> = model_dir.split('/').collect();
+    let assets = segments
+        .iter()
+        .rposition(|segment| segment.eq_ignore_ascii_case("assets"))?;
+    let depth = segments.len() - assets - 1;
+    let root = vec![".."; depth].join("/");
+    let domain = segments.get(assets + 1).map(|domain| domain.to_string());
+    Some((root, domain))
 }
 
-/// Resolves VS texture references to actual PNG files on disk.
+/// Resolves VS texture references to PNG files in the asset tree.
 ///
 /// Strategy:
-/// 1. Find the `assets/` root by walking up from the model file
+/// 1. Find the `assets/` root by walking up from the model's directory
 /// 2. For each texture mapping (name → path like "entity/humanoid/tobias"):
 ///    a. Try `assets/<same_domain>/textures/<path>.png` first
 ///    b. Then try `assets/*/textures/<path>.png` for other domains
 fn resolve_vs_textures(
-    model_path: &Path,
+    resolver: &dyn ResourceResolver,
     textures: &HashMap<String, String>,
-) -> HashMap<String, Arc<TextureData>> {
+    budget: &Budget,
+) -> Result<HashMap<String, Arc<TextureData>>, LoadError> {
     let mut loaded = HashMap::new();
 
-    let assets_root = match find_assets_root(model_path) {
-        Some(root) => root,
-        None => return loaded,
+    let Some((assets_root, model_domain)) = find_assets_root(resolver) else {
+        return Ok(loaded);
     };
 
-    let model_domain = detect_domain(model_path, &assets_root);
-
     // List available domains under assets/
-    let domains: Vec<String> = std::fs::read_dir(&assets_root)
-        .ok()
-        .map(|entries| {
-            entries
-                .filter_map(|e| e.ok())
-                .filter(|e| e.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
-                .map(|e| e.file_name().to_string_lossy().to_string())
-                .collect()
-        })
-        .unwrap_or_default();
+    let mut domains = resolver.list(&assets_root).unwrap_or_default();
+    domains.sort();
+
+    let load = |domain: &str, tex_rel: &str| {
+        let candidate = join(&assets_root, &format!("{domain}/textures/{tex_rel}"));
+        if !resolver.exists(&candidate) {
+            return None;
+        }
+        decode_texture(&resolver.read(&candidate).ok()?, Some("png"))
+    };
 
     for (tex_name, tex_path) in textures {
+        budget.check()?;
+
         // Strip domain prefix if present (e.g. "game:block/leather" → "block/leather")
         let (override_domain, clean_path) = if let Some(pos) = tex_path.find(':') {
             (Some(tex_path[..pos].to_string()), &tex_path[pos + 1..])
@@ -337,55 +339,31 @@ fn resolve_vs_textures(
             (None, tex_path.as_str())
         };
 
-        // Normalize path separators
-        let tex_rel = clean_path.replace('/', std::path::MAIN_SEPARATOR_STR);
+        let tex_rel = Path::new(clean_path)
+            .with_extension("png")
+            .to_string_lossy()
+            .into_owned();
 
         let mut found = None;
 
         // If there's a domain override (e.g. "game:"), try that first
         if let Some(ref domain) = override_domain {
-            let candidate = assets_root
-                .join(domain)
-                .join("textures")
-                .join(&tex_rel)
-                .with_extension("png");
-            if candidate.exists() {
-                found = load_texture_from_file(&candidate);
-            }
+            found = load(domain, &tex_rel);
         }
 
         // Try same domain as the model file
         if found.is_none() {
             if let Some(ref domain) = model_domain {
-                let candidate = assets_root
-                    .join(domain)
-                    .join("textures")
-                    .join(&tex_rel)
-                    .with_extension("png");
-                if candidate.exists() {
-                    found = load_texture_from_file(&candidate);
-                }
+                found = load(domain, &tex_rel);
             }
         }
 
         // Try all other domains
         if found.is_none() {
-            for domain in &domains {
-                if model_domain.as_deref() == Some(domain.as_str()) {
-                    continue; // Already tried
-                }
-                let candidate = assets_root
-                    .join(domain)
-                    .join("textures")
-                    .join(&tex_rel)
-                    .with_extension("png");
-                if candidate.exists() {
-                    found = load_texture_from_file(&candidate);
-                    if found.is_some() {
-                        break;
-                    }
-                }
-            }
+            found = domains
+                .iter()
+                .filter(|domain| model_domain.as_deref() != Some(domain.as_str()))
+                .find_map(|domain| load(domain, &tex_rel));
         }
 
         if let Some(texture) = found {
@@ -393,26 +371,53 @@ fn resolve_vs_textures(
         }
     }
 
-    loaded
+    Ok(loaded)
 }
 
 // ---- Model conversion ----
 
-/// Converts a Vintage Story model to triangles.
-fn convert_vs_model_to_triangles(model: VsModelFile, ctx: &VsTextureContext) -> LoadResult {
-    let mut triangles = Vec::new();
+/// Converts a Vintage Story model to meshes.
+fn convert_vs_model_to_meshes(model: VsModelFile, ctx: &VsTextureContext) -> LoadResult {
+    let mut builder = ModelBuilder::new();
 
     // Convert each element (cube) to triangles
     // Root elements use zero offset; children accumulate parent positions.
     for element in &model.elements {
-        convert_vs_element_recursive(element, &mut triangles, &[], [0.0; 3], ctx);
+        convert_vs_element_recursive(element, &mut builder, &[], [0.0; 3], None, ctx);
     }
 
-    if triangles.is_empty() {
+    if builder.is_empty() {
         return Err(LoadError::NoGeometry);
     }
 
-    Ok(ModelData { triangles })
+    Ok(builder.build())
+}
+
+/// Builds the material for a face of `element`.
+///
+/// Render passes 2 (BlendNoCull) and 3 (Transparent) are alpha-blended, and
+/// glowing faces emit their own texture scaled by the glow level.
+fn vs_face_material(element: &VsElement, face: &VsFace, ctx: &VsTextureContext) -> Material {
+    let texture = face
+        .texture
+        .as_deref()
+        .and_then(|tex_ref| ctx.get_texture(tex_ref));
+    let glow = f32::from(face.glow.unwrap_or(0)) / 255.0;
+    let alpha_mode = match element.render_pass {
+        Some(2 | 3) => AlphaMode::Blend,
+        _ => AlphaMode::Mask { cutoff: 0.5 },
+    };
+
+    Material {
+        name: face.texture.clone(),
+        // Solid gray color for untextured rendering
+        color: [0.75, 0.75, 0.78],
+        emissive: [glow; 3],
+        emissive_texture: if glow > 0.0 { texture.clone() } else { None },
+        texture,
+        alpha_mode,
+        ..Default::default()
+    }
 }
 
 /// Returns the rotation angles from a VS element.
@@ -424,16 +429,36 @@ fn vs_element_rotation(element: &VsElement) -> Vec3 {
     ]
 }
 
-/// Recursively converts VS elements to triangles.
+/// Returns the scene node transform of an element relative to its parent.
+///
+/// Children are positioned relative to their parent's `from` corner, so the
+/// node frame is that corner, turned by the element's own rotation.
+fn vs_node_transform(element: &VsElement) -> Mat4 {
+    let origin = element
+        .rotation_origin
+        .unwrap_or_else(|| [0, 1, 2].map(|axis| (element.from[axis] + element.to[axis]) / 2.0));
+    let rotation = RotationTransform::new_if_non_zero(origin, vs_element_rotation(element));
+    let pivot = glam::Mat4::from_cols_array_2d(&pivot_node_transform(rotation, BLOCK_SCALE));
+    let corner = glam::Vec3::from_array(scale_vec3(element.from, BLOCK_SCALE));
+    (pivot * glam::Mat4::from_translation(corner)).to_cols_array_2d()
+}
+
+/// Recursively converts VS elements to meshes, adding a scene node per element.
 fn convert_vs_element_recursive(
     element: &VsElement,
-    triangles: &mut Vec<Triangle>,
+    builder: &mut ModelBuilder,
     parent_rotations: &[RotationTransform],
     parent_offset: Vec3,
+    parent_node: Option<usize>,
     ctx: &VsTextureContext,
 ) {
-    let cubes = convert_vs_cube_to_triangles(element, parent_rotations, parent_offset, ctx);
-    triangles.extend(cubes);
+    let node = builder.add_node(
+        element.name.clone(),
+        vs_node_transform(element),
+        parent_node,
+    );
+    builder.set_node(Some(node));
+    convert_vs_cube(element, builder, parent_rotations, parent_offset, ctx);
 
     let elem_angles = vs_element_rotation(element);
     let raw_origin = element.rotation_origin.unwrap_or([0.0; 3]);
@@ -463,19 +488,25 @@ fn convert_vs_element_recursive(
     ];
 
     for child in &element.children {
-        convert_vs_element_recursive(child, triangles, rotations_for_children, child_offset, ctx);
+        convert_vs_element_recursive(
+            child,
+            builder,
+            rotations_for_children,
+            child_offset,
+            Some(node),
+            ctx,
+        );
     }
 }
 
 /// Converts a VS cube element to 12 triangles (2 per face).
-fn convert_vs_cube_to_triangles(
+fn convert_vs_cube(
     element: &VsElement,
+    builder: &mut ModelBuilder,
     parent_rotations: &[RotationTransform],
     parent_offset: Vec3,
     ctx: &VsTextureContext,
-) -> Vec<Triangle> {
-    let mut triangles = Vec::with_capacity(12);
-
+) {
     let scale = BLOCK_SCALE;
 
     // Child coordinates are relative to parent; add the accumulated offset
@@ -540,9 +571,6 @@ fn convert_vs_cube_to_triangles(
         vertices = rotate_vertices(&vertices, &scaled);
     }
 
-    // Solid gray color for untextured rendering
-    let default_color = [0.75, 0.75, 0.78];
-
     // Face definitions: (vertex indices for quad, face data)
     // Note: Vintage Story uses different winding order than standard CUBE_FACES
     let face_defs: [([usize; 4], &Option<VsFace>); 6] = [
@@ -571,11 +599,8 @@ fn convert_vs_cube_to_triangles(
             continue;
         }
 
-        // Resolve texture for this face
-        let face_texture = face
-            .texture
-            .as_deref()
-            .and_then(|tex_ref| ctx.get_texture(tex_ref));
+        // Resolve texture and material for this face
+        let material = builder.material(vs_face_material(element, face, ctx));
 
         // Get the texture dimensions for UV normalization
         let (tex_width, tex_height) = face
@@ -598,10 +623,6 @@ fn convert_vs_cube_to_triangles(
             DEFAULT_UVS
         };
 
-        // Create two triangles for this face using shared utility
-        let tris = quad_to_triangles(&vertices, indices, uvs, default_color, face_texture);
-        triangles.extend(tris);
+        builder.push_quad(material, indices.map(|i| vertices[i]), uvs);
     }
-
-    triangles
 }
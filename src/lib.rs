@@ -29,6 +29,7 @@
 #[macro_use]
 pub mod com;
 
+pub mod budget;
 pub mod formats;
 pub mod provider;
 pub mod renderer;
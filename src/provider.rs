@@ -12,8 +12,10 @@
 //! ```
 
 use std::ffi::c_void;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use windows::core::{Error, Interface, Result, GUID, HRESULT};
 use windows::Win32::Foundation::*;
@@ -21,7 +23,9 @@ use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::System::Com::*;
 use windows::Win32::UI::Shell::*;
 
+use crate::budget::Budget;
 use crate::com::helpers::{ComWrapper, MutexExt};
+use crate::formats::resolver::{DirectoryResolver, ResourceResolver};
 use crate::renderer;
 
 // ---------------------------------------------------------------------------
@@ -33,14 +37,24 @@ const IID_ITHUMBNAILPROVIDER: GUID = GUID::from_u128(0xe357fccd_a995_4576_b01f_2
 const IID_IUNKNOWN: GUID = GUID::from_u128(0x00000000_0000_0000_c000_000000000046);
 const IID_IINITIALIZEWITHFILE: GUID = GUID::from_u128(0xb7d14566_0509_4cce_a71f_0a554233bd9b);
 
+/// Maximum time spent loading and rendering a single thumbnail.
+const RENDER_TIMEOUT: Duration = Duration::from_secs(10);
+
 // ---------------------------------------------------------------------------
 // Internal data structure
 // ---------------------------------------------------------------------------
 
 /// Source of model data - either bytes from a stream or a file path.
 enum GltfSource {
-    Bytes(Vec<u8>),
-    Path(std::path::PathBuf),
+    /// Bytes from a stream, with the item name the stream reports, if any.
+    ///
+    /// Only an absolute name lets external buffers and textures resolve; see
+    /// `stream_name`.
+    Bytes {
+        data: Vec<u8>,
+        name: Option<PathBuf>,
+    },
+    Path(PathBuf),
 }
 
 /// Internal data shared across COM interface wrappers via Arc.
@@ -106,8 +120,34 @@ impl GltfThumbnailProvider {
     /// assert!(provider.set_data(vec![1, 2, 3]).is_ok());
     /// ```
     pub fn set_data(&self, data: Vec<u8>) -> Result<()> {
+        self.set_source(GltfSource::Bytes { data, name: None })
+    }
+
+    /// Sets the model data along with the item name a stream would report.
+    ///
+    /// The name's extension picks the loader. An absolute path also lets the
+    /// model's external buffers and textures load from its directory; a bare
+    /// file name does not.
+    ///
+    /// # Errors
+    /// Returns an error if the mutex is poisoned.
+    ///
+    /// # Examples
+    /// ```
+    /// use glimpse::provider::GltfThumbnailProvider;
+    ///
+    /// let provider = GltfThumbnailProvider::new();
+    /// assert!(provider.set_data_with_name(vec![1, 2, 3], "chair.obj").is_ok());
+    /// ```
+    pub fn set_data_with_name(&self, data: Vec<u8>, name: impl Into<PathBuf>) -> Result<()> {
+        let name = Some(name.into());
+        self.set_source(GltfSource::Bytes { data, name })
+    }
+
+    /// Replaces the data the next thumbnail is rendered from.
+    fn set_source(&self, source: GltfSource) -> Result<()> {
         let mut guard = self.inner.source.lock_or_fail()?;
-        *guard = Some(GltfSource::Bytes(data));
+        *guard = Some(source);
         Ok(())
     }
 
@@ -293,11 +333,28 @@ unsafe extern "system" fn get_thumbnail_impl(
 
         let size = cx.clamp(32, 1024);
 
+        // Never let a pathological file hang the thumbnail worker: past the
+        // deadline, fall back to a coarse render rather than nothing at all.
+        let mut options = renderer::RenderOptions {
+            budget: Budget::new().with_timeout(RENDER_TIMEOUT),
+            partial_on_timeout: true,
+            ..Default::default()
+        };
+
         let pixels = match source {
-            GltfSource::Bytes(data) => renderer::render_thumbnail(data, None, size, size),
-            GltfSource::Path(path) => renderer::render_thumbnail_from_path(path, size, size),
+            GltfSource::Bytes { data, name } => {
+                let extension = name
+                    .as_deref()
+                    .and_then(|name| name.extension())
+                    .and_then(|ext| ext.to_str());
+                options.resolver = sibling_resolver(name.as_deref());
+                renderer::render_thumbnail_with_options(data, extension, size, size, &options)
+            }
+            GltfSource::Path(path) => {
+                renderer::render_thumbnail_from_path_with_options(path, size, size, &options)
+            }
         }
-        .ok_or(Error::from(E_FAIL))?;
+        .map_err(|e| Error::new(render_error_hresult(&e), e.to_string()))?;
 
         let hbmp = create_hbitmap_from_rgba(&pixels, size, size)?;
 
@@ -307,6 +364,25 @@ unsafe extern "system" fn get_thumbnail_impl(
     })
 }
 
+/// Maps a render failure to an HRESULT so callers can tell why no thumbnail was produced.
+fn render_error_hresult(error: &renderer::RenderError) -> HRESULT {
+    use crate::formats::LoadError;
+    use renderer::RenderError;
+
+    match error {
+        RenderError::UnsupportedFormat => ERROR_NOT_SUPPORTED.to_hresult(),
+        RenderError::InvalidSize => E_INVALIDARG,
+        RenderError::Load(LoadError::IoError(e)) => e
+            .raw_os_error()
+            .map(|code| HRESULT::from_win32(code as u32))
+            .unwrap_or(E_FAIL),
+        RenderError::Load(_) => ERROR_INVALID_DATA.to_hresult(),
+        RenderError::NoGeometry | RenderError::DegenerateBounds => E_FAIL,
+        RenderError::Cancelled => E_ABORT,
+        RenderError::TimedOut => ERROR_TIMEOUT.to_hresult(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // IInitializeWithStream::Initialize
 // ---------------------------------------------------------------------------
@@ -326,9 +402,12 @@ unsafe extern "system" fn initialize_stream_impl(
         let stream = std::mem::ManuallyDrop::new(IStream::from_raw(pstream));
 
         let bytes = read_stream_to_vec(&stream)?;
+        // Often just the leaf file name, which picks the loader but leaves
+        // external resources unresolved; see `stream_name`
+        let name = stream_name(&stream);
 
         let mut guard = inner.source.lock_or_fail()?;
-        *guard = Some(GltfSource::Bytes(bytes));
+        *guard = Some(GltfSource::Bytes { data: bytes, name });
 
         let _ = grfmode; // Unused but part of interface
         Ok(())
@@ -355,7 +434,7 @@ unsafe extern "system" fn initialize_file_impl(
             len += 1;
         }
         let slice = std::slice::from_raw_parts(pszfilepath, len);
-        let path = std::path::PathBuf::from(String::from_utf16_lossy(slice));
+        let path = PathBuf::from(String::from_utf16_lossy(slice));
 
         let mut guard = inner.source.lock_or_fail()?;
         *guard = Some(GltfSource::Path(path));
@@ -567,6 +646,36 @@ fn read_stream_to_vec(stream: &IStream) -> Result<Vec<u8>> {
     Ok(data)
 }
 
+/// Returns the item name a stream reports, if any.
+///
+/// Some streams report the file's full path, which gives the model's
+/// extension and the directory of its sibling files. Shell item streams
+/// commonly report only the leaf file name, so a `.gltf` with external
+/// buffers or textures renders without them. Isolated thumbnail handlers are
+/// only ever initialized with a stream, so there is no other way to learn the
+/// path; handlers registered without process isolation get it through
+/// `IInitializeWithFile` instead.
+fn stream_name(stream: &IStream) -> Option<PathBuf> {
+    let mut stat = STATSTG::default();
+    unsafe { stream.Stat(&mut stat, STATFLAG_DEFAULT) }.ok()?;
+    if stat.pwcsName.is_null() {
+        return None;
+    }
+    let name = unsafe { stat.pwcsName.to_string() };
+    unsafe { CoTaskMemFree(Some(stat.pwcsName.0 as *const c_void)) };
+    name.ok().filter(|name| !name.is_empty()).map(PathBuf::from)
+}
+
+/// Returns a resolver for the files next to a streamed item, if the stream
+/// named it with a full path.
+///
+/// Bare file names return `None`: resolving them against the working
+/// directory of the thumbnail host would read unrelated files.
+fn sibling_resolver(name: Option<&Path>) -> Option<Arc<dyn ResourceResolver>> {
+    let name = name.filter(|name| name.is_absolute())?;
+    Some(Arc::new(DirectoryResolver::for_model(name)))
+}
+
 /// Creates a top-down 32-bit HBITMAP from an RGBA pixel buffer.
 ///
 /// Windows expects BGRA byte order in DIB sections, so R and B are swizzled.
@@ -622,3 +731,16 @@ unsafe fn create_hbitmap_from_rgba(pixels: &[u8], width: u32, height: u32) -> Re
 
     Ok(hbmp)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sibling_resolver_needs_absolute_name() {
+        assert!(sibling_resolver(None).is_none());
+        // Shell item streams often report only the leaf name
+        assert!(sibling_resolver(Some(Path::new("chair.gltf"))).is_none());
+        assert!(sibling_resolver(Some(Path::new(r"C:\models\chair.gltf"))).is_some());
+    }
+}
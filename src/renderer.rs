@@ -1,8 +1,12 @@
 //! Provides a software rasterizer for 3D model thumbnails.
 //!
-//! This module handles format-agnostic rasterization: taking triangles
-//! and converting them to pixels using a simple perspective camera,
-//! flat shading, and z-buffer.
+//! This module handles format-agnostic rasterization: taking indexed meshes
+//! and converting them to pixels using a perspective or authored camera and
+//! a z-buffer. Lighting comes from the model's lights, or a default rig when
+//! it has none. Meshes with normals are shaded per corner and interpolated
+//! across each triangle, others per face, and the result modulates textures
+//! and interpolated vertex colors. Each mesh's [`Material`] controls alpha
+//! handling, back-face culling, emission and highlights.
 //!
 //! No GPU is required; it runs entirely on the CPU.
 //!
@@ -14,11 +18,329 @@
 //! assert!(pixels.is_none());
 //! ```
 
+use std::collections::HashSet;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use glam::{Mat4, Vec3, Vec4};
+use glam::{Mat4, Vec3};
 
-use crate::formats::{self, ModelData, Triangle};
+use crate::budget::{Budget, Interrupted};
+use crate::formats::{
+    self, resolver::ResourceResolver, simplify, AlphaMode, AnimationPose, Camera, LightKind,
+    LoadError, LoadOptions, LoadReport, Material, Mesh, ModelData, Projection, RenderMode,
+    SceneChoice,
+};
+
+/// Default triangle budget applied before rasterization.
+///
+/// A 256px thumbnail cannot show more detail than this, and larger meshes are
+/// simplified first to keep thumbnail generation fast and memory-friendly.
+///
+/// # Examples
+/// ```
+/// use glimpse::renderer::DEFAULT_TRIANGLE_BUDGET;
+///
+/// assert!(DEFAULT_TRIANGLE_BUDGET > 0);
+/// ```
+pub const DEFAULT_TRIANGLE_BUDGET: usize = 250_000;
+
+/// Controls optional rendering behaviour.
+///
+/// # Examples
+/// ```
+/// use glimpse::renderer::RenderOptions;
+///
+/// let options = RenderOptions {
+///     auto_view: true,
+///     ..Default::default()
+/// };
+/// assert!(options.auto_view);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    /// Picks the most informative camera azimuth with a low-resolution
+    /// pre-pass instead of always using the fixed default view.
+    pub auto_view: bool,
+    /// Simplifies models with more triangles, line segments and points than
+    /// this in total before rendering. `None` renders every primitive.
+    pub triangle_budget: Option<usize>,
+    /// Cancellation token and deadline shared by loading and rasterization.
+    pub budget: Budget,
+    /// Returns a partial, low-detail image instead of [`RenderError::TimedOut`]
+    /// when the deadline passes after the model has loaded. The fallback render
+    /// gets a short grace period and stops drawing once that runs out too.
+    pub partial_on_timeout: bool,
+    /// Animation pose to render, for formats that support animation.
+    pub animation: Option<AnimationPose>,
+    /// Scene to render from files with several scenes; see [`LoadOptions::scene`].
+    pub scene: Option<SceneChoice>,
+    /// Material variant to render; see [`LoadOptions::variant`].
+    pub variant: Option<String>,
+    /// Renders from a camera authored in the model instead of framing it
+    /// automatically. Models without a matching camera are framed as usual.
+    pub camera: Option<CameraChoice>,
+    /// Reads files the model references; see [`LoadOptions::resolver`].
+    pub resolver: Option<Arc<dyn ResourceResolver>>,
+}
+
+/// Selects which authored camera a thumbnail is rendered from.
+///
+/// # Examples
+/// ```
+/// use glimpse::renderer::{CameraChoice, RenderOptions};
+///
+/// let options = RenderOptions {
+///     camera: Some(CameraChoice::Named("Front".to_string())),
+///     ..Default::default()
+/// };
+/// assert!(options.camera.is_some());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum CameraChoice {
+    /// The model's first camera.
+    First,
+    /// The first camera with this name.
+    Named(String),
+}
+
+impl CameraChoice {
+    /// Returns the chosen camera of `model`, if it has one.
+    fn find<'a>(&self, model: &'a ModelData) -> Option<&'a Camera> {
+        match self {
+            CameraChoice::First => model.cameras.first(),
+            CameraChoice::Named(name) => model
+                .cameras
+                .iter()
+                .find(|camera| camera.name.as_deref() == Some(name.as_str())),
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            auto_view: false,
+            triangle_budget: Some(DEFAULT_TRIANGLE_BUDGET),
+            budget: Budget::default(),
+            partial_on_timeout: false,
+            animation: None,
+            scene: None,
+            variant: None,
+            camera: None,
+            resolver: None,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Returns the load options implied by these render options.
+    fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            budget: self.budget.clone(),
+            animation: self.animation.clone(),
+            scene: self.scene.clone(),
+            variant: self.variant.clone(),
+            resolver: self.resolver.clone(),
+            parse_clock: None,
+        }
+    }
+}
+
+/// Time spent in each stage of producing a thumbnail.
+///
+/// # Examples
+/// ```
+/// use glimpse::renderer::StageTimings;
+///
+/// let timings = StageTimings::default();
+/// assert_eq!(timings.total(), std::time::Duration::ZERO);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StageTimings {
+    /// Reading and parsing the file and the resources it references.
+    pub parse: Duration,
+    /// Building meshes from the parsed file, simplification, bounds
+    /// computation, and camera selection.
+    pub convert: Duration,
+    /// Projection, rasterization, and pixel conversion.
+    pub raster: Duration,
+}
+
+impl StageTimings {
+    /// Returns the sum of all stage timings.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use glimpse::renderer::StageTimings;
+    ///
+    /// let timings = StageTimings {
+    ///     parse: Duration::from_millis(2),
+    ///     convert: Duration::from_millis(3),
+    ///     raster: Duration::from_millis(5),
+    /// };
+    /// assert_eq!(timings.total(), Duration::from_millis(10));
+    /// ```
+    pub fn total(&self) -> Duration {
+        self.parse + self.convert + self.raster
+    }
+}
+
+/// Statistics gathered while loading and rendering a thumbnail.
+///
+/// Useful for tracking performance regressions and spotting badly authored
+/// assets, e.g. models with many degenerate triangles or huge textures.
+///
+/// # Examples
+/// ```
+/// use glimpse::renderer::RenderReport;
+///
+/// let report = RenderReport::default();
+/// assert_eq!(report.triangles, 0);
+/// assert!(report.to_string().contains("triangles"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RenderReport {
+    /// Name of the loader that parsed the model.
+    pub loader: &'static str,
+    /// Triangles produced by the loader.
+    pub triangles: usize,
+    /// Triangles left after simplification and sent to the rasterizer.
+    pub rendered_triangles: usize,
+    /// Triangles that fell entirely outside the viewport.
+    pub culled_triangles: usize,
+    /// Back faces of single-sided materials, skipped before rasterization.
+    pub backface_triangles: usize,
+    /// Triangles discarded because a vertex lay behind the camera.
+    pub clipped_triangles: usize,
+    /// Triangles with zero area, in world or screen space.
+    pub degenerate_triangles: usize,
+    /// Number of distinct textures referenced by the model.
+    pub textures: usize,
+    /// Memory used by those textures' pixel data, in bytes.
+    pub texture_bytes: usize,
+    /// Minimum corner of the model's axis-aligned bounding box.
+    pub bounds_min: [f32; 3],
+    /// Maximum corner of the model's axis-aligned bounding box.
+    pub bounds_max: [f32; 3],
+    /// Fraction of thumbnail pixels covered by the model, in `0.0..=1.0`.
+    pub coverage: f32,
+    /// Average number of fragments written per covered pixel.
+    pub overdraw: f32,
+    /// Per-stage timings.
+    pub timings: StageTimings,
+}
+
+impl RenderReport {
+    /// Copies the loader name and load timings into this report.
+    fn record_load(&mut self, load: &LoadReport) {
+        self.loader = load.loader;
+        self.timings.parse = load.parse;
+        self.timings.convert = load.convert;
+    }
+}
+
+impl std::fmt::Display for RenderReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "loader:               {}", self.loader)?;
+        writeln!(f, "triangles:            {}", self.triangles)?;
+        writeln!(f, "rendered triangles:   {}", self.rendered_triangles)?;
+        writeln!(f, "culled triangles:     {}", self.culled_triangles)?;
+        writeln!(f, "back-face triangles:  {}", self.backface_triangles)?;
+        writeln!(f, "clipped triangles:    {}", self.clipped_triangles)?;
+        writeln!(f, "degenerate triangles: {}", self.degenerate_triangles)?;
+        writeln!(
+            f,
+            "textures:             {} ({} bytes)",
+            self.textures, self.texture_bytes
+        )?;
+        writeln!(
+            f,
+            "bounds:               {:?} .. {:?}",
+            self.bounds_min, self.bounds_max
+        )?;
+        writeln!(f, "coverage:             {:.1}%", self.coverage * 100.0)?;
+        writeln!(f, "overdraw:             {:.2}", self.overdraw)?;
+        write!(
+            f,
+            "timings:              parse {:?}, convert {:?}, raster {:?}",
+            self.timings.parse, self.timings.convert, self.timings.raster
+        )
+    }
+}
+
+/// Errors that can occur while loading and rendering a thumbnail.
+///
+/// # Examples
+/// ```
+/// use glimpse::renderer::RenderError;
+///
+/// let err = RenderError::TimedOut;
+/// assert_eq!(format!("{}", err), "Rendering exceeded its time budget");
+/// ```
+#[derive(Debug)]
+pub enum RenderError {
+    /// The model could not be loaded.
+    Load(LoadError),
+    /// No loader recognizes the file's content or extension.
+    UnsupportedFormat,
+    /// The requested thumbnail has zero width or height.
+    InvalidSize,
+    /// The model contains no triangles to draw.
+    NoGeometry,
+    /// The model's bounding box has zero size, so no camera can frame it.
+    DegenerateBounds,
+    /// Work was cancelled through a [`CancellationToken`](crate::budget::CancellationToken).
+    Cancelled,
+    /// Work exceeded its time budget.
+    TimedOut,
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::Load(e) => write!(f, "Failed to load model: {}", e),
+            RenderError::UnsupportedFormat => write!(f, "Unsupported model format"),
+            RenderError::InvalidSize => write!(f, "Thumbnail size must be non-zero"),
+            RenderError::NoGeometry => write!(f, "Model has no geometry to render"),
+            RenderError::DegenerateBounds => write!(f, "Model bounds are degenerate"),
+            RenderError::Cancelled => write!(f, "Rendering was cancelled"),
+            RenderError::TimedOut => write!(f, "Rendering exceeded its time budget"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RenderError::Load(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<LoadError> for RenderError {
+    fn from(e: LoadError) -> Self {
+        match e {
+            LoadError::UnrecognizedFormat => RenderError::UnsupportedFormat,
+            LoadError::NoGeometry => RenderError::NoGeometry,
+            LoadError::Cancelled => RenderError::Cancelled,
+            LoadError::TimedOut => RenderError::TimedOut,
+            other => RenderError::Load(other),
+        }
+    }
+}
+
+impl From<Interrupted> for RenderError {
+    fn from(e: Interrupted) -> Self {
+        match e {
+            Interrupted::Cancelled => RenderError::Cancelled,
+            Interrupted::TimedOut => RenderError::TimedOut,
+        }
+    }
+}
 
 /// Renders a model from raw bytes into an RGBA pixel buffer.
 /// Auto-detects the format based on content and extension.
@@ -36,8 +358,64 @@ pub fn render_thumbnail(
     width: u32,
     height: u32,
 ) -> Option<Vec<u8>> {
-    let model = formats::load_model(data, extension).ok()?;
-    render_model_data(model, width, height)
+    render_thumbnail_with_options(data, extension, width, height, &RenderOptions::default()).ok()
+}
+
+/// Renders a model from raw bytes using the given render options, reporting
+/// why rendering failed. Auto-detects the format based on content and extension.
+///
+/// # Errors
+/// Returns an error if the format is unsupported, the model cannot be loaded
+/// or has nothing that can be framed by the camera, or the budget runs out.
+///
+/// # Examples
+/// ```
+/// use glimpse::renderer::{render_thumbnail_with_options, RenderError, RenderOptions};
+///
+/// let options = RenderOptions {
+///     auto_view: true,
+///     ..Default::default()
+/// };
+/// let err = render_thumbnail_with_options(b"not a model", None, 64, 64, &options).unwrap_err();
+/// assert!(matches!(err, RenderError::UnsupportedFormat));
+/// ```
+pub fn render_thumbnail_with_options(
+    data: &[u8],
+    extension: Option<&str>,
+    width: u32,
+    height: u32,
+    options: &RenderOptions,
+) -> Result<Vec<u8>, RenderError> {
+    render_thumbnail_with_report(data, extension, width, height, options).map(|(p, _)| p)
+}
+
+/// Renders a model from raw bytes and reports statistics about the render.
+///
+/// # Errors
+/// Returns an error if the model cannot be loaded or rendered, or the budget runs out.
+///
+/// # Examples
+/// ```
+/// use glimpse::renderer::{render_thumbnail_with_report, RenderOptions};
+///
+/// let result = render_thumbnail_with_report(b"not a model", None, 64, 64, &RenderOptions::default());
+/// assert!(result.is_err());
+/// ```
+pub fn render_thumbnail_with_report(
+    data: &[u8],
+    extension: Option<&str>,
+    width: u32,
+    height: u32,
+    options: &RenderOptions,
+) -> Result<(Vec<u8>, RenderReport), RenderError> {
+    check_size(width, height)?;
+    let mut report = RenderReport::default();
+
+    let (model, load) = formats::load_model_with_report(data, extension, &options.load_options())?;
+    report.record_load(&load);
+
+    let pixels = render_model_data(model, width, height, options, &mut report)?;
+    Ok((pixels, report))
 }
 
 /// Renders a model from a file path into an RGBA pixel buffer.
@@ -53,8 +431,66 @@ pub fn render_thumbnail(
 /// assert!(pixels.is_none());
 /// ```
 pub fn render_thumbnail_from_path(path: &Path, width: u32, height: u32) -> Option<Vec<u8>> {
-    let model = formats::load_model_from_path(path).ok()?;
-    render_model_data(model, width, height)
+    render_thumbnail_from_path_with_options(path, width, height, &RenderOptions::default()).ok()
+}
+
+/// Renders a model from a file path using the given render options, reporting
+/// why rendering failed. Auto-detects the format based on content and extension.
+///
+/// # Errors
+/// Returns an error if the file cannot be read, the format is unsupported,
+/// the model cannot be loaded or rendered, or the budget runs out.
+///
+/// # Examples
+/// ```
+/// use std::path::Path;
+///
+/// use glimpse::renderer::{render_thumbnail_from_path_with_options, RenderError, RenderOptions};
+///
+/// let options = RenderOptions::default();
+/// let err =
+///     render_thumbnail_from_path_with_options(Path::new("does_not_exist.gltf"), 64, 64, &options);
+/// assert!(matches!(err, Err(RenderError::Load(_))));
+/// ```
+pub fn render_thumbnail_from_path_with_options(
+    path: &Path,
+    width: u32,
+    height: u32,
+    options: &RenderOptions,
+) -> Result<Vec<u8>, RenderError> {
+    render_thumbnail_from_path_with_report(path, width, height, options).map(|(p, _)| p)
+}
+
+/// Renders a model from a file path and reports statistics about the render.
+///
+/// # Errors
+/// Returns an error if the model cannot be loaded or rendered, or the budget runs out.
+///
+/// # Examples
+/// ```
+/// use std::path::Path;
+///
+/// use glimpse::renderer::{render_thumbnail_from_path_with_report, RenderOptions};
+///
+/// let options = RenderOptions::default();
+/// let result =
+///     render_thumbnail_from_path_with_report(Path::new("does_not_exist.gltf"), 64, 64, &options);
+/// assert!(result.is_err());
+/// ```
+pub fn render_thumbnail_from_path_with_report(
+    path: &Path,
+    width: u32,
+    height: u32,
+    options: &RenderOptions,
+) -> Result<(Vec<u8>, RenderReport), RenderError> {
+    check_size(width, height)?;
+    let mut report = RenderReport::default();
+
+    let (model, load) = formats::load_model_from_path_with_report(path, &options.load_options())?;
+    report.record_load(&load);
+
+    let pixels = render_model_data(model, width, height, options, &mut report)?;
+    Ok((pixels, report))
 }
 
 /// Renders a glTF/GLB model from raw bytes into an RGBA pixel buffer.
@@ -87,42 +523,109 @@ pub fn render_gltf_thumbnail_from_path(path: &Path, width: u32, height: u32) ->
     render_thumbnail_from_path(path, width, height)
 }
 
-/// Renders loaded model data to pixels.
-fn render_model_data(model: ModelData, width: u32, height: u32) -> Option<Vec<u8>> {
-    let triangles = model.triangles;
+/// Default camera azimuth, rotated 180° so models face the camera instead of away.
+const DEFAULT_AZIMUTH_DEG: f32 = 35.0 + 180.0;
+/// Default camera elevation above the horizon.
+const DEFAULT_ELEVATION_DEG: f32 = 25.0;
+/// Resolution of the low-res pre-pass used to score auto-view candidates.
+const AUTO_VIEW_PREPASS_SIZE: u32 = 48;
+/// Number of azimuths evaluated around the model by the auto-view pre-pass.
+const AUTO_VIEW_CANDIDATES: usize = 8;
+/// Number of triangles rasterized between budget checks.
+const BUDGET_CHECK_INTERVAL: usize = 1024;
+/// Triangle budget used for the coarse fallback render once the deadline has passed.
+const LOW_DETAIL_TRIANGLE_BUDGET: usize = 4096;
+/// Extra time the coarse fallback render may take after the deadline has passed.
+const LOW_DETAIL_GRACE: Duration = Duration::from_millis(500);
+/// Point radius per pixel of thumbnail size; a 256x256 thumbnail draws 1px-radius points.
+const POINT_RADIUS_SCALE: f32 = 1.0 / 256.0;
 
-    if triangles.is_empty() {
-        return None;
+/// Rejects zero-sized thumbnails before any loading work is done.
+fn check_size(width: u32, height: u32) -> Result<(), RenderError> {
+    if width == 0 || height == 0 {
+        return Err(RenderError::InvalidSize);
+    }
+    Ok(())
+}
+
+/// Renders loaded model data to pixels, filling in the render statistics of `report`.
+fn render_model_data(
+    mut model: ModelData,
+    width: u32,
+    height: u32,
+    options: &RenderOptions,
+    report: &mut RenderReport,
+) -> Result<Vec<u8>, RenderError> {
+    let convert_start = Instant::now();
+    report.triangles = model.triangle_count();
+    (report.textures, report.texture_bytes) = texture_usage(&model);
+
+    // Past the deadline, a partial render falls back to a coarse mesh instead of failing.
+    let mut low_detail = match options.budget.check() {
+        Ok(()) => false,
+        Err(Interrupted::TimedOut) if options.partial_on_timeout => true,
+        Err(interrupted) => return Err(interrupted.into()),
+    };
+    if let (false, Some(max_triangles)) = (low_detail, options.triangle_budget) {
+        match simplify::simplify(&mut model, max_triangles, &options.budget) {
+            Ok(()) => {}
+            Err(Interrupted::TimedOut) if options.partial_on_timeout => low_detail = true,
+            Err(interrupted) => return Err(interrupted.into()),
+        }
+    }
+    // The fallback gets a short grace period of its own, so it stays bounded too.
+    let budget = if low_detail {
+        Budget {
+            token: options.budget.token.clone(),
+            deadline: Some(Instant::now() + LOW_DETAIL_GRACE),
+        }
+    } else {
+        options.budget.clone()
+    };
+    if low_detail {
+        simplify::simplify(&mut model, LOW_DETAIL_TRIANGLE_BUDGET, &budget)?;
+    }
+    report.rendered_triangles = model.triangle_count();
+
+    if model.is_empty() {
+        return Err(RenderError::NoGeometry);
     }
 
     // ---- Compute bounding sphere ----
-    let (bb_min, bb_max) = compute_bounds(&triangles);
+    let (bb_min, bb_max) = compute_bounds(&model);
+    report.bounds_min = bb_min.to_array();
+    report.bounds_max = bb_max.to_array();
     let center = bb_min.lerp(bb_max, 0.5);
     let extent = bb_max - bb_min;
     let radius = extent.length() * 0.5;
 
     if radius < 1e-6 {
-        return None;
+        return Err(RenderError::DegenerateBounds);
     }
 
     // ---- Camera ----
-    // Azimuth rotated 180° so models face the camera instead of away
-    let azimuth: f32 = (35.0 + 180.0_f32).to_radians();
-    let elevation: f32 = 25.0_f32.to_radians();
-    let dist = radius * 2.8;
-
-    let eye = Vec3::new(
-        center.x + dist * elevation.cos() * azimuth.sin(),
-        center.y + dist * elevation.sin(),
-        center.z + dist * elevation.cos() * azimuth.cos(),
-    );
-
-    let view = Mat4::look_at_rh(eye, center, Vec3::Y);
-    let aspect = width as f32 / height as f32;
-    let near = radius * 0.01;
-    let far = radius * 100.0;
-    let proj = Mat4::perspective_rh_gl(45.0_f32.to_radians(), aspect, near, far);
-    let view_proj = proj * view;
+    let authored = options
+        .camera
+        .as_ref()
+        .and_then(|choice| choice.find(&model));
+    let (view_proj, viewpoint) = match authored {
+        Some(camera) => authored_view(camera, width, height),
+        None => {
+            let azimuth = if options.auto_view && !low_detail {
+                options.budget.check()?;
+                pick_best_azimuth(&model, center, radius)
+            } else {
+                DEFAULT_AZIMUTH_DEG.to_radians()
+            };
+            let elevation = DEFAULT_ELEVATION_DEG.to_radians();
+            (
+                camera_view_proj(center, radius, azimuth, elevation, width, height),
+                Viewpoint::Eye(camera_eye(center, radius, azimuth, elevation)),
+            )
+        }
+    };
+    report.timings.convert += convert_start.elapsed();
+    let raster_start = Instant::now();
 
     // ---- Framebuffer ----
     let w = width as usize;
@@ -131,112 +634,149 @@ fn render_model_data(model: ModelData, width: u32, height: u32) -> Option<Vec<u8
     let mut depth_buf = vec![f32::INFINITY; w * h];
 
     // ---- Lighting ----
-    let light_dir = Vec3::new(0.5, 0.8, 0.3).normalize();
-    let light2_dir = Vec3::new(-0.3, 0.2, -0.5).normalize();
-
-    // ---- Rasterize each triangle ----
-    for tri in &triangles {
-        let mut clip = [Vec4::ZERO; 3];
-        let mut screen = [Vec3::ZERO; 3];
-        let mut visible = true;
-
-        for i in 0..3 {
-            let v = Vec3::from_array(tri.verts[i]);
-            clip[i] = view_proj * v.extend(1.0);
-
-            if clip[i].w <= 0.0 {
-                visible = false;
-                break;
+    let lights = scene_lights(&model, center, radius).unwrap_or_else(default_lights);
+
+    // ---- Rasterize each mesh ----
+    // Opaque and cutout meshes go first so translucent ones blend over them.
+    let mut meshes: Vec<&Mesh> = model.meshes.iter().collect();
+    meshes.sort_by_key(|mesh| is_translucent(model.material(mesh.material)));
+
+    let mut fragments = 0usize;
+    let mut tri_num = 0usize;
+    'meshes: for mesh in meshes {
+        let material = model.material(mesh.material);
+        let translucent = is_translucent(material);
+        // Shared vertices are projected once per mesh
+        let projected = project_mesh(view_proj, mesh, width, height);
+
+        for t in 0..mesh.triangle_count() {
+            if tri_num % BUDGET_CHECK_INTERVAL == BUDGET_CHECK_INTERVAL - 1 {
+                match budget.check() {
+                    Ok(()) => {}
+                    // A timed-out render may still yield the triangles drawn so far.
+                    Err(Interrupted::TimedOut) if options.partial_on_timeout => break 'meshes,
+                    Err(interrupted) => return Err(interrupted.into()),
+                }
             }
+            tri_num += 1;
 
-            let inv_w = 1.0 / clip[i].w;
-            screen[i] = Vec3::new(
-                (clip[i].x * inv_w * 0.5 + 0.5) * width as f32,
-                (0.5 - clip[i].y * inv_w * 0.5) * height as f32,
-                clip[i].z * inv_w,
-            );
-        }
+            let idx = mesh.triangle_indices(t);
+            let Some(screen) = screen_triangle(&projected, idx) else {
+                report.clipped_triangles += 1;
+                continue;
+            };
 
-        if !visible {
-            continue;
-        }
+            // Face normal in world space (flat shading)
+            let v0 = Vec3::from_array(mesh.positions[idx[0]]);
+            let v1 = Vec3::from_array(mesh.positions[idx[1]]);
+            let v2 = Vec3::from_array(mesh.positions[idx[2]]);
+            let e1 = v1 - v0;
+            let e2 = v2 - v0;
+            let Some(normal) = e1.cross(e2).try_normalize() else {
+                report.degenerate_triangles += 1;
+                continue;
+            };
+            if !material.double_sided && normal.dot(viewpoint.towards(v0)) <= 0.0 {
+                report.backface_triangles += 1;
+                continue;
+            }
+            let screen_area = (screen[1] - screen[0])
+                .truncate()
+                .perp_dot((screen[2] - screen[0]).truncate());
+            if screen_area.abs() < 1e-10 {
+                report.degenerate_triangles += 1;
+                continue;
+            }
 
-        // Face normal in world space (flat shading)
-        let v0 = Vec3::from_array(tri.verts[0]);
-        let v1 = Vec3::from_array(tri.verts[1]);
-        let v2 = Vec3::from_array(tri.verts[2]);
-        let e1 = v1 - v0;
-        let e2 = v2 - v0;
-        let normal = e1.cross(e2).normalize();
+            let face_shade = shade_surface(material, &lights, normal, (v0 + v1 + v2) / 3.0);
+            // Smooth-shaded meshes light every corner with its own normal
+            let shades = match &mesh.normals {
+                Some(normals) => idx.map(|i| match Vec3::from_array(normals[i]).try_normalize() {
+                    Some(n) => {
+                        shade_surface(material, &lights, n, Vec3::from_array(mesh.positions[i]))
+                    }
+                    None => face_shade,
+                }),
+                None => [face_shade; 3],
+            };
 
-        let ndl_main = normal.dot(light_dir).abs();
-        let ndl_fill = normal.dot(light2_dir).abs();
+            let colors = corner_colors(mesh, material, idx);
+            let uvs = idx.map(|i| mesh.uvs[i]);
 
-        let ambient = 0.15;
-        let diffuse = ndl_main * 0.60 + ndl_fill * 0.15;
-        let specular = ndl_main.powf(32.0) * 0.10;
-        let shade = (ambient + diffuse + specular).min(1.0);
+            // Screen-space bounding box
+            let (min_x, max_x, min_y, max_y) = screen_bounds(screen, w, h);
+            if min_x >= max_x || min_y >= max_y {
+                report.culled_triangles += 1;
+                continue;
+            }
 
-        // Screen-space bounding box
-        let min_x = screen[0].x.min(screen[1].x).min(screen[2].x).max(0.0) as usize;
-        let max_x = (screen[0].x.max(screen[1].x).max(screen[2].x).ceil() as usize).min(w);
-        let min_y = screen[0].y.min(screen[1].y).min(screen[2].y).max(0.0) as usize;
-        let max_y = (screen[0].y.max(screen[1].y).max(screen[2].y).ceil() as usize).min(h);
+            // Rasterize
+            for y in min_y..max_y {
+                for x in min_x..max_x {
+                    let px = x as f32 + 0.5;
+                    let py = y as f32 + 0.5;
 
-        // Rasterize
-        for y in min_y..max_y {
-            for x in min_x..max_x {
-                let px = x as f32 + 0.5;
-                let py = y as f32 + 0.5;
+                    let (u_bary, v_bary, w_bary) = barycentric(screen, px, py);
 
-                let (u_bary, v_bary, w_bary) = barycentric(screen, px, py);
+                    if u_bary >= 0.0 && v_bary >= 0.0 && w_bary >= 0.0 {
+                        let z = u_bary * screen[0].z + v_bary * screen[1].z + w_bary * screen[2].z;
+                        let idx = y * w + x;
 
-                if u_bary >= 0.0 && v_bary >= 0.0 && w_bary >= 0.0 {
-                    let z = u_bary * screen[0].z + v_bary * screen[1].z + w_bary * screen[2].z;
-                    let idx = y * w + x;
+                        if z < depth_buf[idx] {
+                            // Interpolate UVs using barycentric coordinates
+                            let tex_u =
+                                u_bary * uvs[0][0] + v_bary * uvs[1][0] + w_bary * uvs[2][0];
+                            let tex_v =
+                                u_bary * uvs[0][1] + v_bary * uvs[1][1] + w_bary * uvs[2][1];
 
-                    if z < depth_buf[idx] {
-                        depth_buf[idx] = z;
+                            // Interpolate vertex colors for smooth vertex painting
+                            let color = [0, 1, 2].map(|ch| {
+                                u_bary * colors[0][ch]
+                                    + v_bary * colors[1][ch]
+                                    + w_bary * colors[2][ch]
+                            });
 
-                        // Interpolate UVs using barycentric coordinates
-                        let tex_u = u_bary * tri.uvs[0][0]
-                            + v_bary * tri.uvs[1][0]
-                            + w_bary * tri.uvs[2][0];
-                        let tex_v = u_bary * tri.uvs[0][1]
-                            + v_bary * tri.uvs[1][1]
-                            + w_bary * tri.uvs[2][1];
-
-                        // Sample texture if available, otherwise use base color
-                        let (base, alpha) = if let Some(ref tex) = tri.texture {
-                            let sampled = tex.sample(tex_u, tex_v);
-                            (
-                                [
-                                    sampled[0] * tri.color[0],
-                                    sampled[1] * tri.color[1],
-                                    sampled[2] * tri.color[2],
-                                ],
-                                sampled[3],
-                            )
-                        } else {
-                            (tri.color, 1.0)
-                        };
-
-                        // Alpha cutoff - skip fully transparent pixels
-                        if alpha < 0.5 {
-                            continue;
-                        }
+                            let (base, alpha) = surface_color(material, color, [tex_u, tex_v]);
+                            let Some(alpha) = fragment_alpha(material.alpha_mode, alpha) else {
+                                continue;
+                            };
 
-                        let shaded = [
-                            (base[0] * shade).min(1.0),
-                            (base[1] * shade).min(1.0),
-                            (base[2] * shade).min(1.0),
-                        ];
+                            let emissive = emissive_color(material, [tex_u, tex_v]);
+                            let shade =
+                                shades[0] * u_bary + shades[1] * v_bary + shades[2] * w_bary;
+                            let shaded =
+                                [0, 1, 2].map(|ch| (base[ch] * shade[ch] + emissive[ch]).min(1.0));
 
-                        color_buf[idx] = [shaded[0], shaded[1], shaded[2], 1.0];
+                            // Translucent fragments are depth-tested but leave the depth buffer alone
+                            if !translucent {
+                                depth_buf[idx] = z;
+                            }
+                            write_fragment(
+                                &mut color_buf[idx],
+                                shaded,
+                                alpha,
+                                material.render_mode,
+                            );
+                            fragments += 1;
+                        }
                     }
                 }
             }
         }
+
+        match draw_lines_and_points(
+            mesh,
+            material,
+            &projected,
+            &mut color_buf,
+            &mut depth_buf,
+            (w, h),
+            &budget,
+        ) {
+            Ok(drawn) => fragments += drawn,
+            Err(Interrupted::TimedOut) if options.partial_on_timeout => break 'meshes,
+            Err(interrupted) => return Err(interrupted.into()),
+        }
     }
 
     // ---- Convert f32 → u8 RGBA ----
@@ -248,19 +788,508 @@ fn render_model_data(model: ModelData, width: u32, height: u32) -> Option<Vec<u8
         pixels[i * 4 + 3] = (color_buf[i][3].clamp(0.0, 1.0) * 255.0) as u8;
     }
 
-    Some(pixels)
+    let covered = color_buf.iter().filter(|c| c[3] > 0.0).count();
+    report.coverage = covered as f32 / (w * h) as f32;
+    report.overdraw = if covered > 0 {
+        fragments as f32 / covered as f32
+    } else {
+        0.0
+    };
+    report.timings.raster = raster_start.elapsed();
+
+    Ok(pixels)
+}
+
+/// Counts the distinct textures used by the model's meshes and their pixel memory.
+fn texture_usage(model: &ModelData) -> (usize, usize) {
+    let mut seen = HashSet::new();
+    let mut bytes = 0;
+    let textures = model
+        .meshes
+        .iter()
+        .filter_map(|mesh| model.material(mesh.material).texture.as_ref());
+    for texture in textures {
+        if seen.insert(Arc::as_ptr(texture)) {
+            bytes += texture.data.len();
+        }
+    }
+    (seen.len(), bytes)
+}
+
+/// Returns the colors at a triangle's corners: vertex colors times the material color.
+fn corner_colors(mesh: &Mesh, material: &Material, idx: [usize; 3]) -> [[f32; 3]; 3] {
+    idx.map(|i| vertex_color(mesh, material, i))
+}
+
+/// Returns a vertex's color times the material color.
+fn vertex_color(mesh: &Mesh, material: &Material, index: usize) -> [f32; 3] {
+    match &mesh.colors {
+        Some(colors) => [0, 1, 2].map(|ch| colors[index][ch] * material.color[ch]),
+        None => material.color,
+    }
+}
+
+/// Returns the unlit surface color and alpha at `uv`, sampling the texture if
+/// available and otherwise using the interpolated vertex color.
+fn surface_color(material: &Material, color: [f32; 3], uv: [f32; 2]) -> ([f32; 3], f32) {
+    match material.texture {
+        Some(ref tex) => {
+            let sampled = tex.sample_with(uv[0], uv[1], material.wrap);
+            (
+                [0, 1, 2].map(|ch| sampled[ch] * color[ch]),
+                sampled[3] * material.alpha,
+            )
+        }
+        None => (color, material.alpha),
+    }
+}
+
+/// Returns the emitted color at `uv`.
+fn emissive_color(material: &Material, uv: [f32; 2]) -> [f32; 3] {
+    match material.emissive_texture {
+        Some(ref tex) => {
+            let sampled = tex.sample_with(uv[0], uv[1], material.wrap);
+            [0, 1, 2].map(|ch| material.emissive[ch] * sampled[ch])
+        }
+        None => material.emissive,
+    }
+}
+
+/// Draws a mesh's line segments and points as unlit, depth-tested screen-space
+/// primitives, returning the number of fragments written.
+///
+/// Lines are one pixel wide; points are squares that grow with the thumbnail size.
+/// `budget` is checked every [`BUDGET_CHECK_INTERVAL`] primitives, so large
+/// point clouds can be cancelled like triangle meshes.
+fn draw_lines_and_points(
+    mesh: &Mesh,
+    material: &Material,
+    projected: &[Option<Vec3>],
+    color_buf: &mut [[f32; 4]],
+    depth_buf: &mut [f32],
+    (w, h): (usize, usize),
+    budget: &Budget,
+) -> Result<usize, Interrupted> {
+    let mut primitive_num = 0usize;
+    let mut check_budget = || {
+        primitive_num += 1;
+        if primitive_num.is_multiple_of(BUDGET_CHECK_INTERVAL) {
+            budget.check()
+        } else {
+            Ok(())
+        }
+    };
+    let translucent = is_translucent(material);
+    let mut fragments = 0;
+    // Shades pixel (x, y) with the attributes of vertices a and b blended by t
+    let mut plot = |x: f32, y: f32, z: f32, a: usize, b: usize, t: f32| {
+        if x < 0.0 || y < 0.0 || x >= w as f32 || y >= h as f32 {
+            return;
+        }
+        let idx = y as usize * w + x as usize;
+        if z >= depth_buf[idx] {
+            return;
+        }
+        let (color_a, color_b) = (
+            vertex_color(mesh, material, a),
+            vertex_color(mesh, material, b),
+        );
+        let color = [0, 1, 2].map(|ch| color_a[ch] + (color_b[ch] - color_a[ch]) * t);
+        let uv = [0, 1].map(|ch| mesh.uvs[a][ch] + (mesh.uvs[b][ch] - mesh.uvs[a][ch]) * t);
+        let (base, alpha) = surface_color(material, color, uv);
+        let Some(alpha) = fragment_alpha(material.alpha_mode, alpha) else {
+            return;
+        };
+        let emissive = emissive_color(material, uv);
+        let shaded = [0, 1, 2].map(|ch| (base[ch] + emissive[ch]).min(1.0));
+        if !translucent {
+            depth_buf[idx] = z;
+        }
+        write_fragment(&mut color_buf[idx], shaded, alpha, material.render_mode);
+        fragments += 1;
+    };
+
+    for line in mesh.lines.chunks_exact(2) {
+        check_budget()?;
+        let (a, b) = (line[0] as usize, line[1] as usize);
+        let (Some(start), Some(end)) = (projected[a], projected[b]) else {
+            continue;
+        };
+        let Some((t0, t1)) = clip_segment(start, end, w as f32, h as f32) else {
+            continue;
+        };
+        // One sample per pixel along the major axis
+        let delta = end - start;
+        let steps = ((delta.x.abs().max(delta.y.abs()) * (t1 - t0)).ceil() as usize).max(1);
+        for step in 0..=steps {
+            let t = t0 + (t1 - t0) * step as f32 / steps as f32;
+            let p = start + delta * t;
+            plot(p.x, p.y, p.z, a, b, t);
+        }
+    }
+
+    let radius = (w.min(h) as f32 * POINT_RADIUS_SCALE).max(1.0);
+    let extent = Vec3::new(radius, radius, 0.0);
+    for &i in &mesh.points {
+        check_budget()?;
+        let i = i as usize;
+        let Some(p) = projected[i] else {
+            continue;
+        };
+        let (min_x, max_x, min_y, max_y) = screen_bounds([p - extent, p + extent, p], w, h);
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                plot(x as f32, y as f32, p.z, i, i, 0.0);
+            }
+        }
+    }
+
+    Ok(fragments)
+}
+
+/// Clips the screen-space segment `a`→`b` to the framebuffer, returning the
+/// visible range of its parameter `t`.
+fn clip_segment(a: Vec3, b: Vec3, w: f32, h: f32) -> Option<(f32, f32)> {
+    let d = b - a;
+    let (mut t0, mut t1) = (0.0_f32, 1.0_f32);
+    for (p, q) in [(-d.x, a.x), (d.x, w - a.x), (-d.y, a.y), (d.y, h - a.y)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    (t0 <= t1).then_some((t0, t1))
+}
+
+/// Returns whether a material is drawn after opaque geometry without writing depth.
+fn is_translucent(material: &Material) -> bool {
+    material.alpha_mode == AlphaMode::Blend || material.render_mode == RenderMode::Additive
+}
+
+/// Applies a material's alpha mode to a fragment's alpha.
+///
+/// Returns `None` if the fragment is discarded, otherwise the coverage to blend with.
+fn fragment_alpha(mode: AlphaMode, alpha: f32) -> Option<f32> {
+    match mode {
+        AlphaMode::Opaque => Some(1.0),
+        AlphaMode::Mask { cutoff } => (alpha >= cutoff).then_some(1.0),
+        AlphaMode::Blend => (alpha > 0.0).then_some(alpha.min(1.0)),
+    }
+}
+
+/// Combines a shaded fragment with the (straight-alpha) framebuffer pixel.
+fn write_fragment(dst: &mut [f32; 4], color: [f32; 3], alpha: f32, mode: RenderMode) {
+    match mode {
+        RenderMode::Normal if alpha >= 1.0 => *dst = [color[0], color[1], color[2], 1.0],
+        RenderMode::Normal => {
+            let out_alpha = alpha + dst[3] * (1.0 - alpha);
+            let rgb = [0, 1, 2]
+                .map(|ch| (color[ch] * alpha + dst[ch] * dst[3] * (1.0 - alpha)) / out_alpha);
+            *dst = [rgb[0], rgb[1], rgb[2], out_alpha];
+        }
+        RenderMode::Additive => {
+            let out_alpha = alpha + dst[3] * (1.0 - alpha);
+            let rgb =
+                [0, 1, 2].map(|ch| ((color[ch] * alpha + dst[ch] * dst[3]) / out_alpha).min(1.0));
+            *dst = [rgb[0], rgb[1], rgb[2], out_alpha];
+        }
+    }
+}
+
+/// Returns the position of the orbit camera framing the bounding sphere.
+fn camera_eye(center: Vec3, radius: f32, azimuth: f32, elevation: f32) -> Vec3 {
+    let dist = radius * 2.8;
+    Vec3::new(
+        center.x + dist * elevation.cos() * azimuth.sin(),
+        center.y + dist * elevation.sin(),
+        center.z + dist * elevation.cos() * azimuth.cos(),
+    )
+}
+
+/// Builds the view-projection matrix for an orbit camera framing the bounding sphere.
+fn camera_view_proj(
+    center: Vec3,
+    radius: f32,
+    azimuth: f32,
+    elevation: f32,
+    width: u32,
+    height: u32,
+) -> Mat4 {
+    let eye = camera_eye(center, radius, azimuth, elevation);
+
+    let view = Mat4::look_at_rh(eye, center, Vec3::Y);
+    let aspect = width as f32 / height as f32;
+    let near = radius * 0.01;
+    let far = radius * 100.0;
+    let proj = Mat4::perspective_rh_gl(45.0_f32.to_radians(), aspect, near, far);
+    proj * view
+}
+
+/// Where the camera sees the scene from, for back-face culling.
+#[derive(Clone, Copy)]
+enum Viewpoint {
+    /// A perspective camera at this position.
+    Eye(Vec3),
+    /// An orthographic camera looking against this direction.
+    Direction(Vec3),
+}
+
+impl Viewpoint {
+    /// Returns a vector pointing from `point` towards the camera.
+    fn towards(self, point: Vec3) -> Vec3 {
+        match self {
+            Viewpoint::Eye(eye) => eye - point,
+            Viewpoint::Direction(direction) => direction,
+        }
+    }
+}
+
+/// Builds the view-projection matrix and viewpoint of an authored camera.
+///
+/// The vertical field of view or magnification is kept and the horizontal
+/// extent follows the thumbnail's aspect ratio.
+fn authored_view(camera: &Camera, width: u32, height: u32) -> (Mat4, Viewpoint) {
+    let (_, rotation, eye) =
+        Mat4::from_cols_array_2d(&camera.transform).to_scale_rotation_translation();
+    let forward = rotation * Vec3::NEG_Z;
+    let view = Mat4::look_to_rh(eye, forward, rotation * Vec3::Y);
+    let aspect = width as f32 / height as f32;
+    match camera.projection {
+        Projection::Perspective { yfov, znear, zfar } => {
+            let proj = match zfar {
+                Some(zfar) => Mat4::perspective_rh_gl(yfov, aspect, znear, zfar),
+                None => Mat4::perspective_infinite_rh(yfov, aspect, znear),
+            };
+            (proj * view, Viewpoint::Eye(eye))
+        }
+        Projection::Orthographic { ymag, znear, zfar } => {
+            let xmag = ymag * aspect;
+            let proj = Mat4::orthographic_rh_gl(-xmag, xmag, -ymag, ymag, znear, zfar);
+            (proj * view, Viewpoint::Direction(-forward))
+        }
+    }
+}
+
+// ===========================================================================
+// Lighting
+// ===========================================================================
+
+/// A light prepared for flat shading.
+struct ShadingLight {
+    kind: LightKind,
+    position: Vec3,
+    /// Direction the light travels in.
+    direction: Vec3,
+    /// Diffuse contribution at unit attenuation.
+    radiance: Vec3,
+    /// Strength of the specular highlight at unit attenuation.
+    specular: f32,
+    range: Option<f32>,
+    /// Distance below which point and spot lights stop getting brighter.
+    min_distance: f32,
+}
+
+impl ShadingLight {
+    /// Returns the unit vector from `point` towards the light and the light's
+    /// attenuation there.
+    fn incidence(&self, point: Vec3) -> (Vec3, f32) {
+        if self.kind == LightKind::Directional {
+            return (-self.direction, 1.0);
+        }
+        let offset = self.position - point;
+        let distance = offset.length().max(self.min_distance);
+        let to_light = offset.normalize_or_zero();
+        // Inverse-square falloff with the smooth range window KHR_lights_punctual recommends
+        let window = self.range.map_or(1.0, |range| {
+            (1.0 - (distance / range).powi(4)).clamp(0.0, 1.0)
+        });
+        let mut attenuation = window / (distance * distance);
+        if let LightKind::Spot {
+            inner_cone_angle,
+            outer_cone_angle,
+        } = self.kind
+        {
+            let cos_outer = outer_cone_angle.cos();
+            let scale = 1.0 / (inner_cone_angle.cos() - cos_outer).max(0.001);
+            let cos_angle = self.direction.dot(-to_light);
+            attenuation *= ((cos_angle - cos_outer) * scale).clamp(0.0, 1.0).powi(2);
+        }
+        (to_light, attenuation)
+    }
+}
+
+/// Returns the light reaching a surface point, multiplied with its base color.
+///
+/// Unlit materials show their base color as-is.
+fn shade_surface(
+    material: &Material,
+    lights: &[ShadingLight],
+    normal: Vec3,
+    position: Vec3,
+) -> Vec3 {
+    if material.unlit {
+        return Vec3::ONE;
+    }
+    // Metals lose diffuse light; smooth surfaces get tighter, brighter highlights
+    let smoothness = 1.0 - material.roughness.clamp(0.0, 1.0);
+    let metal = 1.0 - 0.4 * material.metallic.clamp(0.0, 1.0);
+    let mut shade = Vec3::splat(0.15);
+    for light in lights {
+        let (to_light, attenuation) = light.incidence(position);
+        let ndl = normal.dot(to_light).abs();
+        let specular = ndl.powf(8.0 + 48.0 * smoothness) * light.specular * smoothness;
+        shade +=
+            (light.radiance * ndl * metal + Vec3::from(material.specular) * specular) * attenuation;
+    }
+    shade.min(Vec3::ONE)
+}
+
+/// Returns the fixed key and fill lights used for models without lights of their own.
+fn default_lights() -> Vec<ShadingLight> {
+    let directional = |towards: Vec3, strength: f32, specular: f32| ShadingLight {
+        kind: LightKind::Directional,
+        position: Vec3::ZERO,
+        direction: -towards.normalize(),
+        radiance: Vec3::splat(strength),
+        specular,
+        range: None,
+        min_distance: 0.0,
+    };
+    vec![
+        directional(Vec3::new(0.5, 0.8, 0.3), 0.60, 0.20),
+        directional(Vec3::new(-0.3, 0.2, -0.5), 0.15, 0.0),
+    ]
+}
+
+/// Prepares the model's punctual lights, exposed so that they light the model
+/// about as brightly as the default rig whatever their physical intensity.
+///
+/// Returns `None` if the model has no lights or none of them reaches its center.
+fn scene_lights(model: &ModelData, center: Vec3, radius: f32) -> Option<Vec<ShadingLight>> {
+    let mut lights: Vec<ShadingLight> = model
+        .lights
+        .iter()
+        .map(|light| {
+            let transform = Mat4::from_cols_array_2d(&light.transform);
+            ShadingLight {
+                kind: light.kind,
+                position: transform.w_axis.truncate(),
+                direction: transform.transform_vector3(Vec3::NEG_Z).normalize_or_zero(),
+                radiance: Vec3::from_array(light.color) * light.intensity,
+                specular: 0.0,
+                range: light.range,
+                min_distance: radius * 0.05,
+            }
+        })
+        .collect();
+
+    let total: f32 = lights
+        .iter()
+        .map(|light| light.radiance.max_element() * light.incidence(center).1)
+        .sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+    let exposure = 0.75 / total;
+    for light in &mut lights {
+        light.radiance *= exposure;
+        // Keep the default rig's ratio of highlight to diffuse strength
+        light.specular = light.radiance.max_element() / 3.0;
+    }
+    Some(lights)
+}
+
+// ===========================================================================
+// Automatic view selection
+// ===========================================================================
+
+/// Scores candidate azimuths with a low-res pre-pass and returns the best one (radians).
+///
+/// Each candidate is rasterized into a small triangle-id buffer. The score combines
+/// projected area (silhouette coverage) with the fraction of triangles that end up
+/// visible, so views showing a broad, detailed side win over thin or back views.
+/// Ties keep the default azimuth so auto view never changes already-good thumbnails.
+fn pick_best_azimuth(model: &ModelData, center: Vec3, radius: f32) -> f32 {
+    let elevation = DEFAULT_ELEVATION_DEG.to_radians();
+    let default_azimuth = DEFAULT_AZIMUTH_DEG.to_radians();
+
+    let mut best_azimuth = default_azimuth;
+    let mut best_score = score_view(model, center, radius, default_azimuth, elevation);
+
+    for i in 1..AUTO_VIEW_CANDIDATES {
+        let step = std::f32::consts::TAU / AUTO_VIEW_CANDIDATES as f32;
+        let azimuth = default_azimuth + step * i as f32;
+        let score = score_view(model, center, radius, azimuth, elevation);
+        // Require a clear improvement to move away from the default view.
+        if score > best_score * 1.05 {
+            best_score = score;
+            best_azimuth = azimuth;
+        }
+    }
+
+    best_azimuth
+}
+
+/// Rasterizes triangle ids at low resolution and scores the resulting view.
+fn score_view(model: &ModelData, center: Vec3, radius: f32, azimuth: f32, elevation: f32) -> f32 {
+    let size = AUTO_VIEW_PREPASS_SIZE;
+    let n = size as usize;
+    let view_proj = camera_view_proj(center, radius, azimuth, elevation, size, size);
+
+    let mut depth_buf = vec![f32::INFINITY; n * n];
+    let mut id_buf = vec![u32::MAX; n * n];
+
+    let mut tri_id = 0u32;
+    for mesh in &model.meshes {
+        let projected = project_mesh(view_proj, mesh, size, size);
+        for t in 0..mesh.triangle_count() {
+            let id = tri_id;
+            tri_id += 1;
+            let Some(screen) = screen_triangle(&projected, mesh.triangle_indices(t)) else {
+                continue;
+            };
+            let (min_x, max_x, min_y, max_y) = screen_bounds(screen, n, n);
+
+            for y in min_y..max_y {
+                for x in min_x..max_x {
+                    let (u, v, w) = barycentric(screen, x as f32 + 0.5, y as f32 + 0.5);
+                    if u < 0.0 || v < 0.0 || w < 0.0 {
+                        continue;
+                    }
+                    let z = u * screen[0].z + v * screen[1].z + w * screen[2].z;
+                    let idx = y * n + x;
+                    if z < depth_buf[idx] {
+                        depth_buf[idx] = z;
+                        id_buf[idx] = id;
+                    }
+                }
+            }
+        }
+    }
+
+    let covered = id_buf.iter().filter(|&&id| id != u32::MAX).count();
+    let mut visible: Vec<u32> = id_buf.into_iter().filter(|&id| id != u32::MAX).collect();
+    visible.sort_unstable();
+    visible.dedup();
+
+    let coverage = covered as f32 / (n * n) as f32;
+    let visible_ratio = visible.len() as f32 / tri_id.max(1) as f32;
+    coverage + 0.5 * visible_ratio
 }
 
-/// Computes the axis-aligned bounding box of all triangle vertices.
-fn compute_bounds(triangles: &[Triangle]) -> (Vec3, Vec3) {
+/// Computes the axis-aligned bounding box of all mesh vertices.
+fn compute_bounds(model: &ModelData) -> (Vec3, Vec3) {
     let mut min = Vec3::splat(f32::INFINITY);
     let mut max = Vec3::splat(f32::NEG_INFINITY);
-    for tri in triangles {
-        for v in &tri.verts {
-            let p = Vec3::from_array(*v);
-            min = min.min(p);
-            max = max.max(p);
-        }
+    for v in model.meshes.iter().flat_map(|m| &m.positions) {
+        let p = Vec3::from_array(*v);
+        min = min.min(p);
+        max = max.max(p);
     }
     (min, max)
 }
@@ -269,6 +1298,44 @@ fn compute_bounds(triangles: &[Triangle]) -> (Vec3, Vec3) {
 // Rasterization helpers
 // ===========================================================================
 
+/// Projects every vertex of a mesh to screen space (pixels, with NDC depth in `z`).
+///
+/// Vertices behind the camera project to `None`.
+fn project_mesh(view_proj: Mat4, mesh: &Mesh, width: u32, height: u32) -> Vec<Option<Vec3>> {
+    mesh.positions
+        .iter()
+        .map(|p| {
+            let clip = view_proj * Vec3::from_array(*p).extend(1.0);
+            if clip.w <= 0.0 {
+                return None;
+            }
+            let inv_w = 1.0 / clip.w;
+            Some(Vec3::new(
+                (clip.x * inv_w * 0.5 + 0.5) * width as f32,
+                (0.5 - clip.y * inv_w * 0.5) * height as f32,
+                clip.z * inv_w,
+            ))
+        })
+        .collect()
+}
+
+/// Gathers the projected corners of a triangle.
+///
+/// Returns `None` if any vertex lies behind the camera.
+fn screen_triangle(projected: &[Option<Vec3>], idx: [usize; 3]) -> Option<[Vec3; 3]> {
+    Some([projected[idx[0]]?, projected[idx[1]]?, projected[idx[2]]?])
+}
+
+/// Returns the pixel bounding box `(min_x, max_x, min_y, max_y)` of a screen triangle,
+/// clamped to the framebuffer.
+fn screen_bounds(screen: [Vec3; 3], w: usize, h: usize) -> (usize, usize, usize, usize) {
+    let min_x = screen[0].x.min(screen[1].x).min(screen[2].x).max(0.0) as usize;
+    let max_x = (screen[0].x.max(screen[1].x).max(screen[2].x).ceil() as usize).min(w);
+    let min_y = screen[0].y.min(screen[1].y).min(screen[2].y).max(0.0) as usize;
+    let max_y = (screen[0].y.max(screen[1].y).max(screen[2].y).ceil() as usize).min(h);
+    (min_x, max_x, min_y, max_y)
+}
+
 fn barycentric(tri: [Vec3; 3], px: f32, py: f32) -> (f32, f32, f32) {
     let v0x = tri[1].x - tri[0].x;
     let v0y = tri[1].y - tri[0].y;
@@ -298,7 +1365,11 @@ fn barycentric(tri: [Vec3; 3], px: f32, py: f32) -> (f32, f32, f32) {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use super::*;
+    use crate::budget::CancellationToken;
+    use crate::formats::{Light, Triangle};
 
     #[test]
     fn test_render_empty_data() {
@@ -312,6 +1383,445 @@ mod tests {
         assert!(render_gltf_thumbnail(invalid, 256, 256).is_none());
     }
 
+    /// Builds a single quad lying in the YZ plane (facing +X).
+    fn x_facing_quad() -> Vec<Triangle> {
+        let corners = [
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 1.0],
+            [0.0, 0.0, 1.0],
+        ];
+        let tri = |a: usize, b: usize, c: usize| Triangle {
+            verts: [corners[a], corners[b], corners[c]],
+            uvs: [[0.0, 0.0]; 3],
+            color: [1.0, 1.0, 1.0],
+            texture: None,
+        };
+        vec![tri(0, 1, 2), tri(0, 2, 3)]
+    }
+
+    #[test]
+    fn test_auto_view_turns_towards_broad_side() {
+        let model = ModelData::from_triangles(x_facing_quad());
+        let (min, max) = compute_bounds(&model);
+        let center = min.lerp(max, 0.5);
+        let radius = (max - min).length() * 0.5;
+
+        let default_score = score_view(
+            &model,
+            center,
+            radius,
+            DEFAULT_AZIMUTH_DEG.to_radians(),
+            DEFAULT_ELEVATION_DEG.to_radians(),
+        );
+        let azimuth = pick_best_azimuth(&model, center, radius);
+        let best_score = score_view(
+            &model,
+            center,
+            radius,
+            azimuth,
+            DEFAULT_ELEVATION_DEG.to_radians(),
+        );
+
+        assert!(best_score > default_score);
+        // The chosen camera should look along the X axis.
+        assert!(azimuth.sin().abs() > 0.9);
+    }
+
+    #[test]
+    fn test_auto_view_renders_pixels() {
+        let model = ModelData::from_triangles(x_facing_quad());
+        let options = RenderOptions {
+            auto_view: true,
+            ..Default::default()
+        };
+        let pixels = render_model_data(model, 64, 64, &options, &mut RenderReport::default())
+            .expect("quad should render");
+        assert!(pixels.chunks(4).any(|p| p[3] > 0));
+    }
+
+    #[test]
+    fn test_cancelled_render_reports_cancellation() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = RenderOptions {
+            budget: Budget::new().with_token(token),
+            ..Default::default()
+        };
+        let model = ModelData::from_triangles(x_facing_quad());
+        let result = render_model_data(model, 32, 32, &options, &mut RenderReport::default());
+        assert!(matches!(result, Err(RenderError::Cancelled)));
+    }
+
+    #[test]
+    fn test_expired_deadline_times_out() {
+        let options = RenderOptions {
+            budget: Budget::new().with_timeout(Duration::ZERO),
+            ..Default::default()
+        };
+        let model = ModelData::from_triangles(x_facing_quad());
+        let result = render_model_data(model, 32, 32, &options, &mut RenderReport::default());
+        assert!(matches!(result, Err(RenderError::TimedOut)));
+    }
+
+    /// Returns a transform at `position` whose -Z axis looks down world -X.
+    fn facing_negative_x(position: Vec3) -> formats::Mat4 {
+        let rotation = glam::Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        Mat4::from_rotation_translation(rotation, position).to_cols_array_2d()
+    }
+
+    #[test]
+    fn test_authored_orthographic_camera_frames_view() {
+        let mut model = ModelData::from_triangles(x_facing_quad());
+        model.cameras.push(Camera {
+            name: Some("Side".to_string()),
+            transform: facing_negative_x(Vec3::new(2.0, 0.5, 0.5)),
+            projection: Projection::Orthographic {
+                ymag: 0.6,
+                znear: 0.1,
+                zfar: 10.0,
+            },
+        });
+        let coverage = |camera: Option<CameraChoice>| {
+            let options = RenderOptions {
+                camera,
+                ..Default::default()
+            };
+            let pixels = render_model_data(
+                model.clone(),
+                64,
+                64,
+                &options,
+                &mut RenderReport::default(),
+            )
+            .expect("quad should render");
+            pixels.chunks(4).filter(|p| p[3] > 0).count()
+        };
+
+        // (0.5 / 0.6)² of the frame is covered by the quad
+        let side = coverage(Some(CameraChoice::Named("Side".to_string())));
+        assert!(side > 64 * 64 * 6 / 10, "covered {side}");
+        assert_eq!(coverage(Some(CameraChoice::First)), side);
+        // Unknown cameras fall back to automatic framing
+        let auto = coverage(None);
+        assert!(auto < side);
+        assert_eq!(coverage(Some(CameraChoice::Named("Top".to_string()))), auto);
+    }
+
+    #[test]
+    fn test_scene_lights_replace_default_rig() {
+        let mut model = ModelData::from_triangles(x_facing_quad());
+        model.lights.push(Light {
+            kind: LightKind::Directional,
+            transform: facing_negative_x(Vec3::ZERO),
+            color: [1.0, 0.0, 0.0],
+            intensity: 1000.0,
+            ..Default::default()
+        });
+        let pixels = render_model_data(
+            model,
+            32,
+            32,
+            &RenderOptions::default(),
+            &mut RenderReport::default(),
+        )
+        .expect("quad should render");
+        let lit = pixels
+            .chunks(4)
+            .find(|p| p[3] > 0)
+            .expect("quad should be visible");
+        // Red light on top of the neutral ambient term
+        assert!(lit[0] > lit[1] + 100, "pixel {lit:?}");
+        assert_eq!(lit[1], lit[2]);
+    }
+
+    #[test]
+    fn test_unlit_material_keeps_base_color() {
+        let mut model = ModelData::from_triangles(x_facing_quad());
+        for material in &mut model.materials {
+            material.color = [0.8, 0.4, 0.2];
+            material.unlit = true;
+        }
+        let pixels = render_model_data(
+            model,
+            32,
+            32,
+            &RenderOptions::default(),
+            &mut RenderReport::default(),
+        )
+        .expect("quad should render");
+        let lit = pixels
+            .chunks(4)
+            .find(|p| p[3] > 0)
+            .expect("quad should be visible");
+        assert_eq!(&lit[..3], &[204, 102, 51]);
+    }
+
+    #[test]
+    fn test_vertex_normals_shade_smoothly() {
+        let render = |normals: Option<Vec<[f32; 3]>>| {
+            let mut model = ModelData::from_triangles(x_facing_quad());
+            model.meshes[0].normals = normals;
+            let pixels = render_model_data(
+                model,
+                32,
+                32,
+                &RenderOptions::default(),
+                &mut RenderReport::default(),
+            )
+            .expect("quad should render");
+            let mut colors: Vec<&[u8]> = pixels.chunks(4).filter(|p| p[3] > 0).collect();
+            colors.sort_unstable();
+            colors.dedup();
+            colors.len()
+        };
+        let vertex_count = ModelData::from_triangles(x_facing_quad()).meshes[0]
+            .positions
+            .len();
+        assert_eq!(render(None), 1);
+        // Normals fanning out from the quad's center give a gradient
+        let fanned = (0..vertex_count)
+            .map(|i| [1.0, i as f32 - 1.5, 0.5])
+            .collect();
+        assert!(render(Some(fanned)) > 4);
+        // Zero normals fall back to flat shading
+        assert_eq!(render(Some(vec![[0.0; 3]; vertex_count])), 1);
+    }
+
+    #[test]
+    fn test_lines_and_points_render_without_triangles() {
+        let mesh = Mesh {
+            positions: vec![[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [1.0, 0.0, 0.0]],
+            uvs: vec![[0.0, 0.0]; 3],
+            lines: vec![0, 1],
+            points: vec![2],
+            ..Default::default()
+        };
+        let model = ModelData {
+            meshes: vec![mesh],
+            ..Default::default()
+        };
+        let pixels = render_model_data(
+            model,
+            64,
+            64,
+            &RenderOptions::default(),
+            &mut RenderReport::default(),
+        )
+        .expect("lines and points should render");
+        let covered = pixels.chunks(4).filter(|p| p[3] > 0).count();
+        // A diagonal line plus a small splat, far from filling the frame
+        assert!(covered > 20, "covered {covered}");
+        assert!(covered < 200, "covered {covered}");
+    }
+
+    #[test]
+    fn test_cancelled_point_cloud_stops_drawing() {
+        let count = BUDGET_CHECK_INTERVAL * 4;
+        let mesh = Mesh {
+            positions: vec![[0.0; 3]; count],
+            uvs: vec![[0.0; 2]; count],
+            points: (0..count as u32).collect(),
+            ..Default::default()
+        };
+        let projected = vec![Some(Vec3::new(8.0, 8.0, 0.5)); count];
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = draw_lines_and_points(
+            &mesh,
+            &Material::default(),
+            &projected,
+            &mut [[0.0; 4]; 16 * 16],
+            &mut [f32::INFINITY; 16 * 16],
+            (16, 16),
+            &Budget::new().with_token(token),
+        );
+        assert_eq!(result, Err(Interrupted::Cancelled));
+    }
+
+    #[test]
+    fn test_clip_segment_keeps_visible_range() {
+        let a = Vec3::new(-10.0, 5.0, 0.0);
+        let b = Vec3::new(30.0, 5.0, 0.0);
+        assert_eq!(clip_segment(a, b, 20.0, 20.0), Some((0.25, 0.75)));
+        let above = Vec3::new(0.0, -5.0, 0.0);
+        assert_eq!(clip_segment(above, above + Vec3::X, 20.0, 20.0), None);
+    }
+
+    #[test]
+    fn test_expired_deadline_with_partial_renders_low_detail() {
+        let options = RenderOptions {
+            budget: Budget::new().with_timeout(Duration::ZERO),
+            partial_on_timeout: true,
+            ..Default::default()
+        };
+        let model = ModelData::from_triangles(x_facing_quad());
+        let pixels = render_model_data(model, 32, 32, &options, &mut RenderReport::default())
+            .expect("partial render");
+        assert!(pixels.chunks(4).any(|p| p[3] > 0));
+    }
+
+    #[test]
+    fn test_render_with_options_reports_unsupported_format() {
+        let options = RenderOptions::default();
+        let result = render_thumbnail_with_options(b"not a model", None, 32, 32, &options);
+        assert!(matches!(result, Err(RenderError::UnsupportedFormat)));
+    }
+
+    #[test]
+    fn test_render_with_options_rejects_zero_size() {
+        let options = RenderOptions::default();
+        let result = render_thumbnail_with_options(b"not a model", Some("obj"), 0, 32, &options);
+        assert!(matches!(result, Err(RenderError::InvalidSize)));
+    }
+
+    #[test]
+    fn test_render_reports_no_geometry() {
+        let model = ModelData::default();
+        let result = render_model_data(
+            model,
+            32,
+            32,
+            &RenderOptions::default(),
+            &mut RenderReport::default(),
+        );
+        assert!(matches!(result, Err(RenderError::NoGeometry)));
+    }
+
+    #[test]
+    fn test_render_reports_degenerate_bounds() {
+        let model = ModelData::from_triangles(vec![Triangle {
+            verts: [[1.0, 2.0, 3.0]; 3],
+            uvs: [[0.0, 0.0]; 3],
+            color: [1.0, 1.0, 1.0],
+            texture: None,
+        }]);
+        let options = RenderOptions {
+            triangle_budget: None,
+            ..Default::default()
+        };
+        let result = render_model_data(model, 32, 32, &options, &mut RenderReport::default());
+        assert!(matches!(result, Err(RenderError::DegenerateBounds)));
+    }
+
+    #[test]
+    fn test_report_counts_culled_and_degenerate_triangles() {
+        let mut triangles = x_facing_quad();
+        // Collapsed onto a line: zero area but does not shrink the bounds.
+        triangles.push(Triangle {
+            verts: [[0.0, 0.0, 0.0], [0.0, 0.5, 0.5], [0.0, 1.0, 1.0]],
+            uvs: [[0.0, 0.0]; 3],
+            color: [1.0, 1.0, 1.0],
+            texture: None,
+        });
+        let model = ModelData::from_triangles(triangles);
+        let options = RenderOptions {
+            triangle_budget: None,
+            ..Default::default()
+        };
+        let mut report = RenderReport::default();
+        render_model_data(model, 32, 32, &options, &mut report).expect("quad should render");
+
+        assert_eq!(report.triangles, 3);
+        assert_eq!(report.rendered_triangles, 3);
+        assert_eq!(report.degenerate_triangles, 1);
+        assert_eq!(report.clipped_triangles, 0);
+        assert_eq!(report.culled_triangles, 0);
+        assert_eq!(report.bounds_min, [0.0, 0.0, 0.0]);
+        assert_eq!(report.bounds_max, [0.0, 1.0, 1.0]);
+        assert!(report.coverage > 0.0);
+    }
+
+    /// Renders the +X facing quad from the default camera with a customised material.
+    ///
+    /// The default camera sits on the -X side, so the quad shows its back face.
+    fn render_quad_with(material: impl FnOnce(&mut Material)) -> (Vec<u8>, RenderReport) {
+        let mut model = ModelData::from_triangles(x_facing_quad());
+        material(&mut model.materials[0]);
+        let mut report = RenderReport::default();
+        let pixels = render_model_data(model, 32, 32, &RenderOptions::default(), &mut report)
+            .expect("quad should render");
+        (pixels, report)
+    }
+
+    #[test]
+    fn test_single_sided_back_faces_are_culled() {
+        let (pixels, report) = render_quad_with(|_| {});
+        assert_eq!(report.backface_triangles, 0);
+        assert!(pixels.chunks(4).any(|p| p[3] > 0));
+
+        let (pixels, report) = render_quad_with(|m| m.double_sided = false);
+        assert_eq!(report.backface_triangles, 2);
+        assert_eq!(report.culled_triangles, 0);
+        assert!(pixels.chunks(4).all(|p| p[3] == 0));
+    }
+
+    #[test]
+    fn test_blended_material_is_translucent() {
+        let (pixels, _) = render_quad_with(|m| {
+            m.alpha = 0.5;
+            m.alpha_mode = AlphaMode::Blend;
+        });
+        let alphas: Vec<u8> = pixels.chunks(4).map(|p| p[3]).filter(|&a| a > 0).collect();
+        assert!(!alphas.is_empty());
+        assert!(alphas.iter().all(|&a| (120..=135).contains(&a)));
+    }
+
+    #[test]
+    fn test_masked_material_discards_below_cutoff() {
+        let (pixels, _) = render_quad_with(|m| {
+            m.alpha = 0.4;
+            m.alpha_mode = AlphaMode::Mask { cutoff: 0.5 };
+        });
+        assert!(pixels.chunks(4).all(|p| p[3] == 0));
+    }
+
+    #[test]
+    fn test_emissive_material_glows_without_light() {
+        let (pixels, _) = render_quad_with(|m| {
+            m.color = [0.0, 0.0, 0.0];
+            m.emissive = [1.0, 0.0, 0.0];
+        });
+        let lit: Vec<&[u8]> = pixels.chunks(4).filter(|p| p[3] > 0).collect();
+        assert!(!lit.is_empty());
+        assert!(lit.iter().all(|p| p[0] == 255 && p[1] == 0 && p[2] == 0));
+    }
+
+    #[test]
+    fn test_vertex_colors_are_interpolated() {
+        let quad = ModelData::from_triangles(x_facing_quad());
+        let mut mesh = quad.meshes[0].clone();
+        // Red along z = 0, blue along z = 1
+        mesh.colors = Some(
+            mesh.positions
+                .iter()
+                .map(|p| [1.0 - p[2], 0.0, p[2]])
+                .collect(),
+        );
+        let model = ModelData {
+            meshes: vec![mesh],
+            ..Default::default()
+        };
+        let pixels = render_model_data(
+            model,
+            64,
+            64,
+            &RenderOptions::default(),
+            &mut RenderReport::default(),
+        )
+        .expect("quad should render");
+
+        let lit: Vec<&[u8]> = pixels.chunks(4).filter(|p| p[3] > 0).collect();
+        // A blend of both colors shows up between the red and blue edges
+        let red = |p: &[u8]| u32::from(p[0]);
+        let blue = |p: &[u8]| u32::from(p[2]);
+        assert!(lit.iter().any(|p| red(p) > 4 * blue(p).max(1)));
+        assert!(lit.iter().any(|p| blue(p) > 4 * red(p).max(1)));
+        assert!(lit
+            .iter()
+            .any(|p| red(p) > 20 && blue(p) > 20 && red(p).abs_diff(blue(p)) < 20));
+    }
+
     #[test]
     fn test_render_output_dimensions() {
         let width = 128;
@@ -13,8 +13,13 @@
 //! ```
 
 use std::path::Path;
+use std::sync::Arc;
 
-use glimpse::formats::{self, FormatLoader, LoadError};
+use glimpse::formats::resolver::MemoryResolver;
+use glimpse::formats::{
+    self, AnimationPose, FormatLoader, LightKind, LoadError, LoadOptions, Projection, RenderMode,
+    SceneChoice,
+};
 use glimpse::renderer;
 
 /// Helper to save RGBA pixels as PNG for visual inspection
@@ -86,6 +91,731 @@ fn test_vintagestory_loader_rejects_non_vs_json() {
 // Loader trait tests
 // ===========================================================================
 
+// ===========================================================================
+// glTF parsing tests (synthetic data)
+// ===========================================================================
+
+#[test]
+fn test_gltf_applies_khr_texture_transform() {
+    // One triangle with an unused TEXCOORD_0 and the atlas UVs in TEXCOORD_1
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "extensionsUsed": ["KHR_texture_transform"],
+        "buffers": [{
+            "byteLength": 84,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/"
+        }],
+        "bufferViews": [
+            {"buffer": 0, "byteOffset": 0, "byteLength": 36},
+            {"buffer": 0, "byteOffset": 36, "byteLength": 24},
+            {"buffer": 0, "byteOffset": 60, "byteLength": 24}
+        ],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+             "min": [0, 0, 0], "max": [1, 1, 0]},
+            {"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC2"},
+            {"bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC2"}
+        ],
+        "images": [{"uri": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAAEElEQVR4AQEFAPr/AP////8J+wP9o9FJCgAAAABJRU5ErkJggg=="}],
+        "textures": [{"source": 0}],
+        "materials": [{
+            "pbrMetallicRoughness": {
+                "baseColorTexture": {
+                    "index": 0,
+                    "extensions": {
+                        "KHR_texture_transform": {
+                            "offset": [0.5, 0.0],
+                            "rotation": 1.5707964,
+                            "scale": [0.5, 0.5],
+                            "texCoord": 1
+                        }
+                    }
+                }
+            }
+        }],
+        "meshes": [{"primitives": [{
+            "attributes": {"POSITION": 0, "TEXCOORD_0": 1, "TEXCOORD_1": 2},
+            "material": 0
+        }]}],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("glTF should load");
+
+    let expected = [[0.5, 0.0], [0.5, -0.5], [1.0, 0.0]];
+    for (uv, want) in model.meshes[0].uvs.iter().zip(expected) {
+        assert!(
+            (uv[0] - want[0]).abs() < 1e-5 && (uv[1] - want[1]).abs() < 1e-5,
+            "expected {want:?}, got {uv:?}"
+        );
+    }
+}
+
+#[test]
+fn test_gltf_poses_skinned_mesh_at_animation_time() {
+    // One triangle fully weighted to a single joint whose translation animates
+    // from the origin to y = 2 over one second
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{
+            "byteLength": 128,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAAAAAAAAQAAAAAA="
+        }],
+        "bufferViews": [
+            {"buffer": 0, "byteOffset": 0, "byteLength": 36},
+            {"buffer": 0, "byteOffset": 36, "byteLength": 12},
+            {"buffer": 0, "byteOffset": 48, "byteLength": 48},
+            {"buffer": 0, "byteOffset": 96, "byteLength": 8},
+            {"buffer": 0, "byteOffset": 104, "byteLength": 24}
+        ],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+             "min": [0, 0, 0], "max": [1, 1, 0]},
+            {"bufferView": 1, "componentType": 5121, "count": 3, "type": "VEC4"},
+            {"bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC4"},
+            {"bufferView": 3, "componentType": 5126, "count": 2, "type": "SCALAR",
+             "min": [0], "max": [1]},
+            {"bufferView": 4, "componentType": 5126, "count": 2, "type": "VEC3"}
+        ],
+        "meshes": [{"primitives": [{
+            "attributes": {"POSITION": 0, "JOINTS_0": 1, "WEIGHTS_0": 2}
+        }]}],
+        "skins": [{"joints": [1]}],
+        "animations": [{
+            "name": "Rise",
+            "channels": [{"sampler": 0, "target": {"node": 1, "path": "translation"}}],
+            "samplers": [{"input": 3, "output": 4}]
+        }],
+        "nodes": [
+            {"mesh": 0, "skin": 0, "translation": [10, 0, 0]},
+            {"name": "joint"}
+        ],
+        "scenes": [{"nodes": [0, 1]}]
+    }"#;
+
+    let min_y = |time: f32| {
+        let options = LoadOptions {
+            animation: Some(AnimationPose {
+                name: Some("Rise".into()),
+                time,
+            }),
+            ..Default::default()
+        };
+        let model = formats::gltf::GltfLoader
+            .load_from_bytes_with_options(gltf, &options)
+            .expect("glTF should load");
+        // The skinned mesh follows its joint, not its own node's translation
+        assert!(model.meshes[0].positions.iter().all(|p| p[0] < 2.0));
+        model.meshes[0]
+            .positions
+            .iter()
+            .map(|p| p[1])
+            .fold(f32::INFINITY, f32::min)
+    };
+
+    assert!((min_y(0.0) - 0.0).abs() < 1e-5);
+    assert!((min_y(0.5) - 1.0).abs() < 1e-5);
+    // Times past the last keyframe hold its value
+    assert!((min_y(5.0) - 2.0).abs() < 1e-5);
+
+    let missing = LoadOptions {
+        animation: Some(AnimationPose {
+            name: Some("Walk".into()),
+            time: 0.0,
+        }),
+        ..Default::default()
+    };
+    assert!(matches!(
+        formats::gltf::GltfLoader.load_from_bytes_with_options(gltf, &missing),
+        Err(LoadError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_gltf_keeps_sheared_static_node_matrix() {
+    // A static node whose matrix shears x by half of y; TRS cannot express this
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{
+            "byteLength": 36,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+        }],
+        "bufferViews": [{"buffer": 0, "byteOffset": 0, "byteLength": 36}],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+             "min": [0, 0, 0], "max": [1, 1, 0]}
+        ],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0}}]}],
+        "nodes": [{
+            "mesh": 0,
+            "matrix": [1, 0, 0, 0, 0.5, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1]
+        }],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("glTF should load");
+
+    let top = model.meshes[0]
+        .positions
+        .iter()
+        .find(|p| p[1] > 0.5)
+        .expect("top vertex");
+    assert!((top[0] - 0.5).abs() < 1e-5, "top vertex at {top:?}");
+}
+
+#[test]
+fn test_gltf_applies_morph_target_weights() {
+    // One triangle with a single morph target that lifts every vertex by z = 2;
+    // the default weight is 0.5 and the animation ramps it from 0 to 1
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{
+            "byteLength": 88,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAABAAAAAAAAAAAAAAABAAAAAAAAAAAAAAABAAAAAAAAAgD8AAAAAAACAPw=="
+        }],
+        "bufferViews": [
+            {"buffer": 0, "byteOffset": 0, "byteLength": 36},
+            {"buffer": 0, "byteOffset": 36, "byteLength": 36},
+            {"buffer": 0, "byteOffset": 72, "byteLength": 8},
+            {"buffer": 0, "byteOffset": 80, "byteLength": 8}
+        ],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+             "min": [0, 0, 0], "max": [1, 1, 0]},
+            {"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3",
+             "min": [0, 0, 2], "max": [0, 0, 2]},
+            {"bufferView": 2, "componentType": 5126, "count": 2, "type": "SCALAR",
+             "min": [0], "max": [1]},
+            {"bufferView": 3, "componentType": 5126, "count": 2, "type": "SCALAR"}
+        ],
+        "meshes": [{
+            "primitives": [{"attributes": {"POSITION": 0}, "targets": [{"POSITION": 1}]}],
+            "weights": [0.5]
+        }],
+        "animations": [{
+            "channels": [{"sampler": 0, "target": {"node": 0, "path": "weights"}}],
+            "samplers": [{"input": 2, "output": 3}]
+        }],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let z_at = |animation: Option<AnimationPose>| {
+        let options = LoadOptions {
+            animation,
+            ..Default::default()
+        };
+        let model = formats::gltf::GltfLoader
+            .load_from_bytes_with_options(gltf, &options)
+            .expect("glTF should load");
+        model.meshes[0].positions[0][2]
+    };
+
+    // Default mesh weights apply without an animation
+    assert!((z_at(None) - 1.0).abs() < 1e-5);
+    // Animated weights override them
+    let at = |time| Some(AnimationPose { name: None, time });
+    assert!((z_at(at(0.0)) - 0.0).abs() < 1e-5);
+    assert!((z_at(at(0.25)) - 0.5).abs() < 1e-5);
+    assert!((z_at(at(1.0)) - 2.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_gltf_morphs_and_transforms_vertex_normals() {
+    // Normals point along +z; a half-weighted morph target tilts them towards +x,
+    // and the node's non-uniform scale must not skew them away from the surface
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{
+            "byteLength": 108,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AACAPwAAAAAAAIC/AACAPwAAAAAAAIC/AACAPwAAAAAAAIC/"
+        }],
+        "bufferViews": [
+            {"buffer": 0, "byteOffset": 0, "byteLength": 36},
+            {"buffer": 0, "byteOffset": 36, "byteLength": 36},
+            {"buffer": 0, "byteOffset": 72, "byteLength": 36}
+        ],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+             "min": [0, 0, 0], "max": [1, 1, 0]},
+            {"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3"},
+            {"bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC3"}
+        ],
+        "meshes": [{
+            "primitives": [{
+                "attributes": {"POSITION": 0, "NORMAL": 1},
+                "targets": [{"NORMAL": 2}]
+            }],
+            "weights": [0.5]
+        }],
+        "nodes": [{"mesh": 0, "scale": [2, 1, 1]}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("glTF should load");
+
+    // (0.5, 0, 0.5) through the inverse-transpose of the scale is (0.25, 0, 0.5)
+    let normals = model.meshes[0].normals.as_ref().expect("normals");
+    for n in normals {
+        let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        let unit = n.map(|c| c / len);
+        let want = [1.0 / 5f32.sqrt(), 0.0, 2.0 / 5f32.sqrt()];
+        assert!(
+            unit.iter().zip(want).all(|(a, b)| (a - b).abs() < 1e-5),
+            "normal {n:?}"
+        );
+    }
+}
+
+#[test]
+fn test_gltf_decodes_meshopt_compressed_quantized_geometry() {
+    // 16-bit integer positions and u16 indices, both stored in meshopt-compressed
+    // views that decode into an otherwise empty fallback buffer
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "extensionsUsed": ["EXT_meshopt_compression", "KHR_mesh_quantization"],
+        "extensionsRequired": ["EXT_meshopt_compression", "KHR_mesh_quantization"],
+        "buffers": [
+            {
+                "byteLength": 190,
+                "uri": "data:application/octet-stream;base64,oP8ABAMAAAAAAAAAAAAAAAAA/wAAAAAAAAAAAAAAAAAAAAD/AAAEAAAAAAAAAAAAAAAAAP8AAAAAAAAAAAAAAAAAAAAA/wAAAAAAAAAAAAAAAAAAAAD/AAAAAAAAAAAAAAAAAAAAAP8AAAAAAAAAAAAAAAAAAAAA/wAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAOHwAHaHVmd4qYZliWiYAWkAAA=="
+            },
+            {"byteLength": 32, "extensions": {"EXT_meshopt_compression": {"fallback": true}}}
+        ],
+        "bufferViews": [
+            {"buffer": 1, "byteOffset": 0, "byteLength": 24, "byteStride": 8,
+             "extensions": {"EXT_meshopt_compression": {
+                 "buffer": 0, "byteOffset": 0, "byteLength": 169,
+                 "byteStride": 8, "count": 3, "mode": "ATTRIBUTES"}}},
+            {"buffer": 1, "byteOffset": 24, "byteLength": 6,
+             "extensions": {"EXT_meshopt_compression": {
+                 "buffer": 0, "byteOffset": 172, "byteLength": 18,
+                 "byteStride": 2, "count": 3, "mode": "TRIANGLES"}}}
+        ],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5122, "count": 3, "type": "VEC3",
+             "min": [0, 0, 0], "max": [2, 2, 0]},
+            {"bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR"}
+        ],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0}, "indices": 1}]}],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("meshopt glTF should load");
+
+    assert_eq!(model.meshes[0].indices, [0, 1, 2]);
+    assert_eq!(
+        model.meshes[0].positions,
+        [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 2.0, 0.0]]
+    );
+}
+
+#[cfg(not(feature = "draco"))]
+#[test]
+fn test_gltf_rejects_required_draco_compression() {
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "extensionsUsed": ["KHR_draco_mesh_compression"],
+        "extensionsRequired": ["KHR_draco_mesh_compression"],
+        "scenes": [{"nodes": []}]
+    }"#;
+
+    match formats::gltf::GltfLoader.load_from_bytes(gltf) {
+        Err(LoadError::InvalidData(msg)) => assert!(msg.contains("KHR_draco_mesh_compression")),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("Draco files need the draco feature"),
+    }
+}
+
+#[cfg(feature = "draco")]
+#[test]
+fn test_gltf_decodes_draco_compression() {
+    // An edgebreaker-coded unit quad whose UVs equal its XY positions, with
+    // one byte colour per corner; the accessors have no data of their own
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "extensionsUsed": ["KHR_draco_mesh_compression"],
+        "extensionsRequired": ["KHR_draco_mesh_compression"],
+        "buffers": [{
+            "byteLength": 162,
+            "uri": "data:application/octet-stream;base64,RFJBQ08CAgEBAAAABAICAgAAAR//ARH/ARH/ARED/wAAAAAAAQAAAQAJAwAAAgEDCQIAAQIBAgIDAQIBAQEBAAMDATABEAMAKIKYAAAAAAD/AAAAAAAAAAAAAAAAAAAAAACAPwgBAQEAAwMBMAEQAwAogiwAAAAAAP8AAAAAAAAAAAAAAAAAgD8IAQEBAAMDATABEAMAJIKRJgAAAAD/AAAA"
+        }],
+        "bufferViews": [{"buffer": 0, "byteLength": 162}],
+        "accessors": [
+            {"componentType": 5126, "count": 4, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0]},
+            {"componentType": 5126, "count": 4, "type": "VEC2"},
+            {"componentType": 5121, "normalized": true, "count": 4, "type": "VEC3"},
+            {"componentType": 5123, "count": 6, "type": "SCALAR"}
+        ],
+        "meshes": [{"primitives": [{
+            "attributes": {"POSITION": 0, "TEXCOORD_0": 1, "COLOR_0": 2},
+            "indices": 3,
+            "extensions": {"KHR_draco_mesh_compression": {
+                "bufferView": 0,
+                "attributes": {"POSITION": 0, "TEXCOORD_0": 1, "COLOR_0": 2}
+            }}
+        }]}],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("Draco glTF should load");
+
+    let mesh = &model.meshes[0];
+    assert_eq!(mesh.indices.len(), 6);
+    assert_eq!(mesh.positions.len(), 4);
+    let colors = mesh.colors.as_ref().expect("colours should be decoded");
+    for &i in &mesh.indices {
+        let [x, y, z] = mesh.positions[i as usize];
+        assert_eq!(z, 0.0);
+        assert_eq!(mesh.uvs[i as usize], [x, y]);
+        let expected = match (x as u8, y as u8) {
+            (0, 0) => [1.0, 0.0, 0.0],
+            (1, 0) => [0.0, 1.0, 0.0],
+            (1, 1) => [0.0, 0.0, 1.0],
+            _ => [1.0, 1.0, 1.0],
+        };
+        assert_eq!(colors[i as usize], expected);
+    }
+}
+
+#[test]
+fn test_gltf_expands_strips_fans_lines_and_points() {
+    // One unit square drawn as a strip, a fan, a line loop and points
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{
+            "byteLength": 48,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAACAPwAAgD8AAAAA"
+        }],
+        "bufferViews": [{"buffer": 0, "byteLength": 48}],
+        "accessors": [{"bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3",
+                       "min": [0, 0, 0], "max": [1, 1, 0]}],
+        "meshes": [{"primitives": [
+            {"attributes": {"POSITION": 0}, "mode": 5},
+            {"attributes": {"POSITION": 0}, "mode": 6},
+            {"attributes": {"POSITION": 0}, "mode": 2},
+            {"attributes": {"POSITION": 0}, "mode": 0}
+        ]}],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("glTF should load");
+    assert_eq!(model.meshes.len(), 4);
+    assert_eq!(model.meshes[0].indices, vec![0, 1, 2, 1, 3, 2]);
+    assert_eq!(model.meshes[1].indices, vec![1, 2, 0, 2, 3, 0]);
+    assert_eq!(model.meshes[2].lines, vec![0, 1, 1, 2, 2, 3, 3, 0]);
+    assert_eq!(model.meshes[3].points, vec![0, 1, 2, 3]);
+    assert_eq!(model.triangle_count(), 4);
+}
+
+#[test]
+fn test_gltf_loads_cameras_and_punctual_lights() {
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "extensionsUsed": ["KHR_lights_punctual"],
+        "extensions": {"KHR_lights_punctual": {"lights": [
+            {"type": "spot", "color": [1, 0.5, 0], "intensity": 40, "range": 10,
+             "spot": {"innerConeAngle": 0.2, "outerConeAngle": 0.6}}
+        ]}},
+        "buffers": [{
+            "byteLength": 36,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+        }],
+        "bufferViews": [{"buffer": 0, "byteLength": 36}],
+        "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                       "min": [0, 0, 0], "max": [1, 1, 0]}],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0}}]}],
+        "cameras": [
+            {"type": "perspective", "perspective": {"yfov": 0.7, "znear": 0.05}},
+            {"type": "orthographic", "name": "Plan",
+             "orthographic": {"xmag": 2, "ymag": 1.5, "znear": 0.1, "zfar": 50}}
+        ],
+        "nodes": [
+            {"mesh": 0, "children": [1, 2, 3]},
+            {"name": "Hero", "camera": 0, "translation": [0, 0, 5]},
+            {"camera": 1},
+            {"name": "Key", "translation": [1, 2, 3],
+             "extensions": {"KHR_lights_punctual": {"light": 0}}}
+        ],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("glTF should load");
+    assert_eq!(model.cameras.len(), 2);
+    assert_eq!(model.cameras[0].name.as_deref(), Some("Hero"));
+    assert_eq!(model.cameras[0].transform[3], [0.0, 0.0, 5.0, 1.0]);
+    assert_eq!(
+        model.cameras[0].projection,
+        Projection::Perspective {
+            yfov: 0.7,
+            znear: 0.05,
+            zfar: None
+        }
+    );
+    assert_eq!(model.cameras[1].name.as_deref(), Some("Plan"));
+    assert!(matches!(
+        model.cameras[1].projection,
+        Projection::Orthographic { ymag: 1.5, .. }
+    ));
+
+    assert_eq!(model.lights.len(), 1);
+    let light = &model.lights[0];
+    assert_eq!(light.name.as_deref(), Some("Key"));
+    assert_eq!(light.transform[3], [1.0, 2.0, 3.0, 1.0]);
+    assert_eq!(light.color, [1.0, 0.5, 0.0]);
+    assert_eq!(light.intensity, 40.0);
+    assert_eq!(light.range, Some(10.0));
+    assert_eq!(
+        light.kind,
+        LightKind::Spot {
+            inner_cone_angle: 0.2,
+            outer_cone_angle: 0.6
+        }
+    );
+}
+
+#[test]
+fn test_gltf_selects_scene_and_material_variant() {
+    // Scene 1 ("Scaled") doubles the triangle; the "Red" variant swaps its material
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "extensionsUsed": ["KHR_materials_variants"],
+        "extensions": {"KHR_materials_variants": {"variants": [{"name": "Blue"}, {"name": "Red"}]}},
+        "buffers": [{
+            "byteLength": 36,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+        }],
+        "bufferViews": [{"buffer": 0, "byteLength": 36}],
+        "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                       "min": [0, 0, 0], "max": [1, 1, 0]}],
+        "materials": [
+            {"name": "white"},
+            {"name": "red", "pbrMetallicRoughness": {"baseColorFactor": [1, 0, 0, 1]}}
+        ],
+        "meshes": [{"primitives": [{
+            "attributes": {"POSITION": 0},
+            "material": 0,
+            "extensions": {"KHR_materials_variants": {"mappings": [{"material": 1, "variants": [1]}]}}
+        }]}],
+        "nodes": [{"mesh": 0}, {"mesh": 0, "scale": [2, 2, 2]}],
+        "scene": 0,
+        "scenes": [{"name": "Plain", "nodes": [0]}, {"name": "Scaled", "nodes": [1]}]
+    }"#;
+
+    let load = |scene: Option<SceneChoice>, variant: Option<&str>| {
+        let options = LoadOptions {
+            scene,
+            variant: variant.map(str::to_owned),
+            ..Default::default()
+        };
+        formats::gltf::GltfLoader.load_from_bytes_with_options(gltf, &options)
+    };
+    let width = |model: &formats::ModelData| model.meshes[0].positions[1][0];
+    let material = |model: &formats::ModelData| {
+        model
+            .material(model.meshes[0].material)
+            .name
+            .clone()
+            .unwrap()
+    };
+
+    let default = load(None, None).expect("default scene should load");
+    assert_eq!(width(&default), 1.0);
+    assert_eq!(material(&default), "white");
+    let by_index = load(Some(SceneChoice::Index(1)), None).expect("scene 1 should load");
+    assert_eq!(width(&by_index), 2.0);
+    let by_name = load(Some(SceneChoice::Named("Scaled".to_string())), Some("Red"))
+        .expect("named scene and variant should load");
+    assert_eq!(width(&by_name), 2.0);
+    assert_eq!(material(&by_name), "red");
+    // Variants without a mapping keep the default material
+    assert_eq!(material(&load(None, Some("Blue")).unwrap()), "white");
+
+    assert!(matches!(
+        load(Some(SceneChoice::Index(2)), None),
+        Err(LoadError::InvalidData(_))
+    ));
+    assert!(matches!(
+        load(None, Some("Green")),
+        Err(LoadError::InvalidData(_))
+    ));
+
+    let contents = formats::gltf::contents(gltf).expect("contents should parse");
+    assert_eq!(
+        contents.scenes,
+        vec![Some("Plain".to_string()), Some("Scaled".to_string())]
+    );
+    assert_eq!(contents.default_scene, Some(0));
+    assert_eq!(
+        contents.variants,
+        vec!["Blue".to_string(), "Red".to_string()]
+    );
+}
+
+#[test]
+fn test_gltf_reads_unlit_and_emissive_strength() {
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "extensionsUsed": ["KHR_materials_unlit", "KHR_materials_emissive_strength"],
+        "buffers": [{
+            "byteLength": 36,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+        }],
+        "bufferViews": [{"buffer": 0, "byteLength": 36}],
+        "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                       "min": [0, 0, 0], "max": [1, 1, 0]}],
+        "materials": [
+            {"extensions": {"KHR_materials_unlit": {}}},
+            {"emissiveFactor": [0.5, 0.25, 0],
+             "extensions": {"KHR_materials_emissive_strength": {"emissiveStrength": 4}}}
+        ],
+        "meshes": [{"primitives": [
+            {"attributes": {"POSITION": 0}, "material": 0},
+            {"attributes": {"POSITION": 0}, "material": 1}
+        ]}],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("glTF should load");
+    assert!(model.materials[0].unlit);
+    assert!(!model.materials[1].unlit);
+    assert_eq!(model.materials[1].emissive, [2.0, 1.0, 0.0]);
+}
+
+#[test]
+fn test_gltf_bytes_read_external_files_through_resolver() {
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{"byteLength": 36, "uri": "mesh%20data/triangle.bin"}],
+        "bufferViews": [{"buffer": 0, "byteLength": 36}],
+        "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                       "min": [0, 0, 0], "max": [1, 1, 0]}],
+        "images": [{"uri": "red.png"}],
+        "textures": [{"source": 0}],
+        "materials": [{"pbrMetallicRoughness": {"baseColorTexture": {"index": 0}}}],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0}, "material": 0}]}],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+    let positions: Vec<u8> = [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+    let mut png = Vec::new();
+    image::RgbaImage::from_pixel(1, 1, image::Rgba([255, 0, 0, 255]))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+
+    // Without a resolver the external buffer is out of reach
+    assert!(formats::gltf::GltfLoader.load_from_bytes(gltf).is_err());
+
+    let resolver = MemoryResolver::new()
+        .with_file("mesh data/triangle.bin", positions)
+        .with_file("red.png", png);
+    let options = LoadOptions {
+        resolver: Some(Arc::new(resolver)),
+        ..Default::default()
+    };
+    let model = formats::load_model_with_options(gltf, None, &options).expect("glTF should load");
+    assert_eq!(model.triangle_count(), 1);
+    let texture = model.materials[0].texture.as_ref().expect("external image");
+    assert_eq!(texture.data, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_render_gltf_point_cloud() {
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "buffers": [{
+            "byteLength": 48,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAACAPwAAgD8AAAAA"
+        }],
+        "bufferViews": [{"buffer": 0, "byteLength": 48}],
+        "accessors": [{"bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3",
+                       "min": [0, 0, 0], "max": [1, 1, 0]}],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0}, "mode": 0}]}],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let pixels =
+        renderer::render_thumbnail(gltf, Some("gltf"), 64, 64).expect("point clouds should render");
+    assert!(pixels.chunks(4).any(|p| p[3] > 0));
+}
+
+#[test]
+#[cfg(feature = "ktx2")]
+fn test_gltf_prefers_decodable_extension_texture_source() {
+    // Texture 0 prefers a blue KTX2 image over its red PNG fallback; texture 1
+    // points its WebP source at bytes that do not decode and falls back to PNG
+    let gltf = br#"{
+        "asset": {"version": "2.0"},
+        "extensionsUsed": ["KHR_texture_basisu", "EXT_texture_webp"],
+        "buffers": [{
+            "byteLength": 36,
+            "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+        }],
+        "bufferViews": [{"buffer": 0, "byteLength": 36}],
+        "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                       "min": [0, 0, 0], "max": [1, 1, 0]}],
+        "images": [
+            {"uri": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR4nGP4z8DwHwAFAAH/iZk9HQAAAABJRU5ErkJggg=="},
+            {"uri": "data:image/ktx2;base64,q0tUWCAyMLsNChoKJQAAAAEAAAABAAAAAQAAAAAAAAAAAAAAAQAAAAEAAAAAAAAAaAAAAAQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABsAAAAAAAAAAQAAAAAAAAABAAAAAAAAAAEAAAAAAD//w=="},
+            {"uri": "data:image/webp;base64,AAAAAA=="}
+        ],
+        "textures": [
+            {"source": 0, "extensions": {"KHR_texture_basisu": {"source": 1}}},
+            {"source": 0, "extensions": {"EXT_texture_webp": {"source": 2}}}
+        ],
+        "materials": [
+            {"pbrMetallicRoughness": {"baseColorTexture": {"index": 0}}},
+            {"pbrMetallicRoughness": {"baseColorTexture": {"index": 1}}}
+        ],
+        "meshes": [{"primitives": [
+            {"attributes": {"POSITION": 0}, "material": 0},
+            {"attributes": {"POSITION": 0}, "material": 1}
+        ]}],
+        "nodes": [{"mesh": 0}],
+        "scenes": [{"nodes": [0]}]
+    }"#;
+
+    let model = formats::gltf::GltfLoader
+        .load_from_bytes(gltf)
+        .expect("glTF should load");
+    let pixel = |mesh: usize| {
+        let material = model.material(model.meshes[mesh].material);
+        material
+            .texture
+            .as_ref()
+            .expect("texture should decode")
+            .data[..4]
+            .to_vec()
+    };
+    assert_eq!(pixel(0), vec![0, 0, 255, 255]);
+    assert_eq!(pixel(1), vec![255, 0, 0, 255]);
+}
+
 // ===========================================================================
 // Blockbench parsing tests (synthetic data)
 // ===========================================================================
@@ -127,7 +857,157 @@ fn test_bbmodel_parse_simple_cube() {
 
     let model = result.unwrap();
     // One cube = 6 faces = 12 triangles
-    assert_eq!(model.triangles.len(), 12);
+    assert_eq!(model.triangle_count(), 12);
+}
+
+#[test]
+fn test_bbmodel_texture_render_settings_become_materials() {
+    let bbmodel = br#"{
+        "meta": {"format_version": "4.0"},
+        "resolution": {"width": 16, "height": 16},
+        "textures": [{
+            "name": "glow.png",
+            "source": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAAEElEQVR4AQEFAPr/AP////8J+wP9o9FJCgAAAABJRU5ErkJggg==",
+            "render_mode": "additive",
+            "render_sides": "front"
+        }],
+        "elements": [{
+            "from": [0, 0, 0],
+            "to": [16, 16, 16],
+            "faces": {
+                "north": {"uv": [0, 0, 16, 16], "texture": 0},
+                "south": {"uv": [0, 0, 16, 16], "texture": 0}
+            }
+        }]
+    }"#;
+
+    let model = formats::bbmodel::BbmodelLoader
+        .load_from_bytes(bbmodel)
+        .expect("bbmodel should load");
+    assert_eq!(model.meshes.len(), 1);
+
+    let textured = model.material(model.meshes[0].material);
+    assert!(textured.texture.is_some());
+    assert_eq!(textured.render_mode, RenderMode::Additive);
+    assert!(!textured.double_sided);
+    assert_eq!(textured.name.as_deref(), Some("glow.png"));
+}
+
+#[test]
+fn test_bbmodel_outliner_groups_become_nodes() {
+    let bbmodel = br#"{
+        "meta": {"format_version": "4.0"},
+        "elements": [{
+            "name": "hat",
+            "uuid": "e1",
+            "from": [0, 0, 0],
+            "to": [16, 16, 16],
+            "faces": {"up": {"uv": [0, 0, 16, 16], "texture": 0}}
+        }],
+        "outliner": [{
+            "name": "head",
+            "uuid": "g1",
+            "origin": [8, 0, 8],
+            "rotation": [0, 90, 0],
+            "children": ["e1"]
+        }]
+    }"#;
+
+    let model = formats::bbmodel::BbmodelLoader
+        .load_from_bytes(bbmodel)
+        .expect("bbmodel should load");
+
+    let head = model.find_node("head").expect("group node");
+    let hat = model.find_node("hat").expect("element node");
+    assert_eq!(model.nodes[hat].parent, Some(head));
+    assert_eq!(model.node_meshes(head), vec![0]);
+    assert_eq!(model.nodes[hat].meshes, 0..1);
+    assert_ne!(
+        model.nodes[head].transform,
+        formats::Node::default().transform
+    );
+}
+
+#[test]
+fn test_bbmodel_mesh_elements_are_triangulated() {
+    // A flat 16x16 quad whose corners are stored crosswise, as Blockbench does
+    // for quads built from two triangles, plus a hidden triangle
+    let mesh_json = |rotation: &str| {
+        format!(
+            r#"{{
+                "meta": {{"format_version": "4.5"}},
+                "resolution": {{"width": 16, "height": 16}},
+                "textures": [{{"name": "skin", "source": ""}}],
+                "elements": [{{
+                    "name": "plate",
+                    "type": "mesh",
+                    "uuid": "m1",
+                    "origin": [8, 0, 8],
+                    "vertices": {{
+                        "a": [-8, 0, -8], "b": [8, 0, -8], "c": [-8, 0, 8], "d": [8, 0, 8]
+                    }},
+                    "faces": {{
+                        "f1": {{
+                            "vertices": ["a", "b", "c", "d"],
+                            "uv": {{"a": [0, 0], "b": [16, 0], "c": [0, 16], "d": [16, 16]}},
+                            "texture": 0
+                        }},
+                        "f2": {{"vertices": ["a", "b", "c"], "uv": {{}}, "texture": null}}
+                    }}
+                }}],
+                "outliner": [{{
+                    "name": "tilt",
+                    "uuid": "g1",
+                    "origin": [0, 0, 0],
+                    "rotation": {rotation},
+                    "children": ["m1"]
+                }}]
+            }}"#
+        )
+    };
+
+    let model = formats::bbmodel::BbmodelLoader
+        .load_from_bytes(mesh_json("[0, 0, 0]").as_bytes())
+        .expect("bbmodel should load");
+    assert_eq!(model.triangle_count(), 2);
+    let mesh = &model.meshes[0];
+    assert_eq!(model.materials[mesh.material].name.as_deref(), Some("skin"));
+
+    // The triangles share a diagonal of the quad rather than crossing over it
+    let corners = |t: usize| -> Vec<[f32; 3]> {
+        mesh.indices[t * 3..t * 3 + 3]
+            .iter()
+            .map(|&i| mesh.positions[i as usize])
+            .collect()
+    };
+    let first = corners(0);
+    let shared: Vec<[f32; 3]> = corners(1)
+        .into_iter()
+        .filter(|p| first.contains(p))
+        .collect();
+    assert_eq!(shared.len(), 2);
+    assert_ne!(shared[0][0], shared[1][0]);
+    assert_ne!(shared[0][2], shared[1][2]);
+    // Per-corner UVs follow the vertices (the model is turned to face the camera)
+    let corner = mesh
+        .positions
+        .iter()
+        .position(|p| *p == [1.0, 0.0, 1.0])
+        .unwrap();
+    assert_eq!(mesh.uvs[corner], [0.0, 0.0]);
+
+    // The group rotation stands the quad up in the XY plane
+    let model = formats::bbmodel::BbmodelLoader
+        .load_from_bytes(mesh_json("[90, 0, 0]").as_bytes())
+        .expect("bbmodel should load");
+    let positions = &model.meshes[0].positions;
+    assert!(positions
+        .iter()
+        .all(|p| (p[2] - positions[0][2]).abs() < 1e-5));
+    let heights: Vec<f32> = positions.iter().map(|p| p[1]).collect();
+    let span = heights.iter().cloned().fold(f32::MIN, f32::max)
+        - heights.iter().cloned().fold(f32::MAX, f32::min);
+    assert!((span - 1.0).abs() < 1e-5);
 }
 
 #[test]
@@ -142,6 +1022,23 @@ fn test_bbmodel_empty_elements() {
     assert!(matches!(result, Err(LoadError::NoGeometry)));
 }
 
+#[test]
+fn test_render_bbmodel_without_elements_reports_no_geometry() {
+    let bbmodel = br#"{
+        "meta": {"format_version": "4.0"},
+        "elements": []
+    }"#;
+
+    let result = renderer::render_thumbnail_with_options(
+        bbmodel,
+        Some("bbmodel"),
+        64,
+        64,
+        &renderer::RenderOptions::default(),
+    );
+    assert!(matches!(result, Err(renderer::RenderError::NoGeometry)));
+}
+
 // ===========================================================================
 // Vintage Story parsing tests (synthetic data)
 // ===========================================================================
@@ -173,7 +1070,7 @@ fn test_vintagestory_parse_simple_cube() {
 
     let model = result.unwrap();
     // One cube = 6 faces = 12 triangles
-    assert_eq!(model.triangles.len(), 12);
+    assert_eq!(model.triangle_count(), 12);
 }
 
 #[test]
@@ -225,6 +1122,303 @@ fn test_vintagestory_with_rotation() {
     );
 }
 
+#[test]
+fn test_vintagestory_textures_resolve_through_resolver() {
+    let vs_model = br##"{
+        textures: { wood: "block/crate", iron: "game:block/iron", cloth: "block/cloth" },
+        elements: [
+            { from: [0, 0, 0], to: [16, 16, 16], faces: { north: { texture: "#wood" } } },
+            { from: [0, 0, 0], to: [8, 8, 8], faces: { south: { texture: "#iron" } } },
+            { from: [0, 0, 0], to: [4, 4, 4], faces: { east: { texture: "#cloth" } } }
+        ]
+    }"##;
+    let png = |rgb: [u8; 3]| {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(1, 1, image::Rgba([rgb[0], rgb[1], rgb[2], 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        png
+    };
+
+    // The model's own domain, a `game:` override, and a fallback domain
+    let resolver = MemoryResolver::for_model("assets/mymod/shapes/block/crate.json")
+        .with_file("assets/mymod/textures/block/crate.png", png([255, 0, 0]))
+        .with_file("assets/game/textures/block/iron.png", png([0, 255, 0]))
+        .with_file("assets/survival/textures/block/cloth.png", png([0, 0, 255]));
+    let options = LoadOptions {
+        resolver: Some(Arc::new(resolver)),
+        ..Default::default()
+    };
+    let model = formats::vintagestory::VintageStoryLoader
+        .load_from_bytes_with_options(vs_model, &options)
+        .expect("VS model should load");
+
+    let mut colors: Vec<[f32; 4]> = model
+        .meshes
+        .iter()
+        .map(|mesh| {
+            let texture = model.materials[mesh.material]
+                .texture
+                .as_ref()
+                .expect("face texture should resolve");
+            texture.sample(0.5, 0.5)
+        })
+        .collect();
+    colors.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    assert_eq!(
+        colors,
+        [
+            [1.0, 0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0, 1.0]
+        ]
+    );
+}
+
+#[test]
+fn test_vintagestory_children_become_named_nodes() {
+    let vs_model = br#"{
+        "elements": [{
+            "name": "Body",
+            "from": [0, 0, 0],
+            "to": [8, 8, 8],
+            "faces": { "north": {}, "up": {} },
+            "children": [{
+                "name": "Head",
+                "from": [2, 8, 2],
+                "to": [6, 12, 6],
+                "faces": { "north": {} }
+            }]
+        }]
+    }"#;
+
+    let model = formats::vintagestory::VintageStoryLoader
+        .load_from_bytes(vs_model)
+        .expect("VS model should load");
+
+    let body = model.find_node("Body").expect("body node");
+    let head = model.find_node("Head").expect("head node");
+    assert_eq!(model.nodes[head].parent, Some(body));
+    assert_eq!(model.nodes[body].children, vec![head]);
+
+    let triangles = |meshes: Vec<usize>| -> usize {
+        meshes
+            .into_iter()
+            .map(|i| model.meshes[i].triangle_count())
+            .sum()
+    };
+    assert_eq!(triangles(model.nodes[head].meshes.clone().collect()), 2);
+    assert_eq!(triangles(model.node_meshes(body)), 6);
+}
+
+// ===========================================================================
+// OBJ parsing tests (synthetic data)
+// ===========================================================================
+
+#[test]
+fn test_obj_quads_share_vertices() {
+    let obj = b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 2 0 0\nv 2 1 0\nf 1 2 3 4\nf 2 5 6 3\n";
+
+    let model = formats::obj::ObjLoader
+        .load_from_bytes(obj)
+        .expect("OBJ should load");
+    assert_eq!(model.meshes.len(), 1);
+    assert_eq!(model.triangle_count(), 4);
+    // Six distinct positions, shared between both quads.
+    assert_eq!(model.meshes[0].positions.len(), 6);
+    assert_eq!(model.triangles().count(), 4);
+}
+
+#[test]
+fn test_obj_ear_clips_concave_faces() {
+    // An L-shaped face whose fan from vertex 1 would cover the missing corner
+    let obj = b"v 1 1 0\nv 1 2 0\nv 0 2 0\nv 0 0 0\nv 2 0 0\nv 2 1 0\nf 1 2 3 4 5 6\n";
+
+    let model = formats::obj::ObjLoader
+        .load_from_bytes(obj)
+        .expect("OBJ should load");
+    assert_eq!(model.triangle_count(), 4);
+    let area: f32 = model
+        .triangles()
+        .map(|t| {
+            let [a, b, c] = t.verts;
+            ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() / 2.0
+        })
+        .sum();
+    assert!((area - 3.0).abs() < 1e-6, "area {area}");
+}
+
+#[test]
+fn test_obj_reads_full_mtl_materials() {
+    let dir = std::env::temp_dir().join(format!("glimpse_mtl_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("wood textures")).unwrap();
+    let write_png = |name: &str, pixels: &[u8]| {
+        image::RgbaImage::from_raw(2, 1, pixels.to_vec())
+            .unwrap()
+            .save(dir.join(name))
+            .unwrap();
+    };
+    write_png(
+        "wood textures/oak grain.png",
+        &[200, 100, 50, 255, 200, 100, 50, 255],
+    );
+    write_png("mask.png", &[0, 0, 0, 255, 128, 128, 128, 255]);
+
+    std::fs::write(
+        dir.join("scene materials.mtl"),
+        "newmtl Wood\n\
+         Kd 1 1 1\n\
+         Ks 0.5 0.5 0.5\n\
+         Ns 900\n\
+         Pr 0.3\n\
+         Tr 0.25\n\
+         map_Kd -s 2 2 1 -o 0.5 0 -clamp on wood textures\\oak grain.png\n\
+         map_d mask.png\n\
+         newmtl Lamp\n\
+         Ke 4 3 2\n\
+         d 1\n\
+         Tr 0.5\n",
+    )
+    .unwrap();
+    let obj_path = dir.join("scene.obj");
+    std::fs::write(
+        &obj_path,
+        "mtllib scene materials.mtl\n\
+         v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+         vt 0 0\nvt 1 0\nvt 0 1\n\
+         usemtl Wood\nf 1/1 2/2 3/3\n\
+         usemtl Lamp\nf 1/1 3/3 2/2\n",
+    )
+    .unwrap();
+
+    let model = formats::obj::ObjLoader.load_from_path(&obj_path);
+    std::fs::remove_dir_all(&dir).unwrap();
+    let model = model.expect("OBJ should load");
+
+    let mesh = |name: &str| {
+        model
+            .meshes
+            .iter()
+            .find(|mesh| model.material(mesh.material).name.as_deref() == Some(name))
+            .expect("mesh for material")
+    };
+    let wood = model.material(mesh("Wood").material);
+    assert_eq!(wood.alpha, 0.75);
+    assert_eq!(wood.alpha_mode, formats::AlphaMode::Blend);
+    assert_eq!(wood.specular, [0.5; 3]);
+    assert!(wood.roughness < 0.1);
+    assert_eq!(wood.wrap, formats::WrapMode::Clamp);
+    // The diffuse map keeps its color and takes its alpha from the mask
+    let texture = wood.texture.as_ref().expect("diffuse map");
+    assert_eq!(&texture.data[..4], &[200, 100, 50, 0]);
+    assert_eq!(&texture.data[4..], &[200, 100, 50, 128]);
+    // UVs are scaled, then offset
+    assert_eq!(mesh("Wood").uvs, vec![[0.5, 0.0], [2.5, 0.0], [0.5, 2.0]]);
+
+    let lamp = model.material(mesh("Lamp").material);
+    assert_eq!(lamp.emissive, [4.0, 3.0, 2.0]);
+    assert_eq!(lamp.alpha, 1.0);
+    assert_eq!(lamp.wrap, formats::WrapMode::Repeat);
+    assert_eq!(mesh("Lamp").uvs[1], [0.0, 1.0]);
+}
+
+#[test]
+fn test_obj_bytes_read_materials_through_resolver() {
+    let obj = b"mtllib materials/scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl Glow\nf 1 2 3\n";
+    let mtl = b"newmtl Glow\nKd 0.2 0.4 0.6\nmap_Ke ..\\textures\\glow.png\n";
+    let mut png = Vec::new();
+    image::RgbaImage::from_pixel(1, 1, image::Rgba([0, 255, 0, 255]))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+
+    let resolver = MemoryResolver::new()
+        .with_file("materials/scene.mtl", mtl.to_vec())
+        .with_file("textures/glow.png", png);
+    let options = LoadOptions {
+        resolver: Some(Arc::new(resolver)),
+        ..Default::default()
+    };
+    let model =
+        formats::load_model_with_options(obj, Some("obj"), &options).expect("OBJ should load");
+    let material = &model.materials[model.meshes[0].material];
+    assert_eq!(material.color, [0.2, 0.4, 0.6]);
+    assert!(material.emissive_texture.is_some());
+
+    // Without a resolver the material library is skipped
+    let plain = formats::load_model(obj, Some("obj")).expect("OBJ should load");
+    assert_eq!(plain.materials[0].color, [0.85; 3]);
+}
+
+#[test]
+fn test_obj_reads_vertex_colors() {
+    // MeshLab-style colors, one with alpha, and one uncolored vertex
+    let obj = b"v 0 0 0 1 0 0\nv 1 0 0 0 1 0 0.5\nv 0 1 0\nf 1 2 3\n";
+
+    let model = formats::obj::ObjLoader
+        .load_from_bytes(obj)
+        .expect("OBJ should load");
+    let mesh = &model.meshes[0];
+    assert_eq!(mesh.positions[1], [1.0, 0.0, 0.0]);
+    let colors = mesh.colors.as_ref().expect("colors");
+    assert_eq!(colors[0], [1.0, 0.0, 0.0]);
+    assert_eq!(colors[1], [0.0, 1.0, 0.0]);
+    // The uncolored vertex keeps the default gray under a white material
+    assert_eq!(colors[2], [0.85; 3]);
+    assert_eq!(model.materials[mesh.material].color, [1.0; 3]);
+
+    let plain = formats::obj::ObjLoader
+        .load_from_bytes(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n")
+        .expect("OBJ should load");
+    assert!(plain.meshes[0].colors.is_none());
+}
+
+#[test]
+fn test_obj_carries_vertex_normals() {
+    let obj = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 2\nvn 0 1 1\nf 1//1 2//1 3//2\n";
+
+    let model = formats::obj::ObjLoader
+        .load_from_bytes(obj)
+        .expect("OBJ should load");
+    let normals = model.meshes[0].normals.as_ref().expect("normals");
+    let s = std::f32::consts::FRAC_1_SQRT_2;
+    assert_eq!(normals[0], [0.0, 0.0, 1.0]);
+    assert_eq!(normals[2], [0.0, s, s]);
+}
+
+#[test]
+fn test_obj_smooths_normals_per_smoothing_group() {
+    // Two quads folded along x = 1; the shared edge is smoothed only in group 1
+    let obj = b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 1 0 -1\nv 1 1 -1\n";
+    let load = |faces: &str| {
+        let data = [&obj[..], faces.as_bytes()].concat();
+        formats::obj::ObjLoader
+            .load_from_bytes(&data)
+            .expect("OBJ should load")
+    };
+
+    let smooth = load("s 1\nf 1 2 3 4\nf 2 5 6 3\n");
+    let mesh = &smooth.meshes[0];
+    let normals = mesh.normals.as_ref().expect("normals");
+    // Shared edge vertices stay shared and point halfway between both faces
+    assert_eq!(mesh.positions.len(), 6);
+    let edge = mesh
+        .positions
+        .iter()
+        .position(|p| *p == [1.0, 0.0, 0.0])
+        .unwrap();
+    let s = std::f32::consts::FRAC_1_SQRT_2;
+    for (got, want) in normals[edge].iter().zip([s, 0.0, s]) {
+        assert!((got - want).abs() < 1e-6);
+    }
+
+    // Different groups split the edge into flat-shaded halves
+    let split = load("s 1\nf 1 2 3 4\ns 2\nf 2 5 6 3\n");
+    assert_eq!(split.meshes[0].positions.len(), 8);
+
+    // Without normals or smoothing groups, meshes stay flat-shaded
+    assert!(load("f 1 2 3 4\nf 2 5 6 3\n").meshes[0].normals.is_none());
+}
+
 // ===========================================================================
 // Auto-detection tests
 // ===========================================================================
@@ -335,6 +1529,78 @@ fn test_render_bbmodel_cube() {
     );
 }
 
+#[test]
+fn test_render_report_for_textured_cube() {
+    let bbmodel = br#"{
+        "meta": {"format_version": "4.0"},
+        "resolution": {"width": 16, "height": 16},
+        "textures": [{
+            "name": "gray.png",
+            "source": "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAAEElEQVR4AQEFAPr/AP////8J+wP9o9FJCgAAAABJRU5ErkJggg==",
+            "width": 1,
+            "height": 1
+        }],
+        "elements": [{
+            "from": [0, 0, 0],
+            "to": [16, 16, 16],
+            "faces": {
+                "north": {"uv": [0, 0, 16, 16], "texture": 0},
+                "south": {"uv": [0, 0, 16, 16], "texture": 0},
+                "east": {"uv": [0, 0, 16, 16], "texture": 0},
+                "west": {"uv": [0, 0, 16, 16], "texture": 0},
+                "up": {"uv": [0, 0, 16, 16], "texture": 0},
+                "down": {"uv": [0, 0, 16, 16], "texture": 0}
+            }
+        }]
+    }"#;
+
+    let (pixels, report) = renderer::render_thumbnail_with_report(
+        bbmodel,
+        Some("bbmodel"),
+        64,
+        64,
+        &renderer::RenderOptions::default(),
+    )
+    .expect("cube should render");
+
+    assert_eq!(pixels.len(), 64 * 64 * 4);
+    assert_eq!(report.loader, "Blockbench");
+    assert_eq!(report.triangles, 12);
+    assert_eq!(report.rendered_triangles, 12);
+    assert_eq!(report.clipped_triangles, 0);
+    assert_eq!(report.degenerate_triangles, 0);
+    assert_eq!(report.textures, 1);
+    assert_eq!(report.texture_bytes, 4);
+    assert!(report.coverage > 0.1 && report.coverage < 1.0);
+    // Back faces are drawn first or overwritten, so some pixels see several fragments.
+    assert!(report.overdraw >= 1.0);
+}
+
+#[test]
+fn test_load_from_path_reports_loader_and_resolves_siblings() {
+    let dir = std::env::temp_dir().join(format!("glimpse_report_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("tri.mtl"), "newmtl Red\nKd 1 0 0\n").unwrap();
+    let obj_path = dir.join("tri.obj");
+    std::fs::write(
+        &obj_path,
+        "mtllib tri.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl Red\nf 1 2 3\n",
+    )
+    .unwrap();
+
+    let start = std::time::Instant::now();
+    let result = formats::load_model_from_path_with_report(&obj_path, &LoadOptions::default());
+    let elapsed = start.elapsed();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let (model, report) = result.expect("OBJ should load");
+
+    assert_eq!(report.loader, "Wavefront OBJ");
+    assert!(report.parse + report.convert <= elapsed);
+    let material = model.material(model.meshes[0].material);
+    assert_eq!(material.name.as_deref(), Some("Red"));
+    assert_eq!(material.color, [1.0, 0.0, 0.0]);
+}
+
 #[test]
 fn test_render_vintagestory_cube() {
     let vs_model = br#"{
@@ -368,6 +1634,161 @@ fn test_render_vintagestory_cube() {
     );
 }
 
+// ===========================================================================
+// Draco decoding tests (reference encoder fixtures)
+// ===========================================================================
+//
+// The fixtures in tests/fixtures/draco are tori with UVs and normals, encoded
+// by the reference `draco_encoder` with `-qp 11 -qt 10 -qn 8` and the
+// compression level in their name. The `.ply` files are what the reference
+// `draco_decoder` produces for them. Quantization does not depend on the
+// compression level, so every level decodes to the same triangles, in a
+// different order.
+
+/// One triangle corner: position, normal and UV.
+#[cfg(feature = "draco")]
+type DracoCorner = [f32; 8];
+
+/// Rotates each triangle to start at its smallest corner and sorts the
+/// triangles, so meshes decoded in different orders can be compared.
+#[cfg(feature = "draco")]
+fn canonical_triangles(mut triangles: Vec<[DracoCorner; 3]>) -> Vec<[DracoCorner; 3]> {
+    let key = |corner: &DracoCorner| corner.map(f32::to_bits);
+    for triangle in &mut triangles {
+        let first = (0..3).min_by_key(|&i| key(&triangle[i])).unwrap();
+        triangle.rotate_left(first);
+    }
+    triangles.sort_by_key(|triangle| triangle.map(|corner| key(&corner)));
+    triangles
+}
+
+/// Reads the binary PLY the reference decoder writes: positions and normals
+/// per vertex, and UVs per face corner.
+#[cfg(feature = "draco")]
+fn reference_triangles(ply: &[u8]) -> Vec<[DracoCorner; 3]> {
+    const END: &[u8] = b"end_header\n";
+    let split = ply.windows(END.len()).position(|w| w == END).unwrap() + END.len();
+    let header = std::str::from_utf8(&ply[..split]).unwrap();
+    let count = |element: &str| -> usize {
+        let line = header
+            .lines()
+            .find_map(|l| l.strip_prefix(element))
+            .unwrap();
+        line.trim().parse().unwrap()
+    };
+    let (vertex_count, face_count) = (count("element vertex "), count("element face "));
+
+    let mut body = &ply[split..];
+    let mut take = |len: usize| {
+        let (head, tail) = body.split_at(len);
+        body = tail;
+        head
+    };
+    let f32s = |bytes: &[u8]| -> Vec<f32> {
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect()
+    };
+    let vertices = f32s(take(vertex_count * 24));
+    (0..face_count)
+        .map(|_| {
+            assert_eq!(take(1), [3]);
+            let ids: Vec<usize> = take(12)
+                .chunks_exact(4)
+                .map(|b| i32::from_le_bytes(b.try_into().unwrap()) as usize)
+                .collect();
+            assert_eq!(take(1), [6]);
+            let uvs = f32s(take(24));
+            [0, 1, 2].map(|k| {
+                let mut corner = [0.0; 8];
+                corner[..6].copy_from_slice(&vertices[ids[k] * 6..ids[k] * 6 + 6]);
+                corner[6..].copy_from_slice(&uvs[k * 2..k * 2 + 2]);
+                corner
+            })
+        })
+        .collect()
+}
+
+/// Decodes a Draco fixture into triangles of position, normal and UV corners.
+#[cfg(feature = "draco")]
+fn decoded_triangles(data: &[u8]) -> Vec<[DracoCorner; 3]> {
+    use glimpse::formats::draco::{self, AttributeKind};
+
+    let mesh = draco::decode(data).expect("reference encoder output should decode");
+    let find = |kind: AttributeKind| {
+        mesh.attributes
+            .iter()
+            .find(|a| a.kind == kind)
+            .expect("fixture attribute")
+    };
+    let layout = [
+        (find(AttributeKind::Position), 0),
+        (find(AttributeKind::Normal), 3),
+        (find(AttributeKind::TexCoord), 6),
+    ];
+    mesh.faces
+        .iter()
+        .map(|face| {
+            face.map(|point| {
+                let mut corner = [0.0; 8];
+                for (attribute, offset) in layout {
+                    let n = attribute.components;
+                    let values = &attribute.values[point as usize * n..][..n];
+                    corner[offset..offset + n].copy_from_slice(values);
+                }
+                corner
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "draco")]
+fn assert_matches_reference(name: &str, data: &[u8], reference: &[u8]) {
+    let decoded = canonical_triangles(decoded_triangles(data));
+    let expected = canonical_triangles(reference_triangles(reference));
+    assert_eq!(decoded.len(), expected.len(), "{name}: triangle count");
+    for (decoded, expected) in decoded.iter().zip(&expected) {
+        for (a, b) in decoded.iter().flatten().zip(expected.iter().flatten()) {
+            assert!((a - b).abs() < 1e-6, "{name}: {decoded:?} != {expected:?}");
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "draco")]
+fn test_draco_matches_reference_decoder() {
+    let reference = include_bytes!("fixtures/draco/torus_reference.ply");
+    let fixtures: [(&str, &[u8]); 5] = [
+        // Sequential connectivity, delta coding
+        ("cl0", include_bytes!("fixtures/draco/torus_cl0.drc")),
+        // Standard edgebreaker, delta coding
+        ("cl2", include_bytes!("fixtures/draco/torus_cl2.drc")),
+        // Standard edgebreaker, parallelogram positions and UVs
+        ("cl5", include_bytes!("fixtures/draco/torus_cl5.drc")),
+        // Valence edgebreaker, parallelogram positions, portable UV and
+        // octahedral geometric normal prediction
+        ("cl7", include_bytes!("fixtures/draco/torus_cl7.drc")),
+        // Valence edgebreaker, constrained multi-parallelogram positions
+        ("cl10", include_bytes!("fixtures/draco/torus_cl10.drc")),
+    ];
+    for (name, data) in fixtures {
+        assert_matches_reference(name, data, reference);
+    }
+}
+
+#[test]
+#[cfg(feature = "draco")]
+fn test_draco_small_mesh_matches_reference_decoder() {
+    // Meshes under 1,000 faces keep the standard edgebreaker at every level,
+    // here with constrained multi-parallelogram positions
+    assert_matches_reference(
+        "small cl10",
+        include_bytes!("fixtures/draco/small_torus_cl10.drc"),
+        include_bytes!("fixtures/draco/small_torus_reference.ply"),
+    );
+}
+
 // ===========================================================================
 // Real file tests — ignored by default, provide your own models to run
 // ===========================================================================
@@ -392,14 +1813,10 @@ fn test_real_bbmodel() {
     );
 
     let model = result.unwrap();
-    assert!(!model.triangles.is_empty(), "Should have triangles");
+    assert!(!model.is_empty(), "Should have triangles");
 
     // Verify textures loaded
-    let textured = model
-        .triangles
-        .iter()
-        .filter(|t| t.texture.is_some())
-        .count();
+    let textured = model.triangles().filter(|t| t.texture.is_some()).count();
     assert!(textured > 0, "Should have textured triangles");
 
     // Render via path and bytes
@@ -441,7 +1858,7 @@ fn test_real_vs_json() {
     );
 
     let model = result.unwrap();
-    assert!(!model.triangles.is_empty(), "Should have triangles");
+    assert!(!model.is_empty(), "Should have triangles");
 
     let pixels = renderer::render_thumbnail(&data, Some("json"), 256, 256);
     assert!(pixels.is_some(), "render failed for test.json");
@@ -478,7 +1895,7 @@ fn test_real_gltf() {
     );
 
     let model = result.unwrap();
-    assert!(!model.triangles.is_empty(), "Should have triangles");
+    assert!(!model.is_empty(), "Should have triangles");
 
     let pixels = renderer::render_thumbnail_from_path(path, 256, 256);
     assert!(pixels.is_some(), "render failed for test.gltf");
@@ -37,6 +37,12 @@ fn test_provider_set_data() {
     let _ = provider.set_data(vec![1, 2, 3, 4, 5]);
     println!("  [OK] Sample data set successfully");
 
+    // Test with the item name a stream would report
+    assert!(provider
+        .set_data_with_name(vec![1, 2, 3, 4, 5], "model.gltf")
+        .is_ok());
+    println!("  [OK] Named data set successfully");
+
     // Test with actual file data if available
     if let Some(test_file) = find_test_file() {
         match fs::read(&test_file) {